@@ -4,6 +4,21 @@ pub mod enum_type;
 
 pub mod m20251126_142840_create_user_table;
 pub mod m20251126_142841_create_address_table;
+pub mod m20251201_000001_add_email_verified_at_to_users;
+pub mod m20251202_000001_add_two_factor_to_users;
+pub mod m20251203_000001_case_insensitive_user_identity;
+pub mod m20251204_000001_create_oauth_client_table;
+pub mod m20251205_000001_add_oidc_to_oauth_clients;
+pub mod m20251206_000001_create_user_identity_table;
+pub mod m20251207_000001_create_api_key_table;
+pub mod m20251208_000001_create_password_history_table;
+pub mod m20251209_000001_create_security_event_table;
+pub mod m20251210_000001_create_audit_log_table;
+pub mod m20251211_000001_create_role_permission_tables;
+pub mod m20251212_000001_add_gender_and_language_to_users;
+pub mod m20251213_000001_create_user_status_change_table;
+pub mod m20251214_000001_add_tenant_to_users_and_addresses;
+pub mod m20251215_000001_add_tenant_to_identity_and_oauth_tables;
 
 pub struct Migrator;
 
@@ -13,6 +28,21 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20251126_142840_create_user_table::Migration),
             Box::new(m20251126_142841_create_address_table::Migration),
+            Box::new(m20251201_000001_add_email_verified_at_to_users::Migration),
+            Box::new(m20251202_000001_add_two_factor_to_users::Migration),
+            Box::new(m20251203_000001_case_insensitive_user_identity::Migration),
+            Box::new(m20251204_000001_create_oauth_client_table::Migration),
+            Box::new(m20251205_000001_add_oidc_to_oauth_clients::Migration),
+            Box::new(m20251206_000001_create_user_identity_table::Migration),
+            Box::new(m20251207_000001_create_api_key_table::Migration),
+            Box::new(m20251208_000001_create_password_history_table::Migration),
+            Box::new(m20251209_000001_create_security_event_table::Migration),
+            Box::new(m20251210_000001_create_audit_log_table::Migration),
+            Box::new(m20251211_000001_create_role_permission_tables::Migration),
+            Box::new(m20251212_000001_add_gender_and_language_to_users::Migration),
+            Box::new(m20251213_000001_create_user_status_change_table::Migration),
+            Box::new(m20251214_000001_add_tenant_to_users_and_addresses::Migration),
+            Box::new(m20251215_000001_add_tenant_to_identity_and_oauth_tables::Migration),
         ]
     }
 }
@@ -52,4 +52,12 @@ pub enum Users {
     IsDeleted,
     CreatedAt,
     DeletedAt,
+    EmailVerifiedAt,
+    TotpSecret,
+    TotpEnabledAt,
+    IsServiceAccount,
+    Gender,
+    Language,
+    SuspendedUntil,
+    TenantId,
 }
@@ -70,4 +70,5 @@ pub enum Addresses {
     PhoneNumber,
     CreatedAt,
     DeletedAt,
+    TenantId,
 }
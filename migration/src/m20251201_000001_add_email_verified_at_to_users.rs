@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(timestamp_null(Users::EmailVerifiedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Accounts created before verification existed are treated as verified
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Users::Table)
+                    .value(Users::EmailVerifiedAt, Expr::current_timestamp())
+                    .and_where(Expr::col(Users::EmailVerifiedAt).is_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::EmailVerifiedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
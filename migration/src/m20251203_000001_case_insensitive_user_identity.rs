@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Replaces the case-sensitive unique constraints on `users.username` / `users.email`
+/// with unique indexes on their lowercased values, after normalizing stored emails.
+/// Rows that only differ by case must be merged by hand before running it.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE users SET email = LOWER(TRIM(email)), username = TRIM(username)")
+            .await?;
+        db.execute_unprepared("ALTER TABLE users DROP CONSTRAINT IF EXISTS users_username_key")
+            .await?;
+        db.execute_unprepared("ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key")
+            .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_lower ON users (LOWER(username))",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_lower ON users (LOWER(email))",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_username_lower").await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_email_lower").await?;
+        db.execute_unprepared("ALTER TABLE users ADD CONSTRAINT users_username_key UNIQUE (username)")
+            .await?;
+        db.execute_unprepared("ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email)")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthClients::Table)
+                    .if_not_exists()
+                    .col(pk_auto(OauthClients::Id))
+                    .col(string_uniq(OauthClients::ClientId))
+                    .col(string(OauthClients::ClientSecretHash))
+                    .col(string(OauthClients::Name))
+                    .col(boolean(OauthClients::IsActive).default(true))
+                    .col(timestamp_null(OauthClients::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OauthClients::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum OauthClients {
+    Table,
+    Id,
+    ClientId,
+    ClientSecretHash,
+    Name,
+    IsActive,
+    CreatedAt,
+    RedirectUris,
+    AllowedScopes,
+    IsPublic,
+    TenantId,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+use super::m20251204_000001_create_oauth_client_table::OauthClients;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthClients::Table)
+                    .add_column_if_not_exists(text(OauthClients::RedirectUris).default(""))
+                    .add_column_if_not_exists(string(OauthClients::AllowedScopes).default("openid"))
+                    .add_column_if_not_exists(boolean(OauthClients::IsPublic).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthConsents::Table)
+                    .if_not_exists()
+                    .col(pk_auto(OauthConsents::Id))
+                    .col(integer(OauthConsents::UserId))
+                    .col(string(OauthConsents::ClientId))
+                    .col(string(OauthConsents::Scopes))
+                    .col(timestamp_null(OauthConsents::CreatedAt))
+                    .col(timestamp_null(OauthConsents::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_consents_user_id")
+                            .from(OauthConsents::Table, OauthConsents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_consents_client_id")
+                            .from(OauthConsents::Table, OauthConsents::ClientId)
+                            .to(OauthClients::Table, OauthClients::ClientId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One consent per user and client, updated in place when more scopes are granted
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_oauth_consents_user_id_client_id")
+                    .table(OauthConsents::Table)
+                    .col(OauthConsents::UserId)
+                    .col(OauthConsents::ClientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OauthConsents::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthClients::Table)
+                    .drop_column(OauthClients::RedirectUris)
+                    .drop_column(OauthClients::AllowedScopes)
+                    .drop_column(OauthClients::IsPublic)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum OauthConsents {
+    Table,
+    Id,
+    UserId,
+    ClientId,
+    Scopes,
+    CreatedAt,
+    UpdatedAt,
+    TenantId,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserPasswordHistory::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserPasswordHistory::Id))
+                    .col(integer(UserPasswordHistory::UserId))
+                    .col(string(UserPasswordHistory::PasswordHash))
+                    .col(timestamp_null(UserPasswordHistory::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_password_history_user_id")
+                            .from(UserPasswordHistory::Table, UserPasswordHistory::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_password_history_user_id")
+                    .table(UserPasswordHistory::Table)
+                    .col(UserPasswordHistory::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserPasswordHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum UserPasswordHistory {
+    Table,
+    Id,
+    UserId,
+    PasswordHash,
+    CreatedAt,
+}
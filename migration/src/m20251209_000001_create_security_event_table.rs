@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSecurityEvents::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserSecurityEvents::Id))
+                    .col(integer(UserSecurityEvents::UserId))
+                    .col(string_len(UserSecurityEvents::Kind, 40))
+                    .col(string_null(UserSecurityEvents::IpAddress))
+                    .col(string_null(UserSecurityEvents::UserAgent))
+                    .col(string_null(UserSecurityEvents::Detail))
+                    .col(timestamp_null(UserSecurityEvents::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_security_events_user_id")
+                            .from(UserSecurityEvents::Table, UserSecurityEvents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_security_events_user_id")
+                    .table(UserSecurityEvents::Table)
+                    .col(UserSecurityEvents::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserSecurityEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum UserSecurityEvents {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    IpAddress,
+    UserAgent,
+    Detail,
+    CreatedAt,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLogs::Table)
+                    .if_not_exists()
+                    .col(pk_auto(AuditLogs::Id))
+                    .col(integer(AuditLogs::ActorId))
+                    .col(integer(AuditLogs::UserId))
+                    .col(string_len(AuditLogs::Action, 40))
+                    .col(string_len_null(AuditLogs::Method, 10))
+                    .col(string_null(AuditLogs::Path))
+                    .col(string_null(AuditLogs::IpAddress))
+                    .col(string_null(AuditLogs::UserAgent))
+                    .col(string_null(AuditLogs::Detail))
+                    .col(timestamp_null(AuditLogs::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_audit_logs_user_id")
+                            .from(AuditLogs::Table, AuditLogs::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_user_id")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_actor_id")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::ActorId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLogs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum AuditLogs {
+    Table,
+    Id,
+    ActorId,
+    UserId,
+    Action,
+    Method,
+    Path,
+    IpAddress,
+    UserAgent,
+    Detail,
+    CreatedAt,
+}
@@ -0,0 +1,186 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Built-in roles and permissions. Permission names must match `domain::user::permission`.
+const SEED_ROLES_AND_PERMISSIONS: &str = r#"
+INSERT INTO roles (name, description, created_at) VALUES
+    ('admin', 'Full access to every admin endpoint', NOW()),
+    ('support', 'Looks up customers and signs in as them to help', NOW())
+ON CONFLICT DO NOTHING;
+
+INSERT INTO permissions (name, description) VALUES
+    ('users:read', 'View any user'),
+    ('users:write', 'Create and edit any user'),
+    ('users:delete', 'Delete any user'),
+    ('users:security', 'Reset passwords and second factors of any user'),
+    ('users:impersonate', 'Sign in as a user for support'),
+    ('oauth_clients:write', 'Register OAuth clients'),
+    ('service_accounts:write', 'Manage service accounts and their API keys')
+ON CONFLICT DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT roles.id, permissions.id FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin'
+   OR (roles.name = 'support' AND permissions.name IN ('users:read', 'users:impersonate'))
+ON CONFLICT DO NOTHING;
+"#;
+
+/// First administrator. It has no password: request a reset for its email to sign in.
+const SEED_ADMIN: &str = r#"
+INSERT INTO users (first_name, last_name, username, email, status, is_deleted, email_verified_at, created_at)
+VALUES ('System', 'Administrator', 'admin', 'admin@june18.local', 'active', false, NOW(), NOW())
+ON CONFLICT DO NOTHING;
+
+INSERT INTO user_roles (user_id, role_id, created_at)
+SELECT users.id, roles.id, NOW() FROM users CROSS JOIN roles
+WHERE LOWER(users.username) = 'admin' AND roles.name = 'admin'
+ON CONFLICT DO NOTHING;
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Roles::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Roles::Id))
+                    .col(string_len_uniq(Roles::Name, 50))
+                    .col(string_null(Roles::Description))
+                    .col(timestamp_null(Roles::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Permissions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Permissions::Id))
+                    .col(string_len_uniq(Permissions::Name, 100))
+                    .col(string_null(Permissions::Description))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RolePermissions::Table)
+                    .if_not_exists()
+                    .col(integer(RolePermissions::RoleId))
+                    .col(integer(RolePermissions::PermissionId))
+                    .primary_key(
+                        Index::create()
+                            .col(RolePermissions::RoleId)
+                            .col(RolePermissions::PermissionId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permissions_role_id")
+                            .from(RolePermissions::Table, RolePermissions::RoleId)
+                            .to(Roles::Table, Roles::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permissions_permission_id")
+                            .from(RolePermissions::Table, RolePermissions::PermissionId)
+                            .to(Permissions::Table, Permissions::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRoles::Table)
+                    .if_not_exists()
+                    .col(integer(UserRoles::UserId))
+                    .col(integer(UserRoles::RoleId))
+                    .col(timestamp_null(UserRoles::CreatedAt))
+                    .primary_key(Index::create().col(UserRoles::UserId).col(UserRoles::RoleId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_roles_user_id")
+                            .from(UserRoles::Table, UserRoles::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_roles_role_id")
+                            .from(UserRoles::Table, UserRoles::RoleId)
+                            .to(Roles::Table, Roles::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared(SEED_ROLES_AND_PERMISSIONS).await?;
+        db.execute_unprepared(SEED_ADMIN).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The seeded admin stays, it is an ordinary user without the tables
+        manager
+            .drop_table(Table::drop().table(UserRoles::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RolePermissions::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Permissions::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Roles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Roles {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    TenantId,
+}
+
+#[derive(DeriveIden)]
+pub enum Permissions {
+    Table,
+    Id,
+    Name,
+    Description,
+}
+
+#[derive(DeriveIden)]
+pub enum RolePermissions {
+    Table,
+    RoleId,
+    PermissionId,
+}
+
+#[derive(DeriveIden)]
+pub enum UserRoles {
+    Table,
+    UserId,
+    RoleId,
+    CreatedAt,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(timestamp_null(Users::SuspendedUntil))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserStatusChanges::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserStatusChanges::Id))
+                    .col(integer(UserStatusChanges::UserId))
+                    .col(string_len(UserStatusChanges::FromStatus, 10))
+                    .col(string_len(UserStatusChanges::ToStatus, 10))
+                    .col(integer_null(UserStatusChanges::ActorId))
+                    .col(string_null(UserStatusChanges::Reason))
+                    .col(timestamp_null(UserStatusChanges::SuspendedUntil))
+                    .col(timestamp_null(UserStatusChanges::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_status_changes_user_id")
+                            .from(UserStatusChanges::Table, UserStatusChanges::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_status_changes_user_id")
+                    .table(UserStatusChanges::Table)
+                    .col(UserStatusChanges::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserStatusChanges::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::SuspendedUntil)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum UserStatusChanges {
+    Table,
+    Id,
+    UserId,
+    FromStatus,
+    ToStatus,
+    ActorId,
+    Reason,
+    SuspendedUntil,
+    CreatedAt,
+}
@@ -0,0 +1,99 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251126_142840_create_user_table::Users;
+use super::m20251126_142841_create_address_table::Addresses;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Puts every user and address into a tenant, the existing ones into `default`, and makes
+/// usernames and emails unique per tenant instead of globally.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(string_len(Users::TenantId, 63).default("default"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Addresses::Table)
+                    .add_column_if_not_exists(
+                        string_len(Addresses::TenantId, 63).default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_addresses_tenant_id_user_id")
+                    .table(Addresses::Table)
+                    .col(Addresses::TenantId)
+                    .col(Addresses::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_username_lower").await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_email_lower").await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_tenant_username_lower ON users (tenant_id, LOWER(username))",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_tenant_email_lower ON users (tenant_id, LOWER(email))",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Fails if two tenants hold the same username or email.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_tenant_username_lower").await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_users_tenant_email_lower").await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_lower ON users (LOWER(username))",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_lower ON users (LOWER(email))",
+        )
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_addresses_tenant_id_user_id")
+                    .table(Addresses::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Addresses::Table)
+                    .drop_column(Addresses::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
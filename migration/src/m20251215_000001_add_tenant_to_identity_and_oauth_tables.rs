@@ -0,0 +1,155 @@
+use sea_orm_migration::{prelude::*, schema::*};
+use super::m20251204_000001_create_oauth_client_table::OauthClients;
+use super::m20251205_000001_add_oidc_to_oauth_clients::OauthConsents;
+use super::m20251206_000001_create_user_identity_table::UserIdentities;
+use super::m20251207_000001_create_api_key_table::ApiKeys;
+use super::m20251211_000001_create_role_permission_tables::Roles;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Unique keys that become per tenant. Consents reference their client through the
+/// tenant as well, so the foreign key moves to `(tenant_id, client_id)`.
+const SCOPE_UNIQUE_KEYS: &str = r#"
+DROP INDEX IF EXISTS idx_user_identities_provider_subject;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_identities_tenant_provider_subject
+    ON user_identities (tenant_id, provider, subject);
+
+DROP INDEX IF EXISTS idx_api_keys_key_hash;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_tenant_key_hash ON api_keys (tenant_id, key_hash);
+
+ALTER TABLE oauth_consents DROP CONSTRAINT IF EXISTS fk_oauth_consents_client_id;
+ALTER TABLE oauth_clients DROP CONSTRAINT IF EXISTS oauth_clients_client_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_oauth_clients_tenant_client_id ON oauth_clients (tenant_id, client_id);
+DROP INDEX IF EXISTS idx_oauth_consents_user_id_client_id;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_oauth_consents_tenant_user_id_client_id
+    ON oauth_consents (tenant_id, user_id, client_id);
+ALTER TABLE oauth_consents ADD CONSTRAINT fk_oauth_consents_client_id
+    FOREIGN KEY (tenant_id, client_id) REFERENCES oauth_clients (tenant_id, client_id)
+    ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE roles DROP CONSTRAINT IF EXISTS roles_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_roles_tenant_name ON roles (COALESCE(tenant_id, ''), name);
+"#;
+
+/// Fails if two tenants hold the same client id, provider account or role name.
+const GLOBAL_UNIQUE_KEYS: &str = r#"
+DROP INDEX IF EXISTS idx_roles_tenant_name;
+ALTER TABLE roles ADD CONSTRAINT roles_name_key UNIQUE (name);
+
+ALTER TABLE oauth_consents DROP CONSTRAINT IF EXISTS fk_oauth_consents_client_id;
+DROP INDEX IF EXISTS idx_oauth_consents_tenant_user_id_client_id;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_oauth_consents_user_id_client_id ON oauth_consents (user_id, client_id);
+DROP INDEX IF EXISTS idx_oauth_clients_tenant_client_id;
+ALTER TABLE oauth_clients ADD CONSTRAINT oauth_clients_client_id_key UNIQUE (client_id);
+ALTER TABLE oauth_consents ADD CONSTRAINT fk_oauth_consents_client_id
+    FOREIGN KEY (client_id) REFERENCES oauth_clients (client_id)
+    ON DELETE CASCADE ON UPDATE CASCADE;
+
+DROP INDEX IF EXISTS idx_api_keys_tenant_key_hash;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_key_hash ON api_keys (key_hash);
+
+DROP INDEX IF EXISTS idx_user_identities_tenant_provider_subject;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_identities_provider_subject ON user_identities (provider, subject);
+"#;
+
+/// Puts identities, API keys, OAuth clients and consents into the tenant of the `users`
+/// rows they hang off, existing rows into `default`. Roles get an optional tenant: the
+/// seeded roles keep none and are shared by every tenant.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserIdentities::Table)
+                    .add_column_if_not_exists(
+                        string_len(UserIdentities::TenantId, 63).default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKeys::Table)
+                    .add_column_if_not_exists(string_len(ApiKeys::TenantId, 63).default("default"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthClients::Table)
+                    .add_column_if_not_exists(
+                        string_len(OauthClients::TenantId, 63).default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthConsents::Table)
+                    .add_column_if_not_exists(
+                        string_len(OauthConsents::TenantId, 63).default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Roles::Table)
+                    .add_column_if_not_exists(string_len_null(Roles::TenantId, 63))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager.get_connection().execute_unprepared(SCOPE_UNIQUE_KEYS).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(GLOBAL_UNIQUE_KEYS).await?;
+
+        manager
+            .alter_table(Table::alter().table(Roles::Table).drop_column(Roles::TenantId).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthConsents::Table)
+                    .drop_column(OauthConsents::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthClients::Table)
+                    .drop_column(OauthClients::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(Table::alter().table(ApiKeys::Table).drop_column(ApiKeys::TenantId).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserIdentities::Table)
+                    .drop_column(UserIdentities::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -24,19 +24,20 @@ pub struct UserIdQuery {
         (status = 201, description = "Address created successfully", body = EntityResponse<bool>),
         (status = 400, description = "Bad request", body = ClientResponseError),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Address for another user without `users:write`", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
     security(("jwt" = []))
 )]
 pub async fn controller_create_address(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Json(request): Json<CreateAddressRequest>,
 ) -> AppResult<Json<EntityResponse<bool>>> {
-    log::info!("Creating address for user_id: {}", request.user_id);
+    log::info!("Creating address for user_id: {:?}", request.user_id);
     let tx = state.db.begin().await?;
 
-    match state.address_service.create_address(&tx, request).await {
+    match state.address_service.create_address(&tx, &claims, request).await {
         Ok(result) => {
             tx.commit().await?;
             Ok(Json(EntityResponse {
@@ -65,6 +66,7 @@ pub async fn controller_create_address(
         (status = 200, description = "Address updated successfully", body = EntityResponse<bool>),
         (status = 400, description = "Bad request", body = ClientResponseError),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not your address and no `users:write` permission", body = ClientResponseError),
         (status = 404, description = "Address not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -72,14 +74,14 @@ pub async fn controller_create_address(
 )]
 pub async fn controller_update_address(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Path(id): Path<i64>,
     Json(request): Json<UpdateAddressRequest>,
 ) -> AppResult<Json<EntityResponse<bool>>> {
     log::info!("Updating address with id: {}", id);
     let tx = state.db.begin().await?;
 
-    match state.address_service.update_address(&tx, id, request).await {
+    match state.address_service.update_address(&tx, &claims, id, request).await {
         Ok(result) => {
             tx.commit().await?;
             Ok(Json(EntityResponse {
@@ -106,6 +108,7 @@ pub async fn controller_update_address(
     responses(
         (status = 200, description = "Address retrieved successfully", body = EntityResponse<AddressSerializer>),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not your address and no `users:read` permission", body = ClientResponseError),
         (status = 404, description = "Address not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -113,13 +116,13 @@ pub async fn controller_update_address(
 )]
 pub async fn controller_get_address_by_id(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Path(id): Path<i64>,
 ) -> AppResult<Json<EntityResponse<AddressSerializer>>> {
     log::info!("Getting address with id: {}", id);
     let tx = state.db.begin().await?;
 
-    match state.address_service.get_address_by_id(&tx, id).await {
+    match state.address_service.get_address_by_id(&tx, &claims, id).await {
         Ok(result) => Ok(Json(EntityResponse {
             message: "Address retrieved successfully.".to_string(),
             data: Some(result),
@@ -142,19 +145,20 @@ pub async fn controller_get_address_by_id(
     responses(
         (status = 200, description = "Addresses retrieved successfully", body = EntityResponse<Vec<AddressSerializer>>),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Another user's addresses without `users:read`", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
     security(("jwt" = []))
 )]
 pub async fn controller_get_addresses_by_user_id(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Query(params): Query<UserIdQuery>,
 ) -> AppResult<Json<EntityResponse<Vec<AddressSerializer>>>> {
     log::info!("Getting addresses for user_id: {}", params.user_id);
     let tx = state.db.begin().await?;
 
-    match state.address_service.get_addresses_by_user_id(&tx, params.user_id).await {
+    match state.address_service.get_addresses_by_user_id(&tx, &claims, params.user_id).await {
         Ok(result) => {
             let total = result.len();
             Ok(Json(EntityResponse {
@@ -180,6 +184,7 @@ pub async fn controller_get_addresses_by_user_id(
     responses(
         (status = 200, description = "Address deleted successfully", body = EntityResponse<String>),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not your address and no `users:write` permission", body = ClientResponseError),
         (status = 404, description = "Address not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -187,13 +192,13 @@ pub async fn controller_get_addresses_by_user_id(
 )]
 pub async fn controller_delete_address(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Path(id): Path<i64>,
 ) -> AppResult<Json<EntityResponse<String>>> {
     log::info!("Deleting address with id: {}", id);
     let tx = state.db.begin().await?;
 
-    match state.address_service.delete_address(&tx, id).await {
+    match state.address_service.delete_address(&tx, &claims, id).await {
         Ok(_) => {
             tx.commit().await?;
             Ok(Json(EntityResponse {
@@ -209,3 +214,77 @@ pub async fn controller_delete_address(
         }
     }
 }
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/addresses",
+    tags = ["address_service"],
+    responses(
+        (status = 200, description = "Addresses of the current user", body = EntityResponse<Vec<AddressSerializer>>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_list_my_addresses(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<Vec<AddressSerializer>>>> {
+    log::info!("Listing addresses of user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.address_service.get_addresses_by_user_id(&tx, &claims, claims.user_id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Addresses retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        }
+        Err(err) => {
+            log::error!("Failed to list addresses: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+/// Adds an address to the current user. A `user_id` in the body is ignored.
+#[utoipa::path(
+    post,
+    path = "/v1/me/addresses",
+    tags = ["address_service"],
+    request_body = CreateAddressRequest,
+    responses(
+        (status = 201, description = "Address created successfully", body = EntityResponse<bool>),
+        (status = 400, description = "Bad request", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_create_my_address(
+    State(state): State<AppState>,
+    claims: UserClaims,
+    Json(request): Json<CreateAddressRequest>,
+) -> AppResult<Json<EntityResponse<bool>>> {
+    log::info!("Creating address for user id: {}", claims.user_id);
+    let request = CreateAddressRequest { user_id: Some(claims.user_id), ..request };
+    let tx = state.db.begin().await?;
+
+    match state.address_service.create_address(&tx, &claims, request).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Address created successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            log::error!("Failed to create address: {err:?}");
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::application::impersonation::impersonation_command::StartImpersonationCommand;
+use crate::application::impersonation::impersonation_service_interface::ImpersonationServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, MessageResponse};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::permission::{RequirePermission, UsersImpersonate};
+use crate::presentation::impersonation::impersonation::ImpersonationResponse;
+use crate::util::claim::UserClaims;
+use axum::extract::{Path, State};
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+/// Signs the admin in as the user for support. The token expires after a few minutes,
+/// cannot be refreshed and cannot change passwords, second factors or keys. Every
+/// request made with it is audited.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/impersonate",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = StartImpersonationCommand,
+    responses(
+        (status = 200, description = "Impersonation token issued", body = ImpersonationResponse),
+        (status = 400, description = "Invalid data input or user not found", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:impersonate` required or the user is an admin", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:impersonate"]))
+)]
+pub async fn controller_admin_start_impersonation(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersImpersonate>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(cmd): Json<StartImpersonationCommand>,
+) -> AppResult<Json<ImpersonationResponse>> {
+    log::info!("Admin {} starts impersonating user id: {}", claims.user_id, id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.impersonation_service.start(&tx, &claims, id, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(result))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to start impersonation: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Called with the impersonation token itself; it stops working right away.
+#[utoipa::path(
+    post,
+    path = "/v1/impersonation/stop",
+    tags = ["admin_service"],
+    responses(
+        (status = 200, description = "Impersonation ended", body = MessageResponse),
+        (status = 400, description = "Not an impersonation token", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_stop_impersonation(
+    State(state): State<AppState>,
+    claims: UserClaims,
+    client: ClientInfo,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Stop impersonation of user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.impersonation_service.stop(&tx, &claims, &client).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Impersonation ended.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to stop impersonation: {err:?}");
+            Err(err)
+        },
+    }
+}
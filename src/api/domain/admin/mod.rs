@@ -1 +1,4 @@
+pub mod impersonation;
+pub mod oauth_client;
+pub mod service_account;
 pub mod user;
@@ -0,0 +1,54 @@
+use crate::application::oauth::oauth_command::CreateOauthClientCommand;
+use crate::application::oauth::oauth_service_interface::OauthServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse};
+use crate::infrastructure::middleware::permission::{OauthClientsWrite, RequirePermission};
+use crate::presentation::oauth::oauth::OauthClientCreatedResponse;
+use axum::extract::State;
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/oauth_clients",
+    tags = ["admin_service"],
+    request_body = CreateOauthClientCommand,
+    responses(
+        (status = 200, description = "Client created, the secret is shown only once", body = EntityResponse<OauthClientCreatedResponse>),
+        (status = 400, description = "Bad request", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `oauth_clients:write` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["oauth_clients:write"]))
+)]
+pub async fn controller_admin_create_oauth_client(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<OauthClientsWrite>,
+    Json(cmd): Json<CreateOauthClientCommand>,
+) -> AppResult<Json<EntityResponse<OauthClientCreatedResponse>>> {
+    log::info!("Admin {} creates oauth client {}", claims.user_id, cmd.name);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.oauth_service.create_client(&tx, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "OAuth client created. Store the secret now, it cannot be shown again.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to create oauth client: {err:?}");
+            Err(err)
+        },
+    }
+}
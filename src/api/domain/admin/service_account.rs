@@ -0,0 +1,193 @@
+use crate::application::api_key::api_key_command::{CreateApiKeyCommand, CreateServiceAccountCommand};
+use crate::application::api_key::api_key_service_interface::ApiKeyServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::permission::{RequirePermission, ServiceAccountsWrite};
+use crate::presentation::api_key::api_key::{
+    ApiKeyCreatedResponse, ApiKeySerializer, ServiceAccountSerializer,
+};
+use axum::extract::{Path, State};
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/service_accounts",
+    tags = ["admin_service"],
+    request_body = CreateServiceAccountCommand,
+    responses(
+        (status = 200, description = "Service account created", body = EntityResponse<ServiceAccountSerializer>),
+        (status = 400, description = "Bad request", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `service_accounts:write` required", body = ClientResponseError),
+        (status = 409, description = "Username already exists", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["service_accounts:write"]))
+)]
+pub async fn controller_admin_create_service_account(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<ServiceAccountsWrite>,
+    Json(cmd): Json<CreateServiceAccountCommand>,
+) -> AppResult<Json<EntityResponse<ServiceAccountSerializer>>> {
+    log::info!("Admin {} creates service account {}", claims.user_id, cmd.username);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.api_key_service.create_service_account(&tx, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Service account created successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to create service account: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/service_accounts/{id}/api_keys",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "Service account ID")
+    ),
+    request_body = CreateApiKeyCommand,
+    responses(
+        (status = 200, description = "Key created, it is shown only once", body = EntityResponse<ApiKeyCreatedResponse>),
+        (status = 400, description = "Invalid data input or unknown service account", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `service_accounts:write` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["service_accounts:write"]))
+)]
+pub async fn controller_admin_create_service_account_key(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<ServiceAccountsWrite>,
+    Path(id): Path<i64>,
+    Json(cmd): Json<CreateApiKeyCommand>,
+) -> AppResult<Json<EntityResponse<ApiKeyCreatedResponse>>> {
+    log::info!("Admin {} creates API key {} for service account {id}", claims.user_id, cmd.name);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    let result = match state.api_key_service.find_service_account(&tx, id).await {
+        Ok(account) => state.api_key_service.create_key(&tx, &claims, account.id, &cmd).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "API key created. Store it now, it cannot be shown again.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to create API key for service account {id}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/service_accounts/{id}/api_keys",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "Service account ID")
+    ),
+    responses(
+        (status = 200, description = "API keys of the service account", body = EntityResponse<Vec<ApiKeySerializer>>),
+        (status = 400, description = "Unknown service account", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `service_accounts:write` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["service_accounts:write"]))
+)]
+pub async fn controller_admin_list_service_account_keys(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<ServiceAccountsWrite>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EntityResponse<Vec<ApiKeySerializer>>>> {
+    log::info!("Admin {} lists API keys of service account {id}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    let result = match state.api_key_service.find_service_account(&tx, id).await {
+        Ok(account) => state.api_key_service.list_keys(&tx, account.id).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "API keys retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list API keys of service account {id}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/service_accounts/{id}/api_keys/{key_id}",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "Service account ID"),
+        ("key_id" = i64, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked", body = MessageResponse),
+        (status = 400, description = "Unknown service account or no active key with this ID", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `service_accounts:write` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["service_accounts:write"]))
+)]
+pub async fn controller_admin_revoke_service_account_key(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<ServiceAccountsWrite>,
+    Path((id, key_id)): Path<(i64, i64)>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Admin {} revokes API key {key_id} of service account {id}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    let result = match state.api_key_service.find_service_account(&tx, id).await {
+        Ok(account) => state.api_key_service.revoke_key(&tx, account.id, key_id).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("API key revoked successfully.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to revoke API key {key_id}: {err:?}");
+            Err(err)
+        },
+    }
+}
@@ -1 +1,509 @@
+use crate::application::admin::admin_command::{
+    AdminUserListQuery, ChangeUserStatusCommand, SuspendUserCommand,
+};
+use crate::application::admin::admin_service_interface::AdminServiceInterface;
+use crate::application::two_factor::two_factor_service_interface::TwoFactorServiceInterface;
+use crate::application::user::user_command::AdminCreateUserCommand;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::permission::{
+    RequirePermission, UsersDelete, UsersRead, UsersSecurity, UsersWrite,
+};
+use crate::presentation::admin::admin::{AdminUserSerializer, StatusChangeSerializer};
+use crate::presentation::session::session::SessionSerializer;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
 
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/users/{id}/2fa",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Two-factor authentication reset", body = MessageResponse),
+        (status = 400, description = "User not found or two-factor not enabled", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:security` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:security"]))
+)]
+pub async fn controller_admin_reset_two_factor(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersSecurity>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Admin {} resets two-factor of user id: {}", claims.user_id, id);
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.admin_reset(&tx, id).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Two-factor authentication has been reset.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to reset two-factor: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Creates an account with the given status and role. Without a password the user gets
+/// a mail to choose one.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users",
+    tags = ["admin_service"],
+    request_body = AdminCreateUserCommand,
+    responses(
+        (status = 200, description = "User created", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "Invalid data input, unknown role, or `PasswordPolicyViolation`", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:write` required, or the role grants permissions the caller lacks", body = ClientResponseError),
+        (status = 409, description = "Username or email already exists", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:write"]))
+)]
+pub async fn controller_admin_create_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersWrite>,
+    client: ClientInfo,
+    Json(cmd): Json<AdminCreateUserCommand>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} creates user {}", claims.user_id, cmd.username);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.create_user(&tx, &claims, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User created successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to create user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users",
+    tags = ["admin_service"],
+    params(AdminUserListQuery),
+    responses(
+        (status = 200, description = "Users, deleted ones too when asked", body = EntityResponse<Vec<AdminUserSerializer>>),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:read` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:read"]))
+)]
+pub async fn controller_admin_list_users(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersRead>,
+    Query(query): Query<AdminUserListQuery>,
+) -> AppResult<Json<EntityResponse<Vec<AdminUserSerializer>>>> {
+    log::info!(
+        "Admin {} lists users - page: {}, page_size: {}",
+        claims.user_id,
+        query.page,
+        query.page_size
+    );
+    if let Err(validation_err) = query.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.list_users(&tx, &query).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Users retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list users: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// The current password stops working and every session ends; the user gets a reset code by mail.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/password_reset",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Password reset forced", body = MessageResponse),
+        (status = 400, description = "User not found or has no password", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:security` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:security"]))
+)]
+pub async fn controller_admin_force_password_reset(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersSecurity>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Admin {} forces a password reset of user id: {}", claims.user_id, id);
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.force_password_reset(&tx, &claims, id, &client).await {
+        Ok(after_commit) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(MessageResponse::new("The user has to choose a new password.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to force a password reset: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Suspends the account and ends its sessions. With `suspended_until` the account is
+/// reactivated on its first sign-in after that time.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/suspend",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = SuspendUserCommand,
+    responses(
+        (status = 200, description = "User suspended", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "Invalid data input, user not found, already suspended or the end is not in the future", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:write` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:write"]))
+)]
+pub async fn controller_admin_suspend_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersWrite>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(cmd): Json<SuspendUserCommand>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} suspends user id: {}", claims.user_id, id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.suspend_user(&tx, &claims, id, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User suspended successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to suspend user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/reactivate",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = ChangeUserStatusCommand,
+    responses(
+        (status = 200, description = "User reactivated", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "Invalid data input, user not found or not suspended, deactivated or banned", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:write` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:write"]))
+)]
+pub async fn controller_admin_reactivate_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersWrite>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(cmd): Json<ChangeUserStatusCommand>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} reactivates user id: {}", claims.user_id, id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.reactivate_user(&tx, &claims, id, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User reactivated successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to reactivate user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Bars the account until staff reactivate it and ends its sessions.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/ban",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = ChangeUserStatusCommand,
+    responses(
+        (status = 200, description = "User banned", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "Invalid data input, user not found, already banned or closed", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:write` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:write"]))
+)]
+pub async fn controller_admin_ban_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersWrite>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(cmd): Json<ChangeUserStatusCommand>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} bans user id: {}", claims.user_id, id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.ban_user(&tx, &claims, id, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User banned successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to ban user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Closes the account for good and ends its sessions.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/close",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = ChangeUserStatusCommand,
+    responses(
+        (status = 200, description = "User closed", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "Invalid data input, user not found or already closed", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:write` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:write"]))
+)]
+pub async fn controller_admin_close_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersWrite>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(cmd): Json<ChangeUserStatusCommand>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} closes user id: {}", claims.user_id, id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.close_user(&tx, &claims, id, &cmd, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User closed successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to close user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/restore",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Deleted user restored", body = EntityResponse<AdminUserSerializer>),
+        (status = 400, description = "User not found or not deleted", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:delete` required, or the user outranks the caller", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:delete"]))
+)]
+pub async fn controller_admin_restore_user(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersDelete>,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EntityResponse<AdminUserSerializer>>> {
+    log::info!("Admin {} restores user id: {}", claims.user_id, id);
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.restore_user(&tx, &claims, id, &client).await {
+        Ok((result, after_commit)) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(EntityResponse {
+                message: "User restored successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to restore user: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users/{id}/sessions",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Signed-in devices of the user", body = EntityResponse<Vec<SessionSerializer>>),
+        (status = 400, description = "User not found", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:read` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:read"]))
+)]
+pub async fn controller_admin_list_user_sessions(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersRead>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EntityResponse<Vec<SessionSerializer>>>> {
+    log::info!("Admin {} lists sessions of user id: {}", claims.user_id, id);
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.list_sessions(&tx, id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Sessions retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list sessions: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users/{id}/status_changes",
+    tags = ["admin_service"],
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Status history of the user, newest first", body = EntityResponse<Vec<StatusChangeSerializer>>),
+        (status = 400, description = "User not found", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:read` required", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = ["users:read"]))
+)]
+pub async fn controller_admin_list_status_changes(
+    State(state): State<AppState>,
+    RequirePermission(claims, _): RequirePermission<UsersRead>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<EntityResponse<Vec<StatusChangeSerializer>>>> {
+    log::info!("Admin {} lists status changes of user id: {}", claims.user_id, id);
+    let tx = state.db.begin().await?;
+
+    match state.admin_service.list_status_changes(&tx, id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Status changes retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list status changes: {err:?}");
+            Err(err)
+        },
+    }
+}
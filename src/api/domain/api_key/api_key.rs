@@ -0,0 +1,126 @@
+use crate::application::api_key::api_key_command::CreateApiKeyCommand;
+use crate::application::api_key::api_key_service_interface::ApiKeyServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::authenticate::NotImpersonated;
+use crate::presentation::api_key::api_key::{ApiKeyCreatedResponse, ApiKeySerializer};
+use crate::util::claim::UserClaims;
+use axum::extract::{Path, State};
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/api_keys",
+    tags = ["api_key_service"],
+    request_body = CreateApiKeyCommand,
+    responses(
+        (status = 200, description = "Key created, it is shown only once", body = EntityResponse<ApiKeyCreatedResponse>),
+        (status = 400, description = "Invalid data input or unsupported scope", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Called with an API key", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_create_api_key(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Json(cmd): Json<CreateApiKeyCommand>,
+) -> AppResult<Json<EntityResponse<ApiKeyCreatedResponse>>> {
+    log::info!("User {} creates API key {}", claims.user_id, cmd.name);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.api_key_service.create_key(&tx, &claims, claims.user_id, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "API key created. Store it now, it cannot be shown again.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to create API key: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/api_keys",
+    tags = ["api_key_service"],
+    responses(
+        (status = 200, description = "API keys of the current user, revoked ones included", body = EntityResponse<Vec<ApiKeySerializer>>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_list_api_keys(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<Vec<ApiKeySerializer>>>> {
+    log::info!("Listing API keys of user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.api_key_service.list_keys(&tx, claims.user_id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "API keys retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list API keys: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/api_keys/{id}",
+    tags = ["api_key_service"],
+    params(
+        ("id" = i64, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked", body = MessageResponse),
+        (status = 400, description = "No active key with this ID", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_revoke_api_key(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Path(id): Path<i64>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("User {} revokes API key {id}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.api_key_service.revoke_key(&tx, claims.user_id, id).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("API key revoked successfully.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to revoke API key {id}: {err:?}");
+            Err(err)
+        },
+    }
+}
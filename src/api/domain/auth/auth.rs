@@ -1,14 +1,24 @@
 use crate::application::authen::authen_service_interface::AuthenServiceInterface;
 use crate::core::app_state::AppState;
 use crate::core::error::{AppError, AppResult};
-use crate::core::response::ClientResponseError;
+use crate::core::response::{ClientResponseError, MessageResponse};
+use crate::infrastructure::middleware::authenticate::RequireRecentAuth;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::cookie_session::SessionDelivery;
 use axum::extract::State;
+use axum::response::Response;
 use axum::Json;
 use log::error;
 use sea_orm::TransactionTrait;
 use validator::Validate;
-use crate::application::authen::authen_command::LoginByEmailCommand;
-use crate::presentation::authen::authen::LoginResponse;
+use crate::application::authen::authen_command::{
+    ChangePasswordCommand, ForgetPasswordCommand, LoginByEmailCommand, LoginTwoFactorCommand,
+    ReauthenticateCommand, RefreshTokenCommand, ResetPasswordCommand,
+};
+use crate::presentation::authen::authen::{JwkSetResponse, LoginResponse, TokenResponse};
+use crate::util::claim::UserClaims;
+use crate::util::constant::{ACCESS_TOKEN_KEYS, CHECK_EMAIL_MESSAGE};
+use axum::http::header;
 
 #[utoipa::path(
     post,
@@ -18,14 +28,19 @@ use crate::presentation::authen::authen::LoginResponse;
     responses(
         (status = 200, description = "Success login", body = LoginResponse),
         (status = 400, description = "Invalid data input", body = ClientResponseError),
-        (status = 404, description = "Account not found", body = ClientResponseError),
+        (status = 401, description = "Username or password is not correct", body = ClientResponseError),
+        (status = 403, description = "`UserNotActive` for deactivated or unverified accounts, `AccountSuspended`, `AccountBanned` or `AccountClosed` for suspended, banned or closed ones", body = ClientResponseError),
+        (status = 423, description = "Account temporarily locked", body = ClientResponseError),
+        (status = 429, description = "Too many failed attempts, retry later", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     )
 )]
 pub async fn controller_login_by_email(
     State(state): State<AppState>,
+    client: ClientInfo,
+    delivery: SessionDelivery,
     Json(cmd): Json<LoginByEmailCommand>,
-) -> AppResult<Json<LoginResponse>> {
+) -> AppResult<Response> {
     log::info!("Login by email with request: {cmd:?}.");
 
     // Validate command
@@ -39,18 +54,283 @@ pub async fn controller_login_by_email(
     // Call application service
     match state
         .authen_service
-        .login_by_email(&tx, &cmd)
+        .login_by_email(&tx, &cmd, &client)
         .await
     {
+        Ok(login_response) => {
+            tx.commit().await?;
+            log::info!("Success login for user: {}", cmd.get_identifier());
+            Ok(delivery.respond(login_response))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to login user '{}': {err:?}", cmd.get_identifier());
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/2fa",
+    request_body = LoginTwoFactorCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Second factor accepted", body = TokenResponse),
+        (status = 400, description = "Invalid data input or wrong code", body = ClientResponseError),
+        (status = 401, description = "Login challenge is invalid or has expired", body = ClientResponseError),
+        (status = 403, description = "`UserNotActive` for deactivated or unverified accounts, `AccountSuspended`, `AccountBanned` or `AccountClosed` for suspended, banned or closed ones", body = ClientResponseError),
+        (status = 429, description = "Too many attempts", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_login_two_factor(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    delivery: SessionDelivery,
+    Json(cmd): Json<LoginTwoFactorCommand>,
+) -> AppResult<Response> {
+    log::info!("Login second factor request.");
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.login_two_factor(&tx, &cmd, &client).await {
         Ok(token_response) => {
             tx.commit().await?;
-            log::info!("Success login for user: {}", cmd.get_username());
-            Ok(Json(LoginResponse::Token(token_response)))
+            Ok(delivery.respond(token_response))
         }
         Err(err) => {
             tx.rollback().await?;
-            error!("Failed to login user '{}': {err:?}", cmd.get_username());
+            error!("Failed to verify second factor: {err:?}");
             Err(err)
         }
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/v1/refresh_token",
+    request_body = RefreshTokenCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Success refresh token", body = TokenResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 401, description = "Refresh token is invalid, expired or already used", body = ClientResponseError),
+        (status = 403, description = "`UserNotActive` for deactivated or unverified accounts, `AccountSuspended`, `AccountBanned` or `AccountClosed` for suspended, banned or closed ones. Refresh tokens issued to a client are refused, clients refresh at `/oauth/token`", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_refresh_token(
+    State(state): State<AppState>,
+    delivery: SessionDelivery,
+    body: Option<Json<RefreshTokenCommand>>,
+) -> AppResult<Response> {
+    log::info!("Refresh token request.");
+
+    // Cookie clients may send no body at all, the refresh token comes in its cookie
+    let mut cmd = body.map(|Json(cmd)| cmd).unwrap_or_default();
+    if let Some(token) = delivery.refresh_token()? {
+        cmd.token = token;
+    }
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.refresh_token(&tx, cmd.get_token()).await {
+        Ok(token_response) => {
+            tx.commit().await?;
+            Ok(delivery.respond(token_response))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to refresh token: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/forget_password",
+    request_body = ForgetPasswordCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Reset code sent if the email is registered", body = MessageResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_forget_password(
+    State(state): State<AppState>,
+    Json(cmd): Json<ForgetPasswordCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Forget password request.");
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.forget_password(&tx, &cmd).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new(CHECK_EMAIL_MESSAGE)))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to issue password reset code: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/reset_password",
+    request_body = ResetPasswordCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Password reset successfully", body = MessageResponse),
+        (status = 400, description = "Invalid or expired reset code, or `PasswordPolicyViolation`", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_reset_password(
+    State(state): State<AppState>,
+    Json(cmd): Json<ResetPasswordCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Reset password request.");
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.reset_password(&tx, &cmd).await {
+        Ok(after_commit) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(MessageResponse::new("Password has been reset. Please login again.")))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to reset password: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/password",
+    request_body = ChangePasswordCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Password changed", body = MessageResponse),
+        (status = 400, description = "Wrong current password, or `PasswordPolicyViolation`", body = ClientResponseError),
+        (status = 401, description = "Unauthorized, or `ReauthenticationRequired`", body = ClientResponseError),
+        (status = 403, description = "Called with an API key", body = ClientResponseError),
+        (status = 423, description = "Account temporarily locked", body = ClientResponseError),
+        (status = 429, description = "Too many failed attempts, retry later", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_change_password(
+    State(state): State<AppState>,
+    RequireRecentAuth(claims): RequireRecentAuth,
+    client: ClientInfo,
+    Json(cmd): Json<ChangePasswordCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Change password of user id: {}.", claims.user_id);
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.change_password(&tx, &claims, &cmd, &client).await {
+        Ok(after_commit) => {
+            tx.commit().await?;
+            after_commit.run().await?;
+            Ok(Json(MessageResponse::new("Password has been changed.")))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to change password: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+/// Step-up for sensitive operations. The answer replaces both tokens of the session;
+/// the previous refresh token stops working.
+#[utoipa::path(
+    post,
+    path = "/v1/me/reauthenticate",
+    request_body = ReauthenticateCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Tokens with a fresh `auth_time`", body = TokenResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 401, description = "Unauthorized or wrong password / code", body = ClientResponseError),
+        (status = 403, description = "Called with an API key", body = ClientResponseError),
+        (status = 423, description = "Account temporarily locked", body = ClientResponseError),
+        (status = 429, description = "Too many failed attempts, retry later", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_reauthenticate(
+    State(state): State<AppState>,
+    claims: UserClaims,
+    client: ClientInfo,
+    delivery: SessionDelivery,
+    Json(cmd): Json<ReauthenticateCommand>,
+) -> AppResult<Response> {
+    log::info!("Re-authenticate user id: {}.", claims.user_id);
+
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    let tx = state.db.begin().await?;
+
+    match state.authen_service.reauthenticate(&tx, &claims, &cmd, &client).await {
+        Ok(token_response) => {
+            tx.commit().await?;
+            Ok(delivery.respond(token_response))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to re-authenticate user {}: {err:?}", claims.user_id);
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Public keys that verify access tokens, selected by `kid`", body = JwkSetResponse)
+    )
+)]
+pub async fn controller_jwks() -> ([(header::HeaderName, &'static str); 1], Json<JwkSetResponse>) {
+    // Short cache so a newly added key is picked up well before it starts signing
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(JwkSetResponse { keys: ACCESS_TOKEN_KEYS.jwks().to_vec() }),
+    )
+}
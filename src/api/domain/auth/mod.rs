@@ -1 +1,3 @@
 pub mod auth;
+pub mod oidc;
+pub mod passwordless;
@@ -0,0 +1,255 @@
+use crate::application::authen::oidc_command::{AuthorizeCommand, ConsentCommand, OidcTokenCommand};
+use crate::application::authen::oidc_service_interface::OidcServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::authenticate::{BearerClaims, NotImpersonated};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::authen::oidc::{
+    AuthorizeResponse, ConsentResponse, OidcTokenResponse, OpenIdConfigurationResponse,
+    UserInfoResponse,
+};
+use crate::util::claim::UserClaims;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::{Form, Json};
+use axum_extra::headers::authorization::Basic;
+use axum_extra::headers::Authorization;
+use axum_extra::TypedHeader;
+use log::error;
+use sea_orm::TransactionTrait;
+
+/// Authentication request of the authorization code flow.
+///
+/// The login page of the first-party frontend forwards the request here with the
+/// signed-in user's access token, then follows `redirect_to` or shows the consent prompt.
+#[utoipa::path(
+    get,
+    path = "/oauth/authorize",
+    tags = ["oidc_service"],
+    params(AuthorizeCommand),
+    responses(
+        (status = 200, description = "Redirect back to the client, or consent required", body = AuthorizeResponse),
+        (status = 400, description = "Unknown client or unregistered redirect URI", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_authorize(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Query(cmd): Query<AuthorizeCommand>,
+) -> AppResult<Json<AuthorizeResponse>> {
+    log::info!("User {} authorizes client {}", claims.user_id, cmd.client_id);
+    let tx = state.db.begin().await?;
+
+    match state.oidc_service.authorize(&tx, &claims, &cmd).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            error!("Failed to authorize client {}: {err:?}", cmd.client_id);
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/oauth/authorize",
+    tags = ["oidc_service"],
+    request_body = ConsentCommand,
+    responses(
+        (status = 200, description = "Redirect back to the client with a code, or with `access_denied`", body = AuthorizeResponse),
+        (status = 400, description = "Unknown client or unregistered redirect URI", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_consent(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Json(cmd): Json<ConsentCommand>,
+) -> AppResult<Json<AuthorizeResponse>> {
+    log::info!("User {} answers consent for client {}", claims.user_id, cmd.authorize.client_id);
+    let tx = state.db.begin().await?;
+
+    match state.oidc_service.consent(&tx, &claims, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(result))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to record consent: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/oauth/token",
+    tags = ["oidc_service"],
+    request_body(content = OidcTokenCommand, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Tokens issued", body = OidcTokenResponse),
+        (status = 400, description = "invalid_grant or unsupported_grant_type", body = ClientResponseError),
+        (status = 401, description = "Client authentication failed", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security((), ("client_basic" = []))
+)]
+pub async fn controller_token(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(cmd): Form<OidcTokenCommand>,
+) -> AppResult<([(header::HeaderName, &'static str); 1], Json<OidcTokenResponse>)> {
+    // HTTP Basic auth wins over credentials in the form body (RFC 6749 2.3.1)
+    let (client_id, client_secret) = match basic {
+        Some(TypedHeader(Authorization(basic))) => {
+            (basic.username().to_string(), Some(basic.password().to_string()))
+        },
+        None => (
+            cmd.client_id
+                .clone()
+                .ok_or_else(|| AppError::UnauthorizedError("invalid_client".to_string()))?,
+            cmd.client_secret.clone(),
+        ),
+    };
+    log::info!("Token request of client {client_id} with grant {}", cmd.grant_type);
+    let tx = state.db.begin().await?;
+
+    match state
+        .oidc_service
+        .token(&tx, &client_id, client_secret.as_deref(), &cmd, &client)
+        .await
+    {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(([(header::CACHE_CONTROL, "no-store")], Json(result)))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to issue tokens to client {client_id}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/oauth/userinfo",
+    tags = ["oidc_service"],
+    responses(
+        (status = 200, description = "Claims about the user, limited to the granted scopes", body = UserInfoResponse),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "The openid scope was not granted", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_userinfo(
+    State(state): State<AppState>,
+    BearerClaims(claims): BearerClaims,
+) -> AppResult<Json<UserInfoResponse>> {
+    let tx = state.db.begin().await?;
+
+    match state.oidc_service.userinfo(&tx, &claims).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            error!("Failed to read userinfo of user {}: {err:?}", claims.user_id);
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/openid-configuration",
+    tags = ["oidc_service"],
+    responses(
+        (status = 200, description = "OpenID provider metadata", body = OpenIdConfigurationResponse)
+    )
+)]
+pub async fn controller_openid_configuration(
+    State(state): State<AppState>,
+) -> ([(header::HeaderName, &'static str); 1], Json<OpenIdConfigurationResponse>) {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=3600")],
+        Json(state.oidc_service.openid_configuration()),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/consents",
+    tags = ["oidc_service"],
+    responses(
+        (status = 200, description = "Clients the current user granted access to", body = EntityResponse<Vec<ConsentResponse>>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_list_consents(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<Vec<ConsentResponse>>>> {
+    log::info!("Listing consents of user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.oidc_service.list_consents(&tx, claims.user_id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Consents retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list consents: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Withdraws the consent, so the client has to ask again on its next sign-in.
+/// Sessions the client already holds are ended from `/v1/me/sessions`.
+#[utoipa::path(
+    delete,
+    path = "/v1/me/consents/{client_id}",
+    tags = ["oidc_service"],
+    params(
+        ("client_id" = String, Path, description = "Client ID")
+    ),
+    responses(
+        (status = 200, description = "Consent revoked", body = MessageResponse),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 400, description = "No consent for this client", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_revoke_consent(
+    State(state): State<AppState>,
+    claims: UserClaims,
+    Path(client_id): Path<String>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("User {} revokes consent of client {}", claims.user_id, client_id);
+    let tx = state.db.begin().await?;
+
+    match state.oidc_service.revoke_consent(&tx, claims.user_id, &client_id).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Consent revoked successfully.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to revoke consent: {err:?}");
+            Err(err)
+        },
+    }
+}
@@ -0,0 +1,162 @@
+use crate::application::authen::passwordless_command::{
+    MagicLinkLoginCommand, PhoneOtpLoginCommand, SendMagicLinkCommand, SendPhoneOtpCommand,
+};
+use crate::application::authen::passwordless_service_interface::PasswordlessServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, MessageResponse};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::cookie_session::SessionDelivery;
+use crate::presentation::authen::authen::LoginResponse;
+use crate::util::constant::CHECK_EMAIL_MESSAGE;
+use axum::extract::State;
+use axum::response::Response;
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/magic_link",
+    request_body = SendMagicLinkCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Sign-in link sent if the email is registered", body = MessageResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 429, description = "Too many links sent to this address, retry later", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_send_magic_link(
+    State(state): State<AppState>,
+    Json(cmd): Json<SendMagicLinkCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Magic link request.");
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.passwordless_service.send_magic_link(&tx, &cmd).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new(CHECK_EMAIL_MESSAGE)))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to send magic link: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/magic_link/verify",
+    request_body = MagicLinkLoginCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Signed in, or a second factor is required", body = LoginResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 401, description = "The link is invalid, expired or already used", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_login_by_magic_link(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    delivery: SessionDelivery,
+    Json(cmd): Json<MagicLinkLoginCommand>,
+) -> AppResult<Response> {
+    log::info!("Login by magic link.");
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.passwordless_service.login_by_magic_link(&tx, &cmd, &client).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(delivery.respond(result))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to login by magic link: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/otp",
+    request_body = SendPhoneOtpCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Code texted if the number belongs to an account", body = MessageResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 429, description = "Too many codes sent to this number, retry later", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_send_phone_otp(
+    State(state): State<AppState>,
+    Json(cmd): Json<SendPhoneOtpCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Phone code request.");
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.passwordless_service.send_phone_otp(&tx, &cmd).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Please check your phone.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to send phone code: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/otp/verify",
+    request_body = PhoneOtpLoginCommand,
+    tags = ["auth_service"],
+    responses(
+        (status = 200, description = "Signed in, or a second factor is required", body = LoginResponse),
+        (status = 400, description = "Invalid data input", body = ClientResponseError),
+        (status = 401, description = "The code is wrong or has expired", body = ClientResponseError),
+        (status = 429, description = "Too many wrong codes, request a new one", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_login_by_phone_otp(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    delivery: SessionDelivery,
+    Json(cmd): Json<PhoneOtpLoginCommand>,
+) -> AppResult<Response> {
+    log::info!("Login by phone code.");
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.passwordless_service.login_by_phone_otp(&tx, &cmd, &client).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(delivery.respond(result))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to login by phone code: {err:?}");
+            Err(err)
+        },
+    }
+}
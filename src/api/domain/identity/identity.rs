@@ -0,0 +1,244 @@
+use crate::application::identity::identity_command::{ExternalLoginCallbackCommand, ExternalLoginStartQuery};
+use crate::application::identity::identity_service_interface::IdentityServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::authenticate::NotImpersonated;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::cookie_session::SessionDelivery;
+use crate::presentation::authen::authen::LoginResponse;
+use crate::presentation::identity::identity::{ExternalLoginStartResponse, IdentitySerializer};
+use crate::util::claim::UserClaims;
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    get,
+    path = "/v1/login/external/{provider}",
+    tags = ["identity_service"],
+    params(
+        ("provider" = String, Path, description = "Configured identity provider name"),
+        ExternalLoginStartQuery
+    ),
+    responses(
+        (status = 200, description = "Send the browser to `authorization_url`", body = ExternalLoginStartResponse),
+        (status = 400, description = "Unknown identity provider", body = ClientResponseError),
+        (status = 401, description = "The provider could not be reached", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_start_external_login(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<ExternalLoginStartQuery>,
+) -> AppResult<Json<ExternalLoginStartResponse>> {
+    log::info!("Starting external login with {provider}");
+    if let Err(validation_err) = query.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+
+    match state.identity_service.start_login(&provider, query.device_name).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            error!("Failed to start external login with {provider}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+/// Finishes the sign-in. Provider accounts seen for the first time get a new user.
+#[utoipa::path(
+    post,
+    path = "/v1/login/external/{provider}/callback",
+    tags = ["identity_service"],
+    params(
+        ("provider" = String, Path, description = "Configured identity provider name")
+    ),
+    request_body = ExternalLoginCallbackCommand,
+    responses(
+        (status = 200, description = "Signed in, or a second factor is required", body = LoginResponse),
+        (status = 400, description = "Invalid data input or expired sign-in", body = ClientResponseError),
+        (status = 401, description = "The provider's answer could not be validated", body = ClientResponseError),
+        (status = 409, description = "An account with this email exists and must link the identity itself", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_complete_external_login(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    delivery: SessionDelivery,
+    Path(provider): Path<String>,
+    Json(cmd): Json<ExternalLoginCallbackCommand>,
+) -> AppResult<Response> {
+    log::info!("Completing external login with {provider}");
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.identity_service.complete_login(&tx, &provider, &cmd, &client).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(delivery.respond(result))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to complete external login with {provider}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/identities",
+    tags = ["identity_service"],
+    responses(
+        (status = 200, description = "External identities linked to the current user", body = EntityResponse<Vec<IdentitySerializer>>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_list_identities(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<Vec<IdentitySerializer>>>> {
+    log::info!("Listing identities of user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.identity_service.list_identities(&tx, claims.user_id).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Identities retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list identities: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/identities/link/{provider}",
+    tags = ["identity_service"],
+    params(
+        ("provider" = String, Path, description = "Configured identity provider name")
+    ),
+    responses(
+        (status = 200, description = "Send the browser to `authorization_url`", body = ExternalLoginStartResponse),
+        (status = 400, description = "Unknown identity provider", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_start_link_identity(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Path(provider): Path<String>,
+) -> AppResult<Json<ExternalLoginStartResponse>> {
+    log::info!("User {} starts linking {provider}", claims.user_id);
+
+    match state.identity_service.start_link(claims.user_id, &provider).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            error!("Failed to start linking {provider}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/identities/link/{provider}/callback",
+    tags = ["identity_service"],
+    params(
+        ("provider" = String, Path, description = "Configured identity provider name")
+    ),
+    request_body = ExternalLoginCallbackCommand,
+    responses(
+        (status = 200, description = "Identity linked", body = EntityResponse<IdentitySerializer>),
+        (status = 400, description = "Invalid data input, expired flow or already linked", body = ClientResponseError),
+        (status = 401, description = "Unauthorized or the provider's answer could not be validated", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 409, description = "The provider account is linked to another user", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_complete_link_identity(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Path(provider): Path<String>,
+    Json(cmd): Json<ExternalLoginCallbackCommand>,
+) -> AppResult<Json<EntityResponse<IdentitySerializer>>> {
+    log::info!("User {} completes linking {provider}", claims.user_id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.identity_service.complete_link(&tx, claims.user_id, &provider, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Identity linked successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to link {provider}: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/identities/{id}",
+    tags = ["identity_service"],
+    params(
+        ("id" = i64, Path, description = "Identity ID")
+    ),
+    responses(
+        (status = 200, description = "Identity unlinked", body = MessageResponse),
+        (status = 400, description = "Identity not found or it is the last sign-in method", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_unlink_identity(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Path(id): Path<i64>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("User {} unlinks identity {id}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.identity_service.unlink(&tx, claims.user_id, id).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Identity unlinked successfully.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to unlink identity {id}: {err:?}");
+            Err(err)
+        },
+    }
+}
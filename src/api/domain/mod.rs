@@ -1,6 +1,11 @@
 pub mod admin;
+pub mod api_key;
 pub mod auth;
 pub mod business_rule_interface;
+pub mod identity;
+pub mod oauth;
 pub mod server;
+pub mod session;
+pub mod two_factor;
 pub mod user;
 pub mod address;
@@ -0,0 +1,94 @@
+use crate::application::oauth::oauth_command::{
+    ClientCredentials, IntrospectTokenCommand, RevokeTokenCommand,
+};
+use crate::application::oauth::oauth_service_interface::OauthServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::ClientResponseError;
+use crate::presentation::oauth::oauth::IntrospectionResponse;
+use axum::extract::State;
+use axum::{Form, Json};
+use axum_extra::headers::authorization::Basic;
+use axum_extra::headers::Authorization;
+use axum_extra::TypedHeader;
+use log::error;
+use sea_orm::TransactionTrait;
+
+/// HTTP Basic auth wins over credentials in the form body (RFC 6749 2.3.1).
+fn client_credentials(
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    client_id: &Option<String>,
+    client_secret: &Option<String>,
+) -> AppResult<ClientCredentials> {
+    if let Some(TypedHeader(Authorization(basic))) = basic {
+        return Ok(ClientCredentials {
+            client_id: basic.username().to_string(),
+            client_secret: basic.password().to_string(),
+        });
+    }
+    match (client_id, client_secret) {
+        (Some(client_id), Some(client_secret)) => Ok(ClientCredentials {
+            client_id: client_id.clone(),
+            client_secret: client_secret.clone(),
+        }),
+        _ => Err(AppError::UnauthorizedError("invalid_client".to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/oauth/introspect",
+    tags = ["oauth_service"],
+    request_body(content = IntrospectTokenCommand, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Token state, `active: false` for unknown, expired or revoked tokens", body = IntrospectionResponse),
+        (status = 401, description = "Client authentication failed", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("client_basic" = []))
+)]
+pub async fn controller_introspect(
+    State(state): State<AppState>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(cmd): Form<IntrospectTokenCommand>,
+) -> AppResult<Json<IntrospectionResponse>> {
+    let credentials = client_credentials(basic, &cmd.client_id, &cmd.client_secret)?;
+    let tx = state.db.begin().await?;
+
+    match state.oauth_service.introspect(&tx, &credentials, &cmd).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            error!("Failed to introspect token: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/oauth/revoke",
+    tags = ["oauth_service"],
+    request_body(content = RevokeTokenCommand, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Token revoked, or it was already invalid or not issued to the client"),
+        (status = 401, description = "Client authentication failed", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("client_basic" = []))
+)]
+pub async fn controller_revoke(
+    State(state): State<AppState>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(cmd): Form<RevokeTokenCommand>,
+) -> AppResult<()> {
+    let credentials = client_credentials(basic, &cmd.client_id, &cmd.client_secret)?;
+    let tx = state.db.begin().await?;
+
+    match state.oauth_service.revoke(&tx, &credentials, &cmd).await {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            error!("Failed to revoke token: {err:?}");
+            Err(err)
+        },
+    }
+}
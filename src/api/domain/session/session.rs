@@ -0,0 +1,114 @@
+use crate::application::session::session_service_interface::SessionServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::AppResult;
+use crate::core::response::{ClientResponseError, EntityResponse};
+use crate::infrastructure::middleware::authenticate::NotImpersonated;
+use crate::presentation::session::session::SessionSerializer;
+use crate::util::claim::UserClaims;
+use axum::extract::{Path, State};
+use axum::Json;
+use log::error;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/sessions",
+    tags = ["session_service"],
+    responses(
+        (status = 200, description = "Active sessions of the current user", body = EntityResponse<Vec<SessionSerializer>>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_list_sessions(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<Vec<SessionSerializer>>>> {
+    log::info!("Listing sessions of user id: {}", claims.user_id);
+
+    match state.session_service.list_sessions(claims.user_id, &claims.sid).await {
+        Ok(result) => {
+            let total = result.len();
+            Ok(Json(EntityResponse {
+                message: "Sessions retrieved successfully.".to_string(),
+                data: Some(result),
+                total: total as i64,
+            }))
+        },
+        Err(err) => {
+            error!("Failed to list sessions: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/sessions/{session_id}",
+    tags = ["session_service"],
+    params(
+        ("session_id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session revoked successfully", body = EntityResponse<String>),
+        (status = 400, description = "Session not found", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating or with an API key", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_revoke_session(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Path(session_id): Path<Uuid>,
+) -> AppResult<Json<EntityResponse<String>>> {
+    log::info!("Revoking session {} of user id: {}", session_id, claims.user_id);
+    claims.require_session()?;
+
+    match state.session_service.revoke_session(claims.user_id, &session_id).await {
+        Ok(_) => Ok(Json(EntityResponse {
+            message: "Session revoked successfully.".to_string(),
+            data: Some("Session revoked successfully.".to_string()),
+            total: 1,
+        })),
+        Err(err) => {
+            error!("Failed to revoke session: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/sessions",
+    tags = ["session_service"],
+    responses(
+        (status = 200, description = "All other sessions revoked", body = EntityResponse<String>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating or with an API key", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_revoke_other_sessions(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+) -> AppResult<Json<EntityResponse<String>>> {
+    log::info!("Revoking other sessions of user id: {}", claims.user_id);
+    // Without a session of its own every session would count as "other"
+    claims.require_session()?;
+
+    match state.session_service.revoke_other_sessions(claims.user_id, &claims.sid).await {
+        Ok(revoked) => Ok(Json(EntityResponse {
+            message: "Other sessions revoked successfully.".to_string(),
+            data: Some(format!("Revoked {revoked} session(s).")),
+            total: revoked as i64,
+        })),
+        Err(err) => {
+            error!("Failed to revoke other sessions: {err:?}");
+            Err(err)
+        },
+    }
+}
@@ -0,0 +1,200 @@
+use crate::application::two_factor::two_factor_command::{ConfirmTotpCommand, TwoFactorCodeCommand};
+use crate::application::two_factor::two_factor_service_interface::TwoFactorServiceInterface;
+use crate::core::app_state::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::core::response::{ClientResponseError, EntityResponse, MessageResponse};
+use crate::infrastructure::middleware::authenticate::{NotImpersonated, RequireRecentAuth};
+use crate::presentation::two_factor::two_factor::{
+    RecoveryCodesResponse, TotpEnrollmentResponse, TwoFactorStatusResponse,
+};
+use crate::util::claim::UserClaims;
+use axum::extract::State;
+use axum::Json;
+use log::error;
+use sea_orm::TransactionTrait;
+use validator::Validate;
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/2fa",
+    tags = ["two_factor_service"],
+    responses(
+        (status = 200, description = "Two-factor status of the current user", body = EntityResponse<TwoFactorStatusResponse>),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_two_factor_status(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> AppResult<Json<EntityResponse<TwoFactorStatusResponse>>> {
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.status(&tx, claims.user_id).await {
+        Ok(result) => Ok(Json(EntityResponse {
+            message: "Successfully get two-factor status.".to_string(),
+            data: Some(result),
+            total: 1,
+        })),
+        Err(err) => {
+            error!("Failed to get two-factor status: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/2fa/totp",
+    tags = ["two_factor_service"],
+    responses(
+        (status = 200, description = "TOTP secret generated, waiting for confirmation", body = EntityResponse<TotpEnrollmentResponse>),
+        (status = 400, description = "Two-factor authentication is already enabled", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_enroll_totp(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+) -> AppResult<Json<EntityResponse<TotpEnrollmentResponse>>> {
+    log::info!("Enroll TOTP for user id: {}", claims.user_id);
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.enroll_totp(&tx, claims.user_id).await {
+        Ok(result) => Ok(Json(EntityResponse {
+            message: "Scan the code with your authenticator app and confirm it.".to_string(),
+            data: Some(result),
+            total: 1,
+        })),
+        Err(err) => {
+            error!("Failed to enroll TOTP: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/2fa/totp/confirm",
+    tags = ["two_factor_service"],
+    request_body = ConfirmTotpCommand,
+    responses(
+        (status = 200, description = "Two-factor enabled, recovery codes are returned once", body = EntityResponse<RecoveryCodesResponse>),
+        (status = 400, description = "Wrong code or no pending enrollment", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_confirm_totp(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Json(cmd): Json<ConfirmTotpCommand>,
+) -> AppResult<Json<EntityResponse<RecoveryCodesResponse>>> {
+    log::info!("Confirm TOTP for user id: {}", claims.user_id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.confirm_totp(&tx, claims.user_id, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Two-factor authentication enabled. Store the recovery codes safely.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to confirm TOTP: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/2fa/recovery_codes",
+    tags = ["two_factor_service"],
+    request_body = TwoFactorCodeCommand,
+    responses(
+        (status = 200, description = "New recovery codes, the old ones stop working", body = EntityResponse<RecoveryCodesResponse>),
+        (status = 400, description = "Wrong code or two-factor not enabled", body = ClientResponseError),
+        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not available while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_regenerate_recovery_codes(
+    State(state): State<AppState>,
+    NotImpersonated(claims): NotImpersonated,
+    Json(cmd): Json<TwoFactorCodeCommand>,
+) -> AppResult<Json<EntityResponse<RecoveryCodesResponse>>> {
+    log::info!("Regenerate recovery codes for user id: {}", claims.user_id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.regenerate_recovery_codes(&tx, claims.user_id, &cmd).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Recovery codes regenerated.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to regenerate recovery codes: {err:?}");
+            Err(err)
+        },
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/2fa",
+    tags = ["two_factor_service"],
+    request_body = TwoFactorCodeCommand,
+    responses(
+        (status = 200, description = "Two-factor authentication disabled", body = MessageResponse),
+        (status = 400, description = "Wrong code or two-factor not enabled", body = ClientResponseError),
+        (status = 401, description = "Unauthorized, or `ReauthenticationRequired`", body = ClientResponseError),
+        (status = 403, description = "Called with an API key or while impersonating", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    ),
+    security(("jwt" = []))
+)]
+pub async fn controller_disable_two_factor(
+    State(state): State<AppState>,
+    RequireRecentAuth(claims): RequireRecentAuth,
+    Json(cmd): Json<TwoFactorCodeCommand>,
+) -> AppResult<Json<MessageResponse>> {
+    log::info!("Disable two-factor for user id: {}", claims.user_id);
+    if let Err(validation_err) = cmd.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.two_factor_service.disable(&tx, claims.user_id, &cmd).await {
+        Ok(_) => {
+            tx.commit().await?;
+            Ok(Json(MessageResponse::new("Two-factor authentication disabled.")))
+        },
+        Err(err) => {
+            tx.rollback().await?;
+            error!("Failed to disable two-factor: {err:?}");
+            Err(err)
+        },
+    }
+}
@@ -1,14 +1,21 @@
 use crate::core::app_state::AppState;
-use crate::core::error::AppResult;
+use crate::core::error::{AppError, AppResult};
 use crate::core::response::{ClientResponseError, EntityResponse};
+use crate::application::user::user_command::{ResendVerificationEmailCommand, VerifyEmailCommand};
 use crate::application::user::user_service_interface::UserServiceInterface;
 use crate::presentation::user::user::{UserSerializer, CreateUserRequest, UpdateUserRequest};
+use crate::infrastructure::middleware::authenticate::RequireRecentAuth;
+use crate::infrastructure::middleware::cookie_session::SessionDelivery;
+use crate::infrastructure::middleware::permission::{RequirePermission, UsersRead};
 use crate::util::claim::UserClaims;
+use crate::util::constant::RECENT_AUTH_MAX_AGE_SECS;
 use axum::extract::{Path, Query, State};
+use axum::response::Response;
 use axum::Json;
 use log::error;
 use sea_orm::TransactionTrait;
 use serde::Deserialize;
+use validator::Validate;
 
 #[utoipa::path(
     get,
@@ -31,7 +38,10 @@ pub async fn controller_get_profile(
     match state.user_service.get_profile(&tx, claims.user_id).await {
         Ok(result) => Ok(Json(EntityResponse {
             message: "Successfully get profile.".to_string(),
-            data: Some(result),
+            data: Some(UserSerializer {
+                impersonated_by: claims.act.map(|actor| actor.user_id),
+                ..result
+            }),
             total: 1,
         })),
         Err(err) => {
@@ -55,14 +65,15 @@ pub async fn controller_get_profile(
 pub async fn controller_logout(
     State(state): State<AppState>,
     claims: UserClaims,
-) -> AppResult<Json<EntityResponse<String>>> {
+    delivery: SessionDelivery,
+) -> AppResult<Response> {
     log::info!("Logout user id: {}", claims.user_id);
     let tx = state.db.begin().await?;
 
-    match state.user_service.logout(&tx, claims.user_id).await {
+    match state.user_service.logout(&tx, claims.user_id, &claims.sid).await {
         Ok(_) => {
             log::info!("Success logout user id: {}", claims.user_id);
-            Ok(Json(EntityResponse {
+            Ok(delivery.clear(EntityResponse {
                 message: "Successfully logged out.".to_string(),
                 data: Some("Successfully logged out.".to_string()),
                 total: 1,
@@ -98,7 +109,7 @@ fn default_page_size() -> u64 {
     request_body = CreateUserRequest,
     responses(
         (status = 201, description = "User created successfully", body = EntityResponse<bool>),
-        (status = 400, description = "Bad request", body = ClientResponseError),
+        (status = 400, description = "Bad request, or `PasswordPolicyViolation` listing the unmet rules", body = ClientResponseError),
         (status = 409, description = "User already exists", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     )
@@ -136,9 +147,10 @@ pub async fn controller_create_user(
         ("id" = i64, Path, description = "User ID")
     ),
     responses(
-        (status = 200, description = "User updated successfully", body = EntityResponse<bool>),
+        (status = 200, description = "User updated successfully. A new email address must be verified again, a code is sent to it", body = EntityResponse<bool>),
         (status = 400, description = "Bad request", body = ClientResponseError),
-        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 401, description = "Unauthorized, or `ReauthenticationRequired` to change the email", body = ClientResponseError),
+        (status = 403, description = "Not your account, or changing the status, without `users:write`", body = ClientResponseError),
         (status = 404, description = "User not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -146,19 +158,24 @@ pub async fn controller_create_user(
 )]
 pub async fn controller_update_user(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Path(id): Path<i64>,
     Json(request): Json<UpdateUserRequest>,
 ) -> AppResult<Json<EntityResponse<bool>>> {
     log::info!("Updating user with id: {}", id);
+    // Whoever controls the email controls the account through password reset
+    if request.email.is_some() {
+        claims.require_recent_auth(RequireRecentAuth::<RECENT_AUTH_MAX_AGE_SECS>::MAX_AGE)?;
+    }
     let tx = state.db.begin().await?;
 
-    match state.user_service.update_user(&tx, id, request).await {
-        Ok(result) => {
+    match state.user_service.update_user(&tx, &claims, id, request).await {
+        Ok(after_commit) => {
             tx.commit().await?;
+            after_commit.run().await?;
             Ok(Json(EntityResponse {
                 message: "User updated successfully.".to_string(),
-                data: Some(result),
+                data: Some(true),
                 total: 1,
             }))
         }
@@ -180,6 +197,7 @@ pub async fn controller_update_user(
     responses(
         (status = 200, description = "User retrieved successfully", body = EntityResponse<UserSerializer>),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Not your account and no `users:read` permission", body = ClientResponseError),
         (status = 404, description = "User not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -187,13 +205,13 @@ pub async fn controller_update_user(
 )]
 pub async fn controller_get_user_by_id(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    claims: UserClaims,
     Path(id): Path<i64>,
 ) -> AppResult<Json<EntityResponse<UserSerializer>>> {
     log::info!("Getting user with id: {}", id);
     let tx = state.db.begin().await?;
 
-    match state.user_service.get_profile(&tx, id).await {
+    match state.user_service.get_user(&tx, &claims, id).await {
         Ok(result) => Ok(Json(EntityResponse {
             message: "User retrieved successfully.".to_string(),
             data: Some(result),
@@ -217,13 +235,14 @@ pub async fn controller_get_user_by_id(
     responses(
         (status = 200, description = "Users retrieved successfully", body = EntityResponse<Vec<UserSerializer>>),
         (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 403, description = "Permission `users:read` required", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
-    security(("jwt" = []))
+    security(("jwt" = ["users:read"]))
 )]
 pub async fn controller_list_users(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    _claims: RequirePermission<UsersRead>,
     Query(params): Query<PaginationQuery>,
 ) -> AppResult<Json<EntityResponse<Vec<UserSerializer>>>> {
     log::info!("Listing users - page: {}, page_size: {}", params.page, params.page_size);
@@ -254,7 +273,8 @@ pub async fn controller_list_users(
     ),
     responses(
         (status = 200, description = "User deleted successfully", body = EntityResponse<String>),
-        (status = 401, description = "Unauthorized", body = ClientResponseError),
+        (status = 401, description = "Unauthorized, or `ReauthenticationRequired`", body = ClientResponseError),
+        (status = 403, description = "Not your account and no `users:delete` permission", body = ClientResponseError),
         (status = 404, description = "User not found", body = ClientResponseError),
         (status = 500, description = "Internal server error", body = ClientResponseError)
     ),
@@ -262,13 +282,13 @@ pub async fn controller_list_users(
 )]
 pub async fn controller_delete_user(
     State(state): State<AppState>,
-    _claims: UserClaims,
+    RequireRecentAuth(claims): RequireRecentAuth,
     Path(id): Path<i64>,
 ) -> AppResult<Json<EntityResponse<String>>> {
     log::info!("Deleting user with id: {}", id);
     let tx = state.db.begin().await?;
 
-    match state.user_service.delete_user(&tx, id).await {
+    match state.user_service.delete_user(&tx, &claims, id).await {
         Ok(_) => {
             tx.commit().await?;
             Ok(Json(EntityResponse {
@@ -284,3 +304,81 @@ pub async fn controller_delete_user(
         }
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/v1/verify_email",
+    tags = ["user_service"],
+    request_body = VerifyEmailCommand,
+    responses(
+        (status = 200, description = "Email verified successfully", body = EntityResponse<bool>),
+        (status = 400, description = "Code is invalid or has expired", body = ClientResponseError),
+        (status = 429, description = "Too many attempts", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_verify_email(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyEmailCommand>,
+) -> AppResult<Json<EntityResponse<bool>>> {
+    log::info!("Verifying email: {}", request.email);
+    if let Err(validation_err) = request.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.user_service.verify_email(&tx, request).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Email verified successfully.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            log::error!("Failed to verify email: {err:?}");
+            Err(err)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/verify_email/resend",
+    tags = ["user_service"],
+    request_body = ResendVerificationEmailCommand,
+    responses(
+        (status = 200, description = "Verification email sent if the account needs one", body = EntityResponse<bool>),
+        (status = 400, description = "Bad request", body = ClientResponseError),
+        (status = 429, description = "Resent too recently for this address, known or not", body = ClientResponseError),
+        (status = 500, description = "Internal server error", body = ClientResponseError)
+    )
+)]
+pub async fn controller_resend_verification_email(
+    State(state): State<AppState>,
+    Json(request): Json<ResendVerificationEmailCommand>,
+) -> AppResult<Json<EntityResponse<bool>>> {
+    log::info!("Resending verification email to: {}", request.email);
+    if let Err(validation_err) = request.validate() {
+        return Err(AppError::BadRequestError(validation_err.to_string()));
+    }
+    let tx = state.db.begin().await?;
+
+    match state.user_service.resend_verification_email(&tx, request).await {
+        Ok(result) => {
+            tx.commit().await?;
+            Ok(Json(EntityResponse {
+                message: "Please check your email.".to_string(),
+                data: Some(result),
+                total: 1,
+            }))
+        }
+        Err(err) => {
+            tx.rollback().await?;
+            log::error!("Failed to resend verification email: {err:?}");
+            Err(err)
+        }
+    }
+}
@@ -5,16 +5,68 @@ use crate::infrastructure::gateway::routes::{
     gateway_health_check, list_services, proxy_to_product_service, proxy_to_order_service,
     proxy_to_inventory_service, proxy_to_notification_service,
 };
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 pub mod domain;
 
+#[derive(OpenApi)]
+#[openapi(modifiers(&SecurityAddon))]
+pub struct ApiDoc;
+
+/// Declares the security schemes the routes refer to. The scopes of a `jwt` requirement
+/// are the permissions the route needs.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "jwt",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("Access token. Scopes list the permissions a route requires."))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "client_basic",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Basic).build()),
+        );
+    }
+}
+
 pub fn build_routes() -> OpenApiRouter<AppState> {
     let server_routes = OpenApiRouter::new()
         .routes(routes!(domain::server::health_check));
 
-    let auth_routes =
-        OpenApiRouter::new().routes(routes!(domain::auth::auth::controller_login_by_email));
+    let auth_routes = OpenApiRouter::new()
+        .routes(routes!(domain::auth::auth::controller_login_by_email))
+        .routes(routes!(domain::auth::auth::controller_login_two_factor))
+        .routes(routes!(domain::auth::auth::controller_refresh_token))
+        .routes(routes!(domain::auth::auth::controller_forget_password))
+        .routes(routes!(domain::auth::auth::controller_reset_password))
+        .routes(routes!(domain::auth::auth::controller_change_password))
+        .routes(routes!(domain::auth::auth::controller_reauthenticate))
+        .routes(routes!(domain::auth::passwordless::controller_send_magic_link))
+        .routes(routes!(domain::auth::passwordless::controller_login_by_magic_link))
+        .routes(routes!(domain::auth::passwordless::controller_send_phone_otp))
+        .routes(routes!(domain::auth::passwordless::controller_login_by_phone_otp))
+        .routes(routes!(domain::auth::auth::controller_jwks));
+
+    let oidc_routes = OpenApiRouter::new()
+        .routes(routes!(
+            domain::auth::oidc::controller_authorize,
+            domain::auth::oidc::controller_consent
+        ))
+        .routes(routes!(domain::auth::oidc::controller_token))
+        .routes(routes!(domain::auth::oidc::controller_userinfo))
+        .routes(routes!(domain::auth::oidc::controller_openid_configuration))
+        .routes(routes!(domain::auth::oidc::controller_list_consents))
+        .routes(routes!(domain::auth::oidc::controller_revoke_consent));
 
     let user_routes = OpenApiRouter::new()
         .routes(routes!(domain::user::user::controller_get_profile))
@@ -23,14 +75,77 @@ pub fn build_routes() -> OpenApiRouter<AppState> {
         .routes(routes!(domain::user::user::controller_update_user))
         .routes(routes!(domain::user::user::controller_get_user_by_id))
         .routes(routes!(domain::user::user::controller_list_users))
-        .routes(routes!(domain::user::user::controller_delete_user));
+        .routes(routes!(domain::user::user::controller_delete_user))
+        .routes(routes!(domain::user::user::controller_verify_email))
+        .routes(routes!(domain::user::user::controller_resend_verification_email));
+
+    let identity_routes = OpenApiRouter::new()
+        .routes(routes!(domain::identity::identity::controller_start_external_login))
+        .routes(routes!(domain::identity::identity::controller_complete_external_login))
+        .routes(routes!(domain::identity::identity::controller_list_identities))
+        .routes(routes!(domain::identity::identity::controller_start_link_identity))
+        .routes(routes!(domain::identity::identity::controller_complete_link_identity))
+        .routes(routes!(domain::identity::identity::controller_unlink_identity));
+
+    let session_routes = OpenApiRouter::new()
+        .routes(routes!(domain::session::session::controller_list_sessions))
+        .routes(routes!(domain::session::session::controller_revoke_session))
+        .routes(routes!(domain::session::session::controller_revoke_other_sessions));
+
+    let two_factor_routes = OpenApiRouter::new()
+        .routes(routes!(
+            domain::two_factor::two_factor::controller_two_factor_status,
+            domain::two_factor::two_factor::controller_disable_two_factor
+        ))
+        .routes(routes!(domain::two_factor::two_factor::controller_enroll_totp))
+        .routes(routes!(domain::two_factor::two_factor::controller_confirm_totp))
+        .routes(routes!(domain::two_factor::two_factor::controller_regenerate_recovery_codes));
+
+    let admin_routes = OpenApiRouter::new()
+        .routes(routes!(domain::admin::user::controller_admin_reset_two_factor))
+        .routes(routes!(
+            domain::admin::user::controller_admin_create_user,
+            domain::admin::user::controller_admin_list_users
+        ))
+        .routes(routes!(domain::admin::user::controller_admin_force_password_reset))
+        .routes(routes!(domain::admin::user::controller_admin_suspend_user))
+        .routes(routes!(domain::admin::user::controller_admin_reactivate_user))
+        .routes(routes!(domain::admin::user::controller_admin_ban_user))
+        .routes(routes!(domain::admin::user::controller_admin_close_user))
+        .routes(routes!(domain::admin::user::controller_admin_restore_user))
+        .routes(routes!(domain::admin::user::controller_admin_list_user_sessions))
+        .routes(routes!(domain::admin::user::controller_admin_list_status_changes))
+        .routes(routes!(domain::admin::impersonation::controller_admin_start_impersonation))
+        .routes(routes!(domain::admin::impersonation::controller_stop_impersonation))
+        .routes(routes!(domain::admin::oauth_client::controller_admin_create_oauth_client))
+        .routes(routes!(domain::admin::service_account::controller_admin_create_service_account))
+        .routes(routes!(
+            domain::admin::service_account::controller_admin_create_service_account_key,
+            domain::admin::service_account::controller_admin_list_service_account_keys
+        ))
+        .routes(routes!(domain::admin::service_account::controller_admin_revoke_service_account_key));
+
+    let api_key_routes = OpenApiRouter::new()
+        .routes(routes!(
+            domain::api_key::api_key::controller_create_api_key,
+            domain::api_key::api_key::controller_list_api_keys
+        ))
+        .routes(routes!(domain::api_key::api_key::controller_revoke_api_key));
+
+    let oauth_routes = OpenApiRouter::new()
+        .routes(routes!(domain::oauth::oauth::controller_introspect))
+        .routes(routes!(domain::oauth::oauth::controller_revoke));
 
     let address_routes = OpenApiRouter::new()
         .routes(routes!(domain::address::address::controller_create_address))
         .routes(routes!(domain::address::address::controller_update_address))
         .routes(routes!(domain::address::address::controller_get_address_by_id))
         .routes(routes!(domain::address::address::controller_get_addresses_by_user_id))
-        .routes(routes!(domain::address::address::controller_delete_address));
+        .routes(routes!(domain::address::address::controller_delete_address))
+        .routes(routes!(
+            domain::address::address::controller_list_my_addresses,
+            domain::address::address::controller_create_my_address
+        ));
 
     let gateway_routes = OpenApiRouter::new()
         .route("/gateway/health", get(gateway_health_check))
@@ -40,9 +155,16 @@ pub fn build_routes() -> OpenApiRouter<AppState> {
         .route("/gateway/inventory-service/{*path}", any(proxy_to_inventory_service))
         .route("/gateway/notification-service/{*path}", any(proxy_to_notification_service));
 
-    OpenApiRouter::new()
+    OpenApiRouter::with_openapi(ApiDoc::openapi())
         .merge(auth_routes)
         .merge(user_routes)
+        .merge(session_routes)
+        .merge(two_factor_routes)
+        .merge(admin_routes)
+        .merge(oauth_routes)
+        .merge(oidc_routes)
+        .merge(identity_routes)
+        .merge(api_key_routes)
         .merge(address_routes)
         .merge(gateway_routes)
         .merge(server_routes)
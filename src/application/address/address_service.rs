@@ -1,10 +1,13 @@
 use crate::application::address::address_service_interface::AddressServiceInterface;
+use crate::application::policy::ownership_policy;
 use crate::core::error::{AppError, AppResult};
 use crate::domain::address::address::Entity;
 use crate::domain::address::address_repository_interface::AddressRepositoryInterface;
+use crate::domain::user::permission;
 use crate::domain::user::user_repository_interface::UserRepositoryInterface;
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::presentation::address::address::{AddressSerializer, CreateAddressRequest, UpdateAddressRequest};
+use crate::util::claim::UserClaims;
 use rdkafka::producer::FutureProducer;
 use sea_orm::{DatabaseTransaction, IntoActiveModel};
 use std::sync::Arc;
@@ -26,18 +29,24 @@ impl AddressServiceInterface for AddressService {
     async fn create_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         request: CreateAddressRequest,
     ) -> AppResult<bool> {
+        // Policy: addresses are added for the caller unless staff names another user
+        let user_id = request.user_id.unwrap_or(caller.user_id);
+        ownership_policy::authorize_owner(caller, user_id, permission::USERS_WRITE)?;
+
         // Database: Check if user exists
-        let user = crate::domain::user::user::Entity::find_user_by_id(conn, request.user_id).await?;
+        let user = crate::domain::user::user::Entity::find_user_by_id(conn, user_id).await?;
         if user.is_none() {
             return Err(AppError::EntityNotFoundError {
-                detail: format!("User with id {} not found", request.user_id),
+                detail: format!("User with id {} not found", user_id),
             });
         }
 
         // Domain: Create model with validation
         let address = address::address::ModelEx::create_new_address(
+            user_id,
             &request
         ).map_err(
             |e| e,
@@ -55,6 +64,7 @@ impl AddressServiceInterface for AddressService {
     async fn update_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
         request: UpdateAddressRequest,
     ) -> AppResult<bool> {
@@ -64,6 +74,7 @@ impl AddressServiceInterface for AddressService {
             .ok_or_else(|| AppError::EntityNotFoundError {
                 detail: format!("Address with id {} not found", id),
             })?;
+        ownership_policy::authorize_owner(caller, existing_address.user_id, permission::USERS_WRITE)?;
 
         // Domain: Update model with validation
         let updated_model = existing_address.update_from(
@@ -85,6 +96,7 @@ impl AddressServiceInterface for AddressService {
     async fn get_address_by_id(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<AddressSerializer> {
         // Database: Fetch address
@@ -93,6 +105,7 @@ impl AddressServiceInterface for AddressService {
             .ok_or_else(|| AppError::EntityNotFoundError {
                 detail: format!("Address with id {} not found", id),
             })?;
+        ownership_policy::authorize_owner(caller, address.user_id, permission::USERS_READ)?;
 
         Ok(AddressSerializer::from(address))
     }
@@ -100,15 +113,16 @@ impl AddressServiceInterface for AddressService {
     async fn delete_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<bool> {
         // Database: Check if address exists
-        let address = Entity::find_address_by_id(conn, id).await?;
-        if address.is_none() {
-            return Err(AppError::EntityNotFoundError {
+        let address = Entity::find_address_by_id(conn, id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
                 detail: format!("Address with id {} not found", id),
-            });
-        }
+            })?;
+        ownership_policy::authorize_owner(caller, address.user_id, permission::USERS_WRITE)?;
 
         // Database: Soft delete
         Entity::delete_address(conn, id).await?;
@@ -122,8 +136,11 @@ impl AddressServiceInterface for AddressService {
     async fn get_addresses_by_user_id(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         user_id: i64,
     ) -> AppResult<Vec<AddressSerializer>> {
+        ownership_policy::authorize_owner(caller, user_id, permission::USERS_READ)?;
+
         // Database: Fetch addresses for user
         let addresses = Entity::find_addresses_by_user_id(conn, user_id).await?;
 
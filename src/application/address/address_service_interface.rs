@@ -1,17 +1,22 @@
 use crate::core::error::AppResult;
 use crate::presentation::address::address::{AddressSerializer, CreateAddressRequest, UpdateAddressRequest};
+use crate::util::claim::UserClaims;
 use sea_orm::DatabaseTransaction;
 
+/// Every operation is limited to the caller's own addresses, unless the caller holds
+/// `users:read` (reading) or `users:write` (changing).
 pub trait AddressServiceInterface: Send + Sync + 'static {
     async fn create_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         request: CreateAddressRequest,
     ) -> AppResult<bool>;
 
     async fn update_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
         request: UpdateAddressRequest,
     ) -> AppResult<bool>;
@@ -19,18 +24,21 @@ pub trait AddressServiceInterface: Send + Sync + 'static {
     async fn get_address_by_id(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<AddressSerializer>;
 
     async fn delete_address(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<bool>;
 
     async fn get_addresses_by_user_id(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         user_id: i64,
     ) -> AppResult<Vec<AddressSerializer>>;
 }
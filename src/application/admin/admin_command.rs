@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct ChangeUserStatusCommand {
+    /// Why the status changes, e.g. the ticket number. Kept in the status history.
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct SuspendUserCommand {
+    /// Why the account is suspended, e.g. the ticket number. Kept in the status history.
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+    /// UTC time the suspension ends on its own. Suspended until reactivated when absent.
+    pub suspended_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate, IntoParams)]
+pub struct AdminUserListQuery {
+    /// Page number, from 0
+    #[serde(default)]
+    pub page: u64,
+    #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// Also list soft-deleted accounts
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+fn default_page_size() -> u64 {
+    20
+}
@@ -0,0 +1,404 @@
+use crate::application::admin::admin_command::{
+    AdminUserListQuery, ChangeUserStatusCommand, SuspendUserCommand,
+};
+use crate::application::admin::admin_service_interface::AdminServiceInterface;
+use crate::application::after_commit::AfterCommit;
+use crate::application::authen::authen_service::{load_access, AuthenService};
+use crate::application::user::password_policy_service::PasswordPolicyService;
+use crate::application::user::password_policy_service_interface::PasswordPolicyServiceInterface;
+use crate::application::user::user_command::AdminCreateUserCommand;
+use crate::application::user::user_status_service::UserStatusService;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::audit_log::{self, AuditAction};
+use crate::domain::user::audit_log_repository_interface::AuditLogRepositoryInterface;
+use crate::domain::user::role;
+use crate::domain::user::role_repository_interface::RoleRepositoryInterface;
+use crate::domain::user::status_change;
+use crate::domain::user::status_change_repository_interface::StatusChangeRepositoryInterface;
+use crate::domain::user::user::{self, ModelEx as UserModel, Status};
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::domain::user::user_role;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::persistence::redis_client::session;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::admin::admin::{AdminUserSerializer, StatusChangeSerializer};
+use crate::presentation::session::session::SessionSerializer;
+use crate::util::claim::UserClaims;
+use crate::util::password;
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Application service - staff managing user accounts
+pub struct AdminService {
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+    pub authen_service: Arc<AuthenService>,
+    pub password_policy_service: Arc<PasswordPolicyService>,
+    pub user_status_service: Arc<UserStatusService>,
+}
+
+impl AdminService {
+    pub fn new(
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        authen_service: Arc<AuthenService>,
+        password_policy_service: Arc<PasswordPolicyService>,
+        user_status_service: Arc<UserStatusService>,
+    ) -> Self {
+        Self { redis, kafka_producer, authen_service, password_policy_service, user_status_service }
+    }
+
+    async fn find_user(conn: &DatabaseTransaction, user_id: i64) -> AppResult<UserModel> {
+        user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", user_id),
+            })
+    }
+
+    /// Staff may only manage accounts whose permissions they hold themselves, so support
+    /// cannot lock out an admin.
+    async fn ensure_outranks(conn: &DatabaseTransaction, admin: &UserClaims, user_id: i64) -> AppResult<()> {
+        if admin.user_id == user_id {
+            return Err(AppError::BadRequestError("You cannot do this to your own account".to_string()));
+        }
+        outranks(admin, &load_access(conn, user_id).await?.permissions)
+    }
+
+    /// Saves `user_res` in its new status and adds the change to its status history.
+    async fn save_status_change(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_res: &UserModel,
+        previous_status: &Status,
+        reason: &str,
+    ) -> AppResult<AfterCommit> {
+        user::Entity::update_user(conn, user_res.clone().into_active_model()).await?;
+        self.user_status_service
+            .record_status_change(conn, user_res, previous_status, Some(admin.user_id), Some(reason.to_string()))
+            .await
+    }
+
+    async fn audit(
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        action: AuditAction,
+        client: &ClientInfo,
+        detail: Option<String>,
+    ) -> AppResult<()> {
+        audit_log::Entity::create_audit_log(
+            conn,
+            audit_log::ActiveModel::new_audit_log(
+                admin.user_id,
+                user_id,
+                action,
+                client.ip_address.clone(),
+                client.user_agent.clone(),
+                detail,
+            ),
+        )
+        .await
+    }
+
+    /// Drops the cached profile once committed, so the user sees the change at once.
+    fn forget_profile(&self, user_id: i64) -> AfterCommit {
+        let redis = self.redis.clone();
+        let mut after_commit = AfterCommit::new();
+        after_commit.push(async move {
+            let _ = redis.delete_key(&format!("profile:user_id:{}", user_id).into()).await;
+            Ok(())
+        });
+        after_commit
+    }
+
+    /// Mails `user_res` a reset code once committed; before that the account it is for
+    /// may not even exist.
+    fn send_password_reset(&self, user_res: &UserModel, note: &'static str) -> AfterCommit {
+        let (authen_service, user_res) = (self.authen_service.clone(), user_res.clone());
+        let mut after_commit = AfterCommit::new();
+        after_commit.push(async move { authen_service.send_password_reset(&user_res, note).await });
+        after_commit
+    }
+}
+
+fn outranks(admin: &UserClaims, user_permissions: &[String]) -> AppResult<()> {
+    if !user_permissions.iter().all(|permission| admin.has_permission(permission)) {
+        return Err(AppError::PermissionDeniedError(
+            "The user holds permissions you do not have".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl AdminServiceInterface for AdminService {
+    async fn create_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        command: &AdminCreateUserCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        if user::Entity::username_exists(conn, &command.username).await? {
+            return Err(AppError::EntityExistsError {
+                detail: format!("Username {} already exists", command.username),
+            });
+        }
+        if user::Entity::email_exists(conn, &command.email).await? {
+            return Err(AppError::EntityExistsError {
+                detail: format!("Email {} already exists", command.email),
+            });
+        }
+
+        // Nobody may hand out more than they hold
+        let role_res = match command.role.as_deref() {
+            Some(name) => {
+                let role_res = role::Entity::find_role_by_name(conn, name).await?.ok_or_else(|| {
+                    AppError::BadRequestError(format!("Role {name} does not exist"))
+                })?;
+                let permissions = role::Entity::find_permission_names(conn, &[role_res.id]).await?;
+                if !permissions.iter().all(|permission| admin.has_permission(permission)) {
+                    return Err(AppError::PermissionDeniedError(format!(
+                        "Role {name} grants permissions you do not have"
+                    )));
+                }
+                Some(role_res)
+            },
+            None => None,
+        };
+
+        let mut new_user = user::ModelEx::create_admin_user(command)?;
+        if let Some(ref new_password) = command.password {
+            self.password_policy_service
+                .validate_new_password(conn, new_password, &command.username, &command.email, None)
+                .await?;
+            new_user = new_user.change_password(password::hash(new_password.clone()).await?);
+        }
+        user::Entity::create_user(conn, new_user.into_active_model()).await?;
+        let created = user::Entity::find_user_by_username(conn, &command.username)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User {} not found after insert", command.username),
+            })?;
+
+        if let Some(ref role_res) = role_res {
+            role::Entity::assign_role(conn, user_role::ActiveModel::new_user_role(created.id, role_res.id))
+                .await?;
+        }
+        let after_commit = match created.password {
+            Some(ref hashed_password) => {
+                self.password_policy_service.remember_password(conn, created.id, hashed_password).await?;
+                AfterCommit::new()
+            },
+            None => self
+                .send_password_reset(&created, "An account was created for you, choose a password to sign in."),
+        };
+        Self::audit(
+            conn,
+            admin,
+            created.id,
+            AuditAction::UserCreated,
+            client,
+            role_res.map(|role_res| format!("role {}", role_res.name)),
+        )
+        .await?;
+
+        log::info!("Admin {} created user {}.", admin.user_id, created.id);
+        Ok((created.into(), after_commit))
+    }
+
+    async fn force_password_reset(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        client: &ClientInfo,
+    ) -> AppResult<AfterCommit> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?;
+        if user_res.is_deleted || user_res.is_service_account {
+            return Err(AppError::BadRequestError("This account has no password to reset".to_string()));
+        }
+
+        let user_res = user_res.clear_password();
+        user::Entity::update_user(conn, user_res.clone().into_active_model()).await?;
+        Self::audit(conn, admin, user_id, AuditAction::PasswordResetForced, client, None).await?;
+
+        let mut after_commit = AfterCommit::new();
+        let redis = self.redis.clone();
+        after_commit.push(async move { session::revoke_all_sessions(&redis, user_id, None).await.map(|_| ()) });
+        after_commit.append(self.send_password_reset(
+            &user_res,
+            "An administrator asked you to choose a new password, your previous one no longer works.",
+        ));
+
+        log::info!("Admin {} forced a password reset of user {user_id}.", admin.user_id);
+        Ok(after_commit)
+    }
+
+    async fn suspend_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &SuspendUserCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?;
+        let previous_status = user_res.status.clone();
+        let user_res = user_res.suspend(command.suspended_until)?;
+        let after_commit =
+            self.save_status_change(conn, admin, &user_res, &previous_status, &command.reason).await?;
+        Self::audit(conn, admin, user_id, AuditAction::UserSuspended, client, Some(command.reason.clone()))
+            .await?;
+
+        log::info!("Admin {} suspended user {user_id}.", admin.user_id);
+        Ok((user_res.into(), after_commit))
+    }
+
+    async fn reactivate_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?;
+        let previous_status = user_res.status.clone();
+        let user_res = user_res.reactivate()?;
+        let after_commit =
+            self.save_status_change(conn, admin, &user_res, &previous_status, &command.reason).await?;
+        Self::audit(conn, admin, user_id, AuditAction::UserReactivated, client, Some(command.reason.clone()))
+            .await?;
+
+        log::info!("Admin {} reactivated user {user_id}.", admin.user_id);
+        Ok((user_res.into(), after_commit))
+    }
+
+    async fn ban_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?;
+        let previous_status = user_res.status.clone();
+        let user_res = user_res.ban()?;
+        let after_commit =
+            self.save_status_change(conn, admin, &user_res, &previous_status, &command.reason).await?;
+        Self::audit(conn, admin, user_id, AuditAction::UserBanned, client, Some(command.reason.clone()))
+            .await?;
+
+        log::info!("Admin {} banned user {user_id}.", admin.user_id);
+        Ok((user_res.into(), after_commit))
+    }
+
+    async fn close_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?;
+        let previous_status = user_res.status.clone();
+        let user_res = user_res.close()?;
+        let after_commit =
+            self.save_status_change(conn, admin, &user_res, &previous_status, &command.reason).await?;
+        Self::audit(conn, admin, user_id, AuditAction::UserClosed, client, Some(command.reason.clone()))
+            .await?;
+
+        log::info!("Admin {} closed user {user_id}.", admin.user_id);
+        Ok((user_res.into(), after_commit))
+    }
+
+    async fn list_users(
+        &self,
+        conn: &DatabaseTransaction,
+        query: &AdminUserListQuery,
+    ) -> AppResult<Vec<AdminUserSerializer>> {
+        let users = if query.include_deleted {
+            user::Entity::list_all_users(conn, query.page, query.page_size).await?
+        } else {
+            user::Entity::list_users(conn, query.page, query.page_size).await?
+        };
+        Ok(users.into_iter().map(AdminUserSerializer::from).collect())
+    }
+
+    async fn restore_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)> {
+        Self::ensure_outranks(conn, admin, user_id).await?;
+        let user_res = Self::find_user(conn, user_id).await?.restore()?;
+        user::Entity::update_user(conn, user_res.clone().into_active_model()).await?;
+        Self::audit(conn, admin, user_id, AuditAction::UserRestored, client, None).await?;
+
+        log::info!("Admin {} restored user {user_id}.", admin.user_id);
+        Ok((user_res.into(), self.forget_profile(user_id)))
+    }
+
+    async fn list_sessions(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<SessionSerializer>> {
+        Self::find_user(conn, user_id).await?;
+        let sessions = session::list_sessions(&self.redis, user_id).await?;
+        // None of them is the caller's
+        Ok(sessions
+            .into_iter()
+            .map(|record| SessionSerializer::from_record(record, &Uuid::nil()))
+            .collect())
+    }
+
+    async fn list_status_changes(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<StatusChangeSerializer>> {
+        Self::find_user(conn, user_id).await?;
+        let changes = status_change::Entity::list_status_changes(conn, user_id).await?;
+        Ok(changes.into_iter().map(StatusChangeSerializer::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::claim::Access;
+    use std::time::Duration;
+
+    fn admin_with(permissions: &[&str]) -> UserClaims {
+        UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4()).with_access(&Access {
+            roles: vec!["support".to_string()],
+            permissions: permissions.iter().map(|permission| permission.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_admin_outranks_users_whose_permissions_it_holds() {
+        let admin = admin_with(&["users:read", "users:write"]);
+        assert!(outranks(&admin, &[]).is_ok());
+        assert!(outranks(&admin, &["users:read".to_string()]).is_ok());
+        assert!(matches!(
+            outranks(&admin, &["users:read".to_string(), "roles:write".to_string()]),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+    }
+}
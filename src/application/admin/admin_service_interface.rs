@@ -0,0 +1,101 @@
+use crate::application::admin::admin_command::{
+    AdminUserListQuery, ChangeUserStatusCommand, SuspendUserCommand,
+};
+use crate::application::after_commit::AfterCommit;
+use crate::application::user::user_command::AdminCreateUserCommand;
+use crate::core::error::AppResult;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::admin::admin::{AdminUserSerializer, StatusChangeSerializer};
+use crate::presentation::session::session::SessionSerializer;
+use crate::util::claim::UserClaims;
+use sea_orm::DatabaseTransaction;
+
+/// Account management for staff. Every change is written to the audit log with `admin`
+/// as the actor, and staff may not act on accounts holding permissions they lack.
+pub trait AdminServiceInterface: Send + Sync + 'static {
+    async fn create_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        command: &AdminCreateUserCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    /// Invalidates the password. The returned effects sign the user out everywhere and
+    /// mail a reset code once the transaction is committed.
+    async fn force_password_reset(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        client: &ClientInfo,
+    ) -> AppResult<AfterCommit>;
+
+    /// Locks the account out, for good or until the given time. The returned effects sign it
+    /// out everywhere once the transaction is committed.
+    async fn suspend_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &SuspendUserCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    /// Lifts a suspension, deactivation or ban.
+    async fn reactivate_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    /// Bars the account until staff reactivate it, signing it out like a suspension.
+    async fn ban_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    /// Closes the account for good, signing it out like a suspension.
+    async fn close_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &ChangeUserStatusCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    async fn list_users(
+        &self,
+        conn: &DatabaseTransaction,
+        query: &AdminUserListQuery,
+    ) -> AppResult<Vec<AdminUserSerializer>>;
+
+    async fn restore_user(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        client: &ClientInfo,
+    ) -> AppResult<(AdminUserSerializer, AfterCommit)>;
+
+    async fn list_sessions(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<SessionSerializer>>;
+
+    /// Newest first.
+    async fn list_status_changes(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<StatusChangeSerializer>>;
+}
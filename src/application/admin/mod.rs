@@ -0,0 +1,3 @@
+pub mod admin_command;
+pub mod admin_service;
+pub mod admin_service_interface;
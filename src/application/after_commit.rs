@@ -0,0 +1,82 @@
+//! Effects outside the database, such as ending sessions or sending mail, that may only
+//! happen once the change justifying them is committed. Services collect them while they
+//! work in the caller's transaction and hand them back; the controller runs them after
+//! `tx.commit()` and simply drops them when it rolls back.
+
+use crate::core::error::AppResult;
+use futures::future::BoxFuture;
+use std::future::Future;
+
+#[must_use = "run it once the transaction has committed"]
+#[derive(Default)]
+pub struct AfterCommit {
+    effects: Vec<BoxFuture<'static, AppResult<()>>>,
+}
+
+impl AfterCommit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<F>(&mut self, effect: F)
+    where
+        F: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        self.effects.push(Box::pin(effect));
+    }
+
+    pub fn append(&mut self, mut other: AfterCommit) {
+        self.effects.append(&mut other.effects);
+    }
+
+    /// Runs every effect in order. The change they follow from is already committed, so a
+    /// failing effect does not keep the others from running; the first error is returned.
+    pub async fn run(self) -> AppResult<()> {
+        let mut result = Ok(());
+        for effect in self.effects {
+            if let Err(err) = effect.await {
+                log::error!("Failed to run an effect after commit: {err:?}");
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_every_effect_runs_and_the_first_error_is_returned() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        let mut after_commit = AfterCommit::new();
+        for fails in [false, true, false] {
+            let ran = ran.clone();
+            after_commit.push(async move {
+                ran.fetch_add(1, Ordering::SeqCst);
+                match fails {
+                    true => Err(AppError::BadRequestError("first".to_string())),
+                    false => Ok(()),
+                }
+            });
+        }
+        let mut later = AfterCommit::new();
+        later.push(async { Err(AppError::BadRequestError("second".to_string())) });
+        after_commit.append(later);
+
+        let err = after_commit.run().await.unwrap_err();
+        assert_eq!(ran.load(Ordering::SeqCst), 3);
+        assert!(matches!(err, AppError::BadRequestError(detail) if detail == "first"));
+    }
+
+    #[tokio::test]
+    async fn test_nothing_to_run_is_ok() {
+        assert!(AfterCommit::new().run().await.is_ok());
+    }
+}
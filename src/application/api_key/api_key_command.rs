@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct CreateApiKeyCommand {
+    #[validate(length(min = 2, max = 100))]
+    pub name: String,
+    /// Out of `read` (safe methods) and `write` (every other method)
+    #[validate(length(min = 1, max = 2))]
+    pub scopes: Vec<String>,
+    /// Days until the key stops working. The key never expires when absent.
+    #[validate(range(min = 1, max = 365))]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct CreateServiceAccountCommand {
+    #[validate(length(min = 3, max = 50))]
+    pub username: String,
+    /// Shown wherever a person's name would be
+    #[validate(length(min = 1, max = 100))]
+    pub display_name: String,
+}
@@ -0,0 +1,170 @@
+use crate::application::api_key::api_key_command::{CreateApiKeyCommand, CreateServiceAccountCommand};
+use crate::application::api_key::api_key_service_interface::ApiKeyServiceInterface;
+use crate::application::authen::authen_service::load_access;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::api_key::{self, KEY_PREFIX};
+use crate::domain::user::api_key_repository_interface::ApiKeyRepositoryInterface;
+use crate::domain::user::user;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::api_key::api_key::{
+    ApiKeyCreatedResponse, ApiKeySerializer, ServiceAccountSerializer,
+};
+use crate::util::claim::UserClaims;
+use crate::util::{hash, random};
+use axum::http::Method;
+use chrono::{Duration, Utc};
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Characters of the key after `j18_` that are kept in clear to tell keys apart
+const KEY_LOOKUP_LEN: usize = 8;
+const KEY_SECRET_LEN: usize = 40;
+
+/// Application service - API keys for scripts, other services and service accounts
+pub struct ApiKeyService {
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+}
+
+impl ApiKeyService {
+    pub fn new(redis: Arc<RedisConnectionPool>, kafka_producer: Arc<FutureProducer>) -> Self {
+        Self { redis, kafka_producer }
+    }
+
+    /// `j18_<lookup>_<secret>`; returns the key and its displayable prefix.
+    fn generate_key() -> (String, String) {
+        let prefix = format!("{KEY_PREFIX}{}", random::generate_random_string(KEY_LOOKUP_LEN));
+        let key = format!("{prefix}_{}", random::generate_random_string(KEY_SECRET_LEN));
+        (key, prefix)
+    }
+}
+
+impl ApiKeyServiceInterface for ApiKeyService {
+    async fn authenticate(
+        &self,
+        conn: &DatabaseTransaction,
+        key: &str,
+        method: &Method,
+    ) -> AppResult<UserClaims> {
+        let invalid_key = || AppError::UnauthorizedError("Invalid API key".to_string());
+        let api_key = api_key::Entity::find_api_key_by_hash(conn, &hash::sha256_hex(key))
+            .await?
+            .filter(api_key::Model::is_usable)
+            .ok_or_else(invalid_key)?;
+        let owner = user::Entity::find_user_by_id(conn, api_key.user_id)
+            .await?
+            .filter(|owner| !owner.is_deleted)
+            .ok_or_else(invalid_key)?;
+        owner.ensure_can_sign_in()?;
+        if !api_key.allows_method(method) {
+            return Err(AppError::PermissionDeniedError(format!(
+                "API key scopes `{}` do not allow {method} requests",
+                api_key.scopes
+            )));
+        }
+
+        api_key::Entity::touch_api_key(conn, api_key.id).await?;
+        // A key acts with its owner's permissions, limited by its scopes
+        Ok(UserClaims::from_api_key(
+            owner.id,
+            api_key.id,
+            &api_key.scopes,
+            api_key.expires_at.map(|expires_at| expires_at.and_utc().timestamp()),
+        )
+        .with_access(&load_access(conn, owner.id).await?))
+    }
+
+    async fn create_key(
+        &self,
+        conn: &DatabaseTransaction,
+        caller: &UserClaims,
+        owner_id: i64,
+        command: &CreateApiKeyCommand,
+    ) -> AppResult<ApiKeyCreatedResponse> {
+        // A leaked key must not be able to outlive its own revocation
+        if caller.is_api_key() {
+            return Err(AppError::PermissionDeniedError(
+                "API keys cannot be created with an API key".to_string(),
+            ));
+        }
+        let scopes = api_key::normalize_scopes(&command.scopes)?;
+        let expires_at = command
+            .expires_in_days
+            .map(|days| Utc::now().naive_utc() + Duration::days(days));
+
+        let (key, prefix) = Self::generate_key();
+        let id = api_key::Entity::create_api_key(
+            conn,
+            api_key::ActiveModel::new_api_key(
+                owner_id,
+                command.name.trim().to_string(),
+                prefix,
+                hash::sha256_hex(&key),
+                scopes,
+                expires_at,
+            ),
+        )
+        .await?;
+        let created = api_key::Entity::find_api_key_by_id(conn, id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: "API key not found after insert".to_string(),
+            })?;
+
+        log::info!("User {} created API key {} for user {owner_id}.", caller.user_id, created.prefix);
+        Ok(ApiKeyCreatedResponse { key, api_key: created.into() })
+    }
+
+    async fn list_keys(&self, conn: &DatabaseTransaction, owner_id: i64) -> AppResult<Vec<ApiKeySerializer>> {
+        let api_keys = api_key::Entity::list_api_keys(conn, owner_id).await?;
+        Ok(api_keys.into_iter().map(ApiKeySerializer::from).collect())
+    }
+
+    async fn revoke_key(&self, conn: &DatabaseTransaction, owner_id: i64, key_id: i64) -> AppResult<()> {
+        if !api_key::Entity::revoke_api_key(conn, owner_id, key_id).await? {
+            return Err(AppError::EntityNotFoundError {
+                detail: format!("Active API key with id {} not found", key_id),
+            });
+        }
+        log::info!("API key {key_id} of user {owner_id} revoked.");
+        Ok(())
+    }
+
+    async fn create_service_account(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &CreateServiceAccountCommand,
+    ) -> AppResult<ServiceAccountSerializer> {
+        if user::Entity::username_exists(conn, &command.username).await? {
+            return Err(AppError::EntityExistsError {
+                detail: format!("Username {} already exists", command.username),
+            });
+        }
+        let account = user::ModelEx::create_service_account(&command.username, &command.display_name)?;
+        user::Entity::create_user(conn, account.into_active_model()).await?;
+        let account = user::Entity::find_user_by_username(conn, command.username.trim())
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("Service account {} not found after insert", command.username),
+            })?;
+
+        log::info!("Created service account {}.", account.id);
+        Ok(account.into())
+    }
+
+    async fn find_service_account(
+        &self,
+        conn: &DatabaseTransaction,
+        id: i64,
+    ) -> AppResult<ServiceAccountSerializer> {
+        user::Entity::find_user_by_id(conn, id)
+            .await?
+            .filter(|account| account.is_service_account && !account.is_deleted)
+            .map(ServiceAccountSerializer::from)
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("Service account with id {} not found", id),
+            })
+    }
+}
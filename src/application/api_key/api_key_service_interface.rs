@@ -0,0 +1,44 @@
+use crate::application::api_key::api_key_command::{CreateApiKeyCommand, CreateServiceAccountCommand};
+use crate::core::error::AppResult;
+use crate::presentation::api_key::api_key::{
+    ApiKeyCreatedResponse, ApiKeySerializer, ServiceAccountSerializer,
+};
+use crate::util::claim::UserClaims;
+use axum::http::Method;
+use sea_orm::DatabaseTransaction;
+
+pub trait ApiKeyServiceInterface: Send + Sync + 'static {
+    /// Resolves a presented key to the claims of its owner, if the key allows `method`.
+    async fn authenticate(
+        &self,
+        conn: &DatabaseTransaction,
+        key: &str,
+        method: &Method,
+    ) -> AppResult<UserClaims>;
+
+    /// Issues a key to `owner_id`. `caller` must have signed in, keys cannot mint keys.
+    async fn create_key(
+        &self,
+        conn: &DatabaseTransaction,
+        caller: &UserClaims,
+        owner_id: i64,
+        command: &CreateApiKeyCommand,
+    ) -> AppResult<ApiKeyCreatedResponse>;
+
+    async fn list_keys(&self, conn: &DatabaseTransaction, owner_id: i64) -> AppResult<Vec<ApiKeySerializer>>;
+
+    async fn revoke_key(&self, conn: &DatabaseTransaction, owner_id: i64, key_id: i64) -> AppResult<()>;
+
+    async fn create_service_account(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &CreateServiceAccountCommand,
+    ) -> AppResult<ServiceAccountSerializer>;
+
+    /// Fails unless `id` is a live service account.
+    async fn find_service_account(
+        &self,
+        conn: &DatabaseTransaction,
+        id: i64,
+    ) -> AppResult<ServiceAccountSerializer>;
+}
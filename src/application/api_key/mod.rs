@@ -0,0 +1,3 @@
+pub mod api_key_command;
+pub mod api_key_service;
+pub mod api_key_service_interface;
@@ -7,15 +7,19 @@ use validator::Validate;
 #[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
 #[serde(tag = "type")]
 pub struct LoginByEmailCommand {
-    #[validate(length(min = 3, max = 50))]
-    pub username: String,
+    /// Username or email address, matched case-insensitively
+    #[serde(alias = "username", alias = "email")]
+    #[validate(length(min = 3, max = 254))]
+    pub identifier: String,
     #[validate(length(min = 8))]
     pub password: String,
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
 }
 
 impl LoginByEmailCommand {
-    pub fn get_username(&self) -> &str {
-        self.username.as_ref()
+    pub fn get_identifier(&self) -> &str {
+        self.identifier.as_ref()
     }
 
     pub fn get_password(&self) -> &str {
@@ -23,8 +27,19 @@ impl LoginByEmailCommand {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema, Validate, IntoParams)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct LoginTwoFactorCommand {
+    #[validate(length(min = 20))]
+    pub challenge_token: String,
+    /// A current TOTP code or an unused recovery code
+    #[validate(length(min = 6, max = 20))]
+    pub code: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate, IntoParams)]
 pub struct RefreshTokenCommand {
+    /// Left out by cookie session clients, whose refresh token travels in a cookie
+    #[serde(default)]
     #[validate(length(min = 30))]
     pub token: String,
 }
@@ -45,4 +60,42 @@ impl ForgetPasswordCommand {
     pub fn get_email(&self) -> &str {
         self.email.as_ref()
     }
-}
\ No newline at end of file
+}
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ResetPasswordCommand {
+    #[validate(length(min = 20))]
+    pub token: String,
+    #[validate(length(min = 8))]
+    pub new_password: String,
+}
+
+impl ResetPasswordCommand {
+    pub fn get_token(&self) -> &str {
+        self.token.as_ref()
+    }
+
+    pub fn get_new_password(&self) -> &str {
+        self.new_password.as_ref()
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ChangePasswordCommand {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+    #[validate(length(min = 1))]
+    pub new_password: String,
+    /// Sign out every other device, keeping only the session making this request
+    #[serde(default)]
+    pub revoke_other_sessions: bool,
+}
+
+/// Proves the user is still at the keyboard with either the password or a second factor.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ReauthenticateCommand {
+    #[validate(length(min = 1))]
+    pub password: Option<String>,
+    /// A current TOTP code or an unused recovery code
+    #[validate(length(min = 6, max = 20))]
+    pub code: Option<String>,
+}
@@ -1,91 +1,704 @@
+use crate::application::after_commit::AfterCommit;
 use crate::application::authen::authen_service_interface::AuthenServiceInterface;
+use crate::core::configure::app::AppConfig;
 use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::application::two_factor::two_factor_service::TwoFactorService;
+use crate::application::user::password_policy_service::PasswordPolicyService;
+use crate::application::user::password_policy_service_interface::PasswordPolicyServiceInterface;
+use crate::application::user::user_status_service::UserStatusService;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
+use crate::application::two_factor::two_factor_service_interface::TwoFactorServiceInterface;
+use crate::infrastructure::persistence::redis_client::login_guard::{self, LoginSubject};
+use crate::infrastructure::persistence::redis_client::{session, two_factor};
+use crate::infrastructure::third_party::mail::{MailMessage, MailSender};
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::RedisKey;
 use crate::infrastructure::third_party::token;
-use crate::presentation::authen::authen::TokenResponse;
-use crate::util::password;
+use crate::presentation::authen::authen::{LoginResponse, TokenResponse};
+use crate::util::claim::{Access, Authentication, UserClaims, AMR_MFA, AMR_OTP, AMR_PASSWORD};
+use crate::util::constant::{
+    EXPIRE_FORGET_PASS_CODE_SECS, EXPIRE_LOGIN_CHALLENGE_SECS, MAX_LOGIN_CHALLENGE_ATTEMPTS,
+    PASSWORD_HASHER, REFRESH_TOKEN_KEYS,
+};
+use crate::util::{hash, password, random};
+use once_cell::sync::Lazy;
 use rdkafka::producer::FutureProducer;
-use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter};
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
 use std::sync::Arc;
 use uuid::Uuid;
-use crate::application::authen::authen_command::LoginByEmailCommand;
+use crate::application::authen::authen_command::{
+    ChangePasswordCommand, ForgetPasswordCommand, LoginByEmailCommand, LoginTwoFactorCommand,
+    ReauthenticateCommand, ResetPasswordCommand,
+};
+use crate::domain::user::security_event::{self, SecurityEventKind};
+use crate::domain::user::security_event_repository_interface::SecurityEventRepositoryInterface;
+use crate::domain::user::role;
+use crate::domain::user::role_repository_interface::RoleRepositoryInterface;
 use crate::domain::user::user;
+use crate::domain::user::user::ModelEx as UserModel;
 use crate::domain::user::user_repository_interface::UserRepositoryInterface;
 
+/// Roles and permissions the user's tokens carry.
+pub async fn load_access(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Access> {
+    let roles = role::Entity::find_roles_of_user(conn, user_id).await?;
+    let role_ids: Vec<i64> = roles.iter().map(|role| role.id).collect();
+    Ok(Access {
+        permissions: role::Entity::find_permission_names(conn, &role_ids).await?,
+        roles: roles.into_iter().map(|role| role.name).collect(),
+    })
+}
+
 pub struct AuthenService {
+    pub config: Arc<AppConfig>,
     pub redis: Arc<RedisConnectionPool>,
     pub kafka_producer: Arc<FutureProducer>,
+    pub mail_sender: Arc<dyn MailSender>,
+    pub two_factor_service: Arc<TwoFactorService>,
+    pub password_policy_service: Arc<PasswordPolicyService>,
+    pub user_status_service: Arc<UserStatusService>,
 }
 
 impl AuthenService {
-    pub fn new(redis: Arc<RedisConnectionPool>, kafka_producer: Arc<FutureProducer>) -> Self {
-        Self { redis, kafka_producer }
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        mail_sender: Arc<dyn MailSender>,
+        two_factor_service: Arc<TwoFactorService>,
+        password_policy_service: Arc<PasswordPolicyService>,
+        user_status_service: Arc<UserStatusService>,
+    ) -> Self {
+        Self {
+            config,
+            redis,
+            kafka_producer,
+            mail_sender,
+            two_factor_service,
+            password_policy_service,
+            user_status_service,
+        }
+    }
+
+    /// Opens a new device session and issues its first token pair.
+    async fn start_session(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        authentication: &Authentication,
+        device_name: Option<String>,
+        client: &ClientInfo,
+    ) -> AppResult<TokenResponse> {
+        let access = load_access(conn, user_id).await?;
+        let session = session::create_session(&self.redis, user_id, device_name, client).await?;
+
+        let refresh_jti = Uuid::new_v4();
+        session::store_refresh_token(&self.redis, &session.session_id, &refresh_jti).await?;
+
+        token::service_generate_tokens(
+            &user_id,
+            &session.session_id,
+            &refresh_jti,
+            authentication,
+            &access,
+        )
+    }
+
+    /// Checks the account and session behind a refresh token and swaps its single-use id
+    /// for a new one. Returns the user and the id the new refresh token must carry.
+    async fn rotate_refresh_token(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+    ) -> AppResult<(UserModel, Uuid)> {
+        claims.require_current_tenant()?;
+        let user_res = user::Entity::find_user_by_id(conn, claims.user_id)
+            .await?
+            .ok_or(AppError::InvalidSessionError("User not found".to_string()))?;
+        // Checked first, so a blocked account learns why rather than that its session is gone
+        self.user_status_service.ensure_can_sign_in(conn, &user_res).await?;
+
+        // The session the token belongs to must still be the active one
+        session::is_valid_session(&self.redis, claims, false).await?;
+
+        // Each refresh token is single-use: presenting a spent one revokes the whole session
+        let new_jti = Uuid::new_v4();
+        if !session::rotate_refresh_token(&self.redis, &claims.sid, &claims.jti, &new_jti).await? {
+            log::warn!(
+                "Refresh token reuse detected for user {} session {}, revoking session.",
+                claims.user_id,
+                claims.sid
+            );
+            self.logout(claims.user_id, &claims.sid).await?;
+            return Err(AppError::InvalidSessionError(
+                "Refresh token has already been used".to_string(),
+            ));
+        }
+        Ok((user_res, new_jti))
+    }
+
+    /// Mails the user a single-use code to choose a new password with. `note` tells them
+    /// why they got it.
+    pub async fn send_password_reset(&self, user_res: &UserModel, note: &str) -> AppResult<()> {
+        let token = random::generate_random_string(32);
+        self.redis
+            .set_key_with_expiry::<String>(
+                &forget_password_key(&token).into(),
+                user_res.id.to_string(),
+                EXPIRE_FORGET_PASS_CODE_SECS.as_secs() as i64,
+            )
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+
+        self.mail_sender
+            .send(MailMessage {
+                to: user_res.email.clone(),
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Use this code to reset your password: {token}\n\n\
+                     The code expires in {} minutes and can be used only once. {note}",
+                    EXPIRE_FORGET_PASS_CODE_SECS.as_secs() / 60
+                ),
+            })
+            .await
+    }
+
+    /// Last step of every first factor (password, external provider, magic link, ...):
+    /// checks the account may sign in, then asks for the second factor or opens the session.
+    pub async fn complete_login(
+        &self,
+        conn: &DatabaseTransaction,
+        user_res: &UserModel,
+        authentication: Authentication,
+        device_name: Option<String>,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse> {
+        self.user_status_service.ensure_can_sign_in(conn, user_res).await?;
+        if self.config.auth.require_verified_email && user_res.email_verified_at.is_none() {
+            return Err(AppError::UserNotActiveError(
+                "Email address has not been verified".to_string(),
+            ));
+        }
+
+        // Accounts with 2FA only get a short-lived challenge until the second factor is shown
+        if user_res.has_two_factor() {
+            let challenge_token = two_factor::create_login_challenge(
+                &self.redis,
+                &two_factor::LoginChallenge { user_id: user_res.id, device_name, authentication },
+            )
+            .await?;
+            return Ok(LoginResponse::Code {
+                message: "Two-factor authentication code required".to_string(),
+                expire_in: EXPIRE_LOGIN_CHALLENGE_SECS.as_secs(),
+                challenge_token,
+            });
+        }
+
+        let res = self.start_session(conn, user_res.id, &authentication, device_name, client).await?;
+        Ok(LoginResponse::Token(res))
     }
+
+    /// Stores the event on the account; the mail telling the user about it is returned to
+    /// send after commit. A failed mail is only logged, the action it reports on has
+    /// already happened.
+    pub async fn record_security_event(
+        &self,
+        conn: &DatabaseTransaction,
+        user_res: &UserModel,
+        kind: SecurityEventKind,
+        client: &ClientInfo,
+        detail: Option<String>,
+    ) -> AppResult<AfterCommit> {
+        security_event::Entity::create_security_event(
+            conn,
+            security_event::ActiveModel::new_security_event(
+                user_res.id,
+                kind.clone(),
+                client.ip_address.clone(),
+                client.user_agent.clone(),
+                detail.clone(),
+            ),
+        )
+        .await?;
+
+        let mail_sender = self.mail_sender.clone();
+        let message = security_event_mail(user_res, &kind, client, detail);
+        let user_id = user_res.id;
+        let mut after_commit = AfterCommit::new();
+        after_commit.push(async move {
+            if let Err(err) = mail_sender.send(message).await {
+                log::error!("Failed to notify user {user_id} of {kind:?}: {err:?}");
+            }
+            Ok(())
+        });
+        Ok(after_commit)
+    }
+}
+
+fn security_event_mail(
+    user_res: &UserModel,
+    kind: &SecurityEventKind,
+    client: &ClientInfo,
+    detail: Option<String>,
+) -> MailMessage {
+    MailMessage {
+        to: user_res.email.clone(),
+        subject: kind.title().to_string(),
+        body: format!(
+            "{}{}.\n\nFrom: {}\nDevice: {}\n\n\
+             If this was not you, reset your password right away.",
+            kind.title(),
+            detail.map(|detail| format!(" ({detail})")).unwrap_or_default(),
+            client.ip_address.as_deref().unwrap_or("unknown address"),
+            client.user_agent.as_deref().unwrap_or("unknown device"),
+        ),
+    }
+}
+
+/// Wrong current passwords count like failed logins, but while no lock or backoff kicks
+/// in the caller is told about the password only; its session is fine.
+fn wrong_current_password(throttled: AppError) -> AppError {
+    match throttled {
+        AppError::InvalidCredentialsError(_) => {
+            AppError::BadRequestError("The current password is not correct".to_string())
+        },
+        throttled => throttled,
+    }
+}
+
+/// Checked against when the username does not exist, so both failures take as long.
+static DUMMY_PASSWORD_HASH: Lazy<String> =
+    Lazy::new(|| {
+        hash::argon_hash(&PASSWORD_HASHER, random::generate_random_string(32)).unwrap_or_default()
+    });
+
+/// Scope a client's refresh token keeps. A refresh token only works for the client it
+/// was issued to, and keeps exactly the access the user granted it.
+fn delegated_scope<'a>(claims: &'a UserClaims, client_id: &str) -> Option<&'a str> {
+    match (claims.azp.as_deref(), claims.scope.as_deref()) {
+        (Some(azp), Some(scope)) if azp == client_id => Some(scope),
+        _ => None,
+    }
+}
+
+fn forget_password_key(token: &str) -> String {
+    format!("forget_password:token:{token}")
 }
 
 impl AuthenServiceInterface for AuthenService {
     async fn login_by_email(
         &self,
         conn: &DatabaseTransaction,
-        req: &LoginByEmailCommand
-    ) -> AppResult<TokenResponse> {
-        // Find user by username
-        let user_res = match user::Entity::find_user_by_username(
-            conn,
-            req.get_username(),
-        ).await
-        {
-            Ok(result) => result.ok_or(
-                AppError::BadRequestError("User not found".to_string())
-            )?,
-            Err(err) => {
-                return Err(err);
+        req: &LoginByEmailCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse> {
+        // Find user by username or email
+        let user_res = user::Entity::find_user_by_identifier(conn, req.get_identifier())
+            .await?
+            .filter(|user_res| !user_res.is_deleted);
+        // Username and email of one account share its failure budget
+        let subject = LoginSubject::new(user_res.as_ref().map(|user_res| user_res.id), req.get_identifier());
+        let ip_address = client.ip_address.as_deref();
+        login_guard::check_login_allowed(&self.redis, &subject, ip_address).await?;
+        let hashed_password = user_res
+            .as_ref()
+            .and_then(|user_res| user_res.password.clone())
+            .unwrap_or_else(|| DUMMY_PASSWORD_HASH.clone());
+        let is_password_correct =
+            password::verify(req.get_password().to_string(), hashed_password).await.is_ok();
+
+        // Unknown user and wrong password answer the same way
+        let user_res = match user_res {
+            Some(user_res) if is_password_correct => user_res,
+            _ => {
+                return Err(login_guard::record_login_failure(&self.redis, &subject, ip_address).await?)
             },
         };
+        login_guard::clear_login_failures(&self.redis, &subject).await?;
 
-        match password::verify(req.get_password().to_string(), user_res.password.unwrap_or_default()).await {
-            Ok(_) => (),
-            Err(err) => return Err(err),
-        }
+        // The plain password is only at hand now, so imported or weaker hashes are upgraded here
+        let user_res = match user_res.password.as_deref() {
+            Some(stored) if password::needs_rehash(stored) => {
+                let hashed_password = password::hash(req.get_password().to_string()).await?;
+                user::Entity::update_user(
+                    conn,
+                    user_res.clone().change_password(hashed_password.clone()).into_active_model(),
+                )
+                .await?;
+                // Imported hashes never went through the history, so the reuse check starts here
+                self.password_policy_service.remember_password(conn, user_res.id, &hashed_password).await?;
+                log::info!("Upgraded the password hash of user {}.", user_res.id);
+                user_res.change_password(hashed_password)
+            },
+            _ => user_res,
+        };
 
-        let user_uuid = Uuid::new_v4();
-        match &self
+        self.complete_login(
+            conn,
+            &user_res,
+            Authentication::now(&[AMR_PASSWORD]),
+            req.device_name.clone(),
+            client,
+        )
+        .await
+    }
+
+    async fn login_two_factor(
+        &self,
+        conn: &DatabaseTransaction,
+        req: &LoginTwoFactorCommand,
+        client: &ClientInfo,
+    ) -> AppResult<TokenResponse> {
+        let invalid_challenge = || {
+            AppError::UnauthorizedError("Login challenge is invalid or has expired".to_string())
+        };
+        let challenge = two_factor::find_login_challenge(&self.redis, &req.challenge_token)
+            .await?
+            .ok_or_else(invalid_challenge)?;
+
+        // Each challenge allows a few guesses, then the password step must be repeated
+        let attempts = self
             .redis
-            .set_key_with_expiry::<String>(
-                &format!("profile:user_id:{}", user_res.id).to_string().into(),
-                user_uuid.to_string().into(),
-                3600,
+            .increment_key(
+                &two_factor::login_challenge_attempts_key(&req.challenge_token).into(),
+                EXPIRE_LOGIN_CHALLENGE_SECS.as_secs() as i64,
             )
             .await
-        {
-            Ok(session_id) => session_id,
-            Err(err) => return Err(AppError::BadRequestError(err.to_string())),
-        };
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        if attempts > MAX_LOGIN_CHALLENGE_ATTEMPTS {
+            two_factor::delete_login_challenge(&self.redis, &req.challenge_token).await?;
+            return Err(AppError::TooManyRequestsError(
+                "Too many attempts, please login again".to_string(),
+            ));
+        }
 
-        let res = match token::service_generate_tokens(&user_res.id, &user_uuid) {
-            Ok(res) => res,
-            Err(err) => return Err(err),
-        };
+        let user_res = user::Entity::find_user_by_id(conn, challenge.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(invalid_challenge)?;
+        self.user_status_service.ensure_can_sign_in(conn, &user_res).await?;
+        // Counted per user as well, a fresh challenge must not mean fresh guesses
+        login_guard::check_second_factor_allowed(&self.redis, user_res.id).await?;
+        if !self.two_factor_service.verify_second_factor(conn, &user_res, &req.code).await? {
+            return Err(login_guard::record_second_factor_failure(&self.redis, user_res.id).await?);
+        }
+        login_guard::clear_second_factor_failures(&self.redis, user_res.id).await?;
 
-        Ok(res)
+        two_factor::delete_login_challenge(&self.redis, &req.challenge_token).await?;
+        let authentication = challenge.authentication.and_then(&[AMR_OTP, AMR_MFA]);
+        self.start_session(conn, user_res.id, &authentication, challenge.device_name, client).await
     }
 
     async fn refresh_token(
         &self,
-        _conn: &DatabaseTransaction,
-        _refresh_token: &str,
+        conn: &DatabaseTransaction,
+        refresh_token: &str,
+    ) -> AppResult<TokenResponse> {
+        let claims = UserClaims::decode(refresh_token, &REFRESH_TOKEN_KEYS)?.claims;
+        // A client's refresh token would come back with full first-party access here
+        if claims.is_delegated() {
+            return Err(AppError::PermissionDeniedError(
+                "Tokens issued to a client are refreshed at /oauth/token".to_string(),
+            ));
+        }
+        let (user_res, new_jti) = self.rotate_refresh_token(conn, &claims).await?;
+        token::service_generate_tokens(
+            &user_res.id,
+            &claims.sid,
+            &new_jti,
+            &claims.authentication(),
+            &load_access(conn, user_res.id).await?,
+        )
+    }
+
+    async fn refresh_delegated_token(
+        &self,
+        conn: &DatabaseTransaction,
+        refresh_token: &str,
+        client_id: &str,
     ) -> AppResult<TokenResponse> {
-        // TODO: Implement refresh token logic
-        Err(AppError::BadRequestError("Refresh token not implemented yet".to_string()))
+        let invalid_grant = || AppError::BadRequestError("invalid_grant".to_string());
+        let claims = UserClaims::decode(refresh_token, &REFRESH_TOKEN_KEYS)
+            .map_err(|_| invalid_grant())?
+            .claims;
+        let scope = delegated_scope(&claims, client_id).ok_or_else(invalid_grant)?;
+        let (user_res, new_jti) = self.rotate_refresh_token(conn, &claims).await?;
+        token::service_generate_delegated_tokens(
+            &user_res.id,
+            &claims.sid,
+            &new_jti,
+            &claims.authentication(),
+            client_id,
+            scope,
+        )
     }
 
     async fn logout(&self, user_id: i64, user_uuid: &Uuid) -> AppResult<()> {
-        self.redis
-            .delete_key(&format!("profile:user_id:{}", user_id).into())
+        session::revoke_session(&self.redis, user_id, user_uuid).await
+    }
+
+    async fn forget_password(
+        &self,
+        conn: &DatabaseTransaction,
+        req: &ForgetPasswordCommand,
+    ) -> AppResult<()> {
+        // Unknown emails succeed silently so the endpoint does not reveal registered accounts
+        let user_res = match user::Entity::find_user_by_email(conn, req.get_email()).await? {
+            Some(user_res) if !user_res.is_deleted => user_res,
+            _ => {
+                log::info!("Password reset requested for unknown email.");
+                return Ok(());
+            },
+        };
+
+        self.send_password_reset(
+            &user_res,
+            "If you did not ask for a password reset, you can ignore this email.",
+        )
+        .await
+    }
+
+    async fn reset_password(
+        &self,
+        conn: &DatabaseTransaction,
+        req: &ResetPasswordCommand,
+    ) -> AppResult<AfterCommit> {
+        let invalid_code =
+            || AppError::BadRequestError("Reset code is invalid or has expired".to_string());
+        let key: RedisKey = forget_password_key(req.get_token()).into();
+
+        let user_id = self
+            .redis
+            .get_key::<Option<String>>(&key)
             .await
-            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?
+            .and_then(|value| value.parse::<i64>().ok())
+            .ok_or_else(invalid_code)?;
+
+        let user_res = user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .ok_or_else(invalid_code)?;
 
-        Ok(())
+        // Checked before the code is used up, so a rejected password can be retried
+        self.password_policy_service
+            .validate_new_password(
+                conn,
+                req.get_new_password(),
+                &user_res.username,
+                &user_res.email,
+                Some(&user_res),
+            )
+            .await?;
+
+        let hashed_password = password::hash(req.get_new_password().to_string()).await?;
+        self.password_policy_service.remember_password(conn, user_id, &hashed_password).await?;
+        user::Entity::update_user(conn, user_res.clone().change_password(hashed_password).into_active_model())
+            .await?;
+
+        // Only once the new password is saved: until then the code stays usable, so a
+        // failed commit can be retried with it
+        let mut after_commit = AfterCommit::new();
+        let redis = self.redis.clone();
+        after_commit.push(async move {
+            redis.delete_key(&key).await.map(|_| ()).map_err(|err| AppError::BadRequestError(err.to_string()))
+        });
+        // Whoever knew the old password must not stay signed in
+        let redis = self.redis.clone();
+        after_commit.push(async move {
+            session::revoke_all_sessions(&redis, user_id, None).await.map(|_| ())
+        });
+        // Failed logins may have locked the account
+        let redis = self.redis.clone();
+        after_commit.push(async move { login_guard::unlock(&redis, user_id).await });
+
+        Ok(after_commit)
+    }
+
+    async fn change_password(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        req: &ChangePasswordCommand,
+        client: &ClientInfo,
+    ) -> AppResult<AfterCommit> {
+        if claims.is_api_key() {
+            return Err(AppError::PermissionDeniedError(
+                "The password cannot be changed with an API key".to_string(),
+            ));
+        }
+        let user_res = user::Entity::find_user_by_id(conn, claims.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", claims.user_id),
+            })?;
+        let Some(current_hash) = user_res.password.clone() else {
+            return Err(AppError::BadRequestError(
+                "This account has no password yet, use forget password to set one".to_string(),
+            ));
+        };
+        let ip_address = client.ip_address.as_deref();
+        let subject = LoginSubject::User(user_res.id);
+        login_guard::check_login_allowed(&self.redis, &subject, ip_address).await?;
+        if password::verify(req.current_password.clone(), current_hash).await.is_err() {
+            let throttled =
+                login_guard::record_login_failure(&self.redis, &subject, ip_address).await?;
+            return Err(wrong_current_password(throttled));
+        }
+        login_guard::clear_login_failures(&self.redis, &subject).await?;
+
+        self.password_policy_service
+            .validate_new_password(conn, &req.new_password, &user_res.username, &user_res.email, Some(&user_res))
+            .await?;
+
+        let hashed_password = password::hash(req.new_password.clone()).await?;
+        self.password_policy_service.remember_password(conn, user_res.id, &hashed_password).await?;
+        user::Entity::update_user(conn, user_res.clone().change_password(hashed_password).into_active_model())
+            .await?;
+
+        let mut after_commit = AfterCommit::new();
+        let detail = if req.revoke_other_sessions {
+            let others = session::list_sessions(&self.redis, user_res.id)
+                .await?
+                .iter()
+                .filter(|record| record.session_id != claims.sid)
+                .count();
+            let (redis, user_id, keep) = (self.redis.clone(), user_res.id, claims.sid);
+            after_commit.push(async move {
+                session::revoke_all_sessions(&redis, user_id, Some(&keep)).await.map(|_| ())
+            });
+            Some(format!("{others} other sessions were signed out"))
+        } else {
+            None
+        };
+        after_commit.append(
+            self.record_security_event(conn, &user_res, SecurityEventKind::PasswordChanged, client, detail)
+                .await?,
+        );
+
+        log::info!("User {} changed their password.", user_res.id);
+        Ok(after_commit)
+    }
+
+    async fn reauthenticate(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        req: &ReauthenticateCommand,
+        client: &ClientInfo,
+    ) -> AppResult<TokenResponse> {
+        if claims.is_api_key() {
+            return Err(AppError::PermissionDeniedError(
+                "API keys cannot re-authenticate".to_string(),
+            ));
+        }
+        claims.require_not_impersonated()?;
+        let user_res = user::Entity::find_user_by_id(conn, claims.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", claims.user_id),
+            })?;
+
+        // Wrong answers count against the same budget as failed logins
+        let ip_address = client.ip_address.as_deref();
+        let subject = LoginSubject::User(user_res.id);
+        login_guard::check_login_allowed(&self.redis, &subject, ip_address).await?;
+        let (is_correct, method) = match (req.password.as_deref(), req.code.as_deref()) {
+            (Some(given), None) => match user_res.password.clone() {
+                Some(current_hash) => {
+                    (password::verify(given.to_string(), current_hash).await.is_ok(), AMR_PASSWORD)
+                },
+                None => {
+                    return Err(AppError::BadRequestError(
+                        "This account has no password, confirm with a second factor instead".to_string(),
+                    ))
+                },
+            },
+            (None, Some(code)) if user_res.has_two_factor() => {
+                (self.two_factor_service.verify_second_factor(conn, &user_res, code).await?, AMR_OTP)
+            },
+            (None, Some(_)) => {
+                return Err(AppError::BadRequestError(
+                    "Two-factor authentication is not enabled".to_string(),
+                ))
+            },
+            _ => {
+                return Err(AppError::BadRequestError(
+                    "Provide either the password or a code".to_string(),
+                ))
+            },
+        };
+        if !is_correct {
+            return Err(
+                login_guard::record_login_failure(&self.redis, &subject, ip_address).await?
+            );
+        }
+        login_guard::clear_login_failures(&self.redis, &subject).await?;
+
+        // Same session, new pair: the previous refresh token stops working
+        let refresh_jti = Uuid::new_v4();
+        session::store_refresh_token(&self.redis, &claims.sid, &refresh_jti).await?;
+        log::info!("User {} re-authenticated with {method}.", user_res.id);
+        token::service_generate_tokens(
+            &user_res.id,
+            &claims.sid,
+            &refresh_jti,
+            &Authentication::now(&[method]),
+            &load_access(conn, user_res.id).await?,
+        )
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::constant::EXPIRE_REFRESH_TOKEN_SECS;
+
+    #[test]
+    fn test_security_event_mail_tells_where_it_came_from() {
+        let user_res =
+            UserModel::create_external_user("jane", "jane@example.com", "Jane", "Doe", true).unwrap();
+        let client = ClientInfo {
+            ip_address: Some("198.51.100.1".to_string()),
+            user_agent: Some("curl/8.0".to_string()),
+        };
+        let message = security_event_mail(
+            &user_res,
+            &SecurityEventKind::PasswordChanged,
+            &client,
+            Some("2 other sessions were signed out".to_string()),
+        );
+
+        assert_eq!(message.to, "jane@example.com");
+        assert_eq!(message.subject, "Your password was changed");
+        assert!(message
+            .body
+            .starts_with("Your password was changed (2 other sessions were signed out).\n\nFrom: 198.51.100.1\nDevice: curl/8.0"));
+    }
+
+    #[test]
+    fn test_wrong_current_password_is_a_bad_request_until_throttled() {
+        let wrong = wrong_current_password(AppError::InvalidCredentialsError("wrong".to_string()));
+        assert!(matches!(wrong, AppError::BadRequestError(_)));
+
+        let locked = wrong_current_password(AppError::AccountLockedError("locked".to_string()));
+        assert!(matches!(locked, AppError::AccountLockedError(_)));
+        let backing_off = wrong_current_password(AppError::TooManyRequestsError("wait".to_string()));
+        assert!(matches!(backing_off, AppError::TooManyRequestsError(_)));
+    }
+
+    #[test]
+    fn test_refresh_token_of_a_client_keeps_only_its_scope() {
+        let claims = UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, &7, &Uuid::new_v4());
+        assert_eq!(delegated_scope(&claims, "app"), None);
+
+        let delegated = claims.clone().delegated_to("app", "openid profile");
+        assert_eq!(delegated_scope(&delegated, "app"), Some("openid profile"));
+        assert_eq!(delegated_scope(&delegated, "other-app"), None);
+
+        // A client token without a scope is not silently upgraded to first-party access
+        let unscoped = UserClaims { scope: None, ..delegated };
+        assert_eq!(delegated_scope(&unscoped, "app"), None);
+    }
+}
@@ -1,26 +1,84 @@
+use crate::application::after_commit::AfterCommit;
 use crate::core::error::AppResult;
-use crate::presentation::authen::authen::TokenResponse;
+use crate::presentation::authen::authen::{LoginResponse, TokenResponse};
 use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
-use crate::application::authen::authen_command::LoginByEmailCommand;
+use crate::application::authen::authen_command::{
+    ChangePasswordCommand, ForgetPasswordCommand, LoginByEmailCommand, LoginTwoFactorCommand,
+    ReauthenticateCommand, ResetPasswordCommand,
+};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::util::claim::UserClaims;
 
 pub trait AuthenServiceInterface: Send + Sync + 'static {
     async fn login_by_email(
         &self,
         conn: &DatabaseTransaction,
-        login_by_email_command: &LoginByEmailCommand
+        login_by_email_command: &LoginByEmailCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse>;
+
+    async fn login_two_factor(
+        &self,
+        conn: &DatabaseTransaction,
+        login_two_factor_command: &LoginTwoFactorCommand,
+        client: &ClientInfo,
     ) -> AppResult<TokenResponse>;
 
+    /// Refreshes the user's own tokens. Tokens issued to a third-party client are refused.
     async fn refresh_token(
         &self,
         conn: &DatabaseTransaction,
         refresh_token: &str,
     ) -> AppResult<TokenResponse>;
 
+    /// Refreshes the tokens of a third-party client that already authenticated at
+    /// `/oauth/token`. The refresh token must have been issued to `client_id`.
+    async fn refresh_delegated_token(
+        &self,
+        conn: &DatabaseTransaction,
+        refresh_token: &str,
+        client_id: &str,
+    ) -> AppResult<TokenResponse>;
+
     async fn logout(
         &self,
         user_id: i64,
         user_uuid: &Uuid,
     ) -> AppResult<()>;
-}
 
+    async fn forget_password(
+        &self,
+        conn: &DatabaseTransaction,
+        forget_password_command: &ForgetPasswordCommand,
+    ) -> AppResult<()>;
+
+    /// Sets a new password with a reset code. Using up the code, signing out every session
+    /// and lifting login locks are returned to run after commit.
+    async fn reset_password(
+        &self,
+        conn: &DatabaseTransaction,
+        reset_password_command: &ResetPasswordCommand,
+    ) -> AppResult<AfterCommit>;
+
+    /// Replaces the password of the signed-in user once the current one is confirmed.
+    /// Signing out the other sessions and the notification mail are returned to run
+    /// after commit.
+    async fn change_password(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        change_password_command: &ChangePasswordCommand,
+        client: &ClientInfo,
+    ) -> AppResult<AfterCommit>;
+
+    /// Checks the password or a second factor again and reissues the session's tokens
+    /// with a fresh `auth_time`.
+    async fn reauthenticate(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        reauthenticate_command: &ReauthenticateCommand,
+        client: &ClientInfo,
+    ) -> AppResult<TokenResponse>;
+}
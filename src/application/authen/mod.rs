@@ -1,4 +1,9 @@
 pub mod authen_command;
 pub mod authen_service;
 pub mod authen_service_interface;
-
+pub mod oidc_command;
+pub mod oidc_service;
+pub mod oidc_service_interface;
+pub mod passwordless_command;
+pub mod passwordless_service;
+pub mod passwordless_service_interface;
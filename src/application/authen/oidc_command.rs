@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub const RESPONSE_TYPE_CODE: &str = "code";
+pub const GRANT_TYPE_AUTHORIZATION_CODE: &str = "authorization_code";
+pub const GRANT_TYPE_REFRESH_TOKEN: &str = "refresh_token";
+
+/// OpenID Connect Core 3.1.2.1 authentication request
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema, IntoParams)]
+pub struct AuthorizeCommand {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    /// Space-separated, must include `openid`
+    pub scope: String,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    /// RFC 7636, required for every client
+    pub code_challenge: Option<String>,
+    /// Only `S256` is supported
+    pub code_challenge_method: Option<String>,
+}
+
+/// The user's answer to a consent prompt, sent with the original authentication request.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ConsentCommand {
+    #[serde(flatten)]
+    pub authorize: AuthorizeCommand,
+    pub approve: bool,
+}
+
+/// RFC 6749 token request, sent as `application/x-www-form-urlencoded`
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct OidcTokenCommand {
+    /// `authorization_code` or `refresh_token`
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    /// Public clients identify themselves here; confidential ones may use HTTP Basic auth instead
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
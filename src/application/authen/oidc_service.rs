@@ -0,0 +1,441 @@
+use crate::application::authen::authen_service::AuthenService;
+use crate::application::authen::authen_service_interface::AuthenServiceInterface;
+use crate::application::authen::oidc_command::{
+    AuthorizeCommand, ConsentCommand, OidcTokenCommand, GRANT_TYPE_AUTHORIZATION_CODE,
+    GRANT_TYPE_REFRESH_TOKEN, RESPONSE_TYPE_CODE,
+};
+use crate::application::authen::oidc_service_interface::OidcServiceInterface;
+use crate::application::oauth::oauth_command::ClientCredentials;
+use crate::application::oauth::oauth_service::OauthService;
+use crate::application::oauth::oauth_service_interface::OauthServiceInterface;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::oauth_client::consent;
+use crate::domain::oauth_client::consent_repository_interface::ConsentRepositoryInterface;
+use crate::domain::oauth_client::oauth_client;
+use crate::domain::oauth_client::oauth_client::ModelEx as OauthClientModel;
+use crate::domain::oauth_client::oauth_client_repository_interface::OauthClientRepositoryInterface;
+use crate::domain::oauth_client::scope;
+use crate::domain::user::user;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::persistence::redis_client::authorization_code::{self, AuthorizationCode};
+use crate::infrastructure::persistence::redis_client::session;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::token;
+use crate::presentation::authen::oidc::{
+    AuthorizeResponse, ConsentResponse, OidcTokenResponse, OpenIdConfigurationResponse,
+    UserInfoResponse,
+};
+use crate::util::claim::{Authentication, IdTokenClaims, UserClaims};
+use crate::util::constant::{ACCESS_TOKEN_KEYS, EXPIRE_ID_TOKEN_SECS, REFRESH_TOKEN_KEYS};
+use crate::util::pkce;
+use chrono::Utc;
+use rdkafka::producer::FutureProducer;
+use sea_orm::DatabaseTransaction;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Application service - OpenID Connect provider (authorization code flow with PKCE)
+pub struct OidcService {
+    pub config: Arc<AppConfig>,
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+    pub oauth_service: Arc<OauthService>,
+    pub authen_service: Arc<AuthenService>,
+}
+
+/// Validated authentication request, ready to be turned into a code.
+struct AuthorizationRequest {
+    client: OauthClientModel,
+    scopes: BTreeSet<String>,
+    code_challenge: String,
+}
+
+impl OidcService {
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        oauth_service: Arc<OauthService>,
+        authen_service: Arc<AuthenService>,
+    ) -> Self {
+        Self { config, redis, kafka_producer, oauth_service, authen_service }
+    }
+
+    fn issuer(&self) -> &str {
+        self.config.auth.issuer.trim_end_matches('/')
+    }
+
+    /// Errors before the redirect URI is known to belong to the client are answered
+    /// directly; later ones go back to the client through the redirect (RFC 6749 4.1.2.1).
+    async fn validate_request(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &AuthorizeCommand,
+    ) -> AppResult<Result<AuthorizationRequest, AuthorizeResponse>> {
+        let client = oauth_client::Entity::find_client_by_client_id(conn, &command.client_id)
+            .await?
+            .filter(|client| client.is_active)
+            .ok_or_else(|| AppError::BadRequestError("Unknown client".to_string()))?;
+        if !client.allows_redirect_uri(&command.redirect_uri) {
+            return Err(AppError::BadRequestError(
+                "Redirect URI is not registered for this client".to_string(),
+            ));
+        }
+
+        if command.response_type != RESPONSE_TYPE_CODE {
+            return Ok(Err(self.error_redirect(command, "unsupported_response_type")));
+        }
+        let scopes = scope::parse(&command.scope);
+        if !scopes.contains(scope::SCOPE_OPENID) || !client.allows_scopes(&scopes) {
+            return Ok(Err(self.error_redirect(command, "invalid_scope")));
+        }
+        let code_challenge = match (&command.code_challenge, command.code_challenge_method.as_deref()) {
+            (Some(challenge), Some(pkce::CODE_CHALLENGE_METHOD_S256)) if !challenge.is_empty() => {
+                challenge.clone()
+            },
+            _ => return Ok(Err(self.error_redirect(command, "invalid_request"))),
+        };
+
+        Ok(Ok(AuthorizationRequest { client, scopes, code_challenge }))
+    }
+
+    async fn issue_code(
+        &self,
+        claims: &UserClaims,
+        command: &AuthorizeCommand,
+        request: &AuthorizationRequest,
+    ) -> AppResult<AuthorizeResponse> {
+        // Tokens from before `auth_time` existed fall back to the start of their session
+        let auth_time = match claims.auth_time {
+            0 => session::find_session(&self.redis, &claims.sid)
+                .await?
+                .map(|record| record.created_at.and_utc().timestamp())
+                .unwrap_or(claims.iat),
+            auth_time => auth_time,
+        };
+        let code = authorization_code::create_authorization_code(
+            &self.redis,
+            &AuthorizationCode {
+                client_id: request.client.client_id.clone(),
+                user_id: claims.user_id,
+                redirect_uri: command.redirect_uri.clone(),
+                scope: scope::join(&request.scopes),
+                nonce: command.nonce.clone(),
+                code_challenge: request.code_challenge.clone(),
+                auth_time,
+            },
+        )
+        .await?;
+
+        Ok(AuthorizeResponse::Redirect {
+            redirect_to: self.redirect_uri_with(command, &[("code", code.as_str())]),
+        })
+    }
+
+    fn error_redirect(&self, command: &AuthorizeCommand, error: &str) -> AuthorizeResponse {
+        AuthorizeResponse::Redirect { redirect_to: self.redirect_uri_with(command, &[("error", error)]) }
+    }
+
+    /// Appends `params`, the echoed `state` and `iss` (RFC 9207) to the client's redirect URI.
+    fn redirect_uri_with(&self, command: &AuthorizeCommand, params: &[(&str, &str)]) -> String {
+        let mut params = params.to_vec();
+        if let Some(state) = command.state.as_deref() {
+            params.push(("state", state));
+        }
+        params.push(("iss", self.issuer()));
+        let query = serde_urlencoded::to_string(&params).unwrap_or_default();
+        let separator = if command.redirect_uri.contains('?') { '&' } else { '?' };
+        format!("{}{separator}{query}", command.redirect_uri)
+    }
+
+    /// Public clients only name themselves; PKCE is what proves they started the flow.
+    async fn authenticate_client(
+        &self,
+        conn: &DatabaseTransaction,
+        client_id: &str,
+        client_secret: Option<&str>,
+    ) -> AppResult<OauthClientModel> {
+        let invalid_client = || AppError::UnauthorizedError("invalid_client".to_string());
+        let client = oauth_client::Entity::find_client_by_client_id(conn, client_id)
+            .await?
+            .filter(|client| client.is_active)
+            .ok_or_else(invalid_client)?;
+        if client.is_public {
+            return Ok(client);
+        }
+        let client_secret = client_secret.ok_or_else(invalid_client)?;
+        self.oauth_service
+            .authenticate_client(
+                conn,
+                &ClientCredentials {
+                    client_id: client_id.to_string(),
+                    client_secret: client_secret.to_string(),
+                },
+            )
+            .await
+    }
+
+    async fn exchange_code(
+        &self,
+        conn: &DatabaseTransaction,
+        client: &OauthClientModel,
+        command: &OidcTokenCommand,
+        client_info: &ClientInfo,
+    ) -> AppResult<OidcTokenResponse> {
+        let invalid_grant = || AppError::BadRequestError("invalid_grant".to_string());
+        let code = command.code.as_deref().ok_or_else(invalid_grant)?;
+        let grant = authorization_code::take_authorization_code(&self.redis, code)
+            .await?
+            .ok_or_else(invalid_grant)?;
+        if grant.client_id != client.client_id
+            || command.redirect_uri.as_deref() != Some(grant.redirect_uri.as_str())
+        {
+            return Err(invalid_grant());
+        }
+        let code_verifier = command.code_verifier.as_deref().unwrap_or_default();
+        if !pkce::verify_s256(code_verifier, &grant.code_challenge) {
+            return Err(invalid_grant());
+        }
+        let user_res = user::Entity::find_user_by_id(conn, grant.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(invalid_grant)?;
+        self.authen_service.user_status_service.ensure_can_sign_in(conn, &user_res).await?;
+
+        // Each client sign-in is its own session, listed and revocable like any device
+        let session =
+            session::create_session(&self.redis, user_res.id, Some(client.name.clone()), client_info)
+                .await?;
+        let refresh_jti = Uuid::new_v4();
+        session::store_refresh_token(&self.redis, &session.session_id, &refresh_jti).await?;
+        let tokens = token::service_generate_delegated_tokens(
+            &user_res.id,
+            &session.session_id,
+            &refresh_jti,
+            &Authentication { auth_time: grant.auth_time, amr: Vec::new() },
+            &client.client_id,
+            &grant.scope,
+        )?;
+
+        let now = Utc::now().timestamp();
+        let with_profile = scope::contains(&grant.scope, scope::SCOPE_PROFILE);
+        let with_email = scope::contains(&grant.scope, scope::SCOPE_EMAIL);
+        let id_token = IdTokenClaims {
+            iss: self.issuer().to_string(),
+            sub: user_res.id.to_string(),
+            aud: client.client_id.clone(),
+            exp: now + EXPIRE_ID_TOKEN_SECS.as_secs() as i64,
+            iat: now,
+            auth_time: grant.auth_time,
+            nonce: grant.nonce,
+            sid: session.session_id,
+            preferred_username: with_profile.then(|| user_res.username.clone()),
+            email: with_email.then(|| user_res.email.clone()),
+            email_verified: with_email.then(|| user_res.email_verified_at.is_some()),
+        }
+        .encode(&ACCESS_TOKEN_KEYS)?;
+
+        log::info!("Client {} signed in user {}.", client.client_id, user_res.id);
+        Ok(OidcTokenResponse {
+            access_token: tokens.access_token,
+            token_type: tokens.token_type,
+            expires_in: tokens.expire_in,
+            refresh_token: tokens.refresh_token,
+            id_token: Some(id_token),
+            scope: grant.scope,
+        })
+    }
+
+    async fn refresh(
+        &self,
+        conn: &DatabaseTransaction,
+        client: &OauthClientModel,
+        command: &OidcTokenCommand,
+    ) -> AppResult<OidcTokenResponse> {
+        let invalid_grant = || AppError::BadRequestError("invalid_grant".to_string());
+        let refresh_token = command.refresh_token.as_deref().ok_or_else(invalid_grant)?;
+        let claims = UserClaims::decode(refresh_token, &REFRESH_TOKEN_KEYS)
+            .map_err(|_| invalid_grant())?
+            .claims;
+
+        let tokens = self
+            .authen_service
+            .refresh_delegated_token(conn, refresh_token, &client.client_id)
+            .await?;
+        Ok(OidcTokenResponse {
+            access_token: tokens.access_token,
+            token_type: tokens.token_type,
+            expires_in: tokens.expire_in,
+            refresh_token: tokens.refresh_token,
+            id_token: None,
+            scope: claims.scope.unwrap_or_default(),
+        })
+    }
+}
+
+impl OidcServiceInterface for OidcService {
+    async fn authorize(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        command: &AuthorizeCommand,
+    ) -> AppResult<AuthorizeResponse> {
+        let request = match self.validate_request(conn, command).await? {
+            Ok(request) => request,
+            Err(error_redirect) => return Ok(error_redirect),
+        };
+
+        let granted = consent::Entity::find_consent(conn, claims.user_id, &request.client.client_id)
+            .await?
+            .map(|consent| scope::parse(&consent.scopes))
+            .unwrap_or_default();
+        if !request.scopes.is_subset(&granted) {
+            return Ok(AuthorizeResponse::ConsentRequired {
+                client_id: request.client.client_id,
+                client_name: request.client.name,
+                scopes: request.scopes.into_iter().collect(),
+            });
+        }
+
+        self.issue_code(claims, command, &request).await
+    }
+
+    async fn consent(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        command: &ConsentCommand,
+    ) -> AppResult<AuthorizeResponse> {
+        let authorize = &command.authorize;
+        let request = match self.validate_request(conn, authorize).await? {
+            Ok(request) => request,
+            Err(error_redirect) => return Ok(error_redirect),
+        };
+        if !command.approve {
+            return Ok(self.error_redirect(authorize, "access_denied"));
+        }
+
+        // Keep what was granted before, so approving a smaller request does not drop scopes
+        let mut granted = consent::Entity::find_consent(conn, claims.user_id, &request.client.client_id)
+            .await?
+            .map(|consent| scope::parse(&consent.scopes))
+            .unwrap_or_default();
+        granted.extend(request.scopes.iter().cloned());
+        consent::Entity::save_consent(conn, claims.user_id, &request.client.client_id, scope::join(&granted))
+            .await?;
+        log::info!(
+            "User {} granted {} to client {}.",
+            claims.user_id,
+            scope::join(&request.scopes),
+            request.client.client_id
+        );
+
+        self.issue_code(claims, authorize, &request).await
+    }
+
+    async fn token(
+        &self,
+        conn: &DatabaseTransaction,
+        client_id: &str,
+        client_secret: Option<&str>,
+        command: &OidcTokenCommand,
+        client: &ClientInfo,
+    ) -> AppResult<OidcTokenResponse> {
+        let oauth_client = self.authenticate_client(conn, client_id, client_secret).await?;
+        match command.grant_type.as_str() {
+            GRANT_TYPE_AUTHORIZATION_CODE => {
+                self.exchange_code(conn, &oauth_client, command, client).await
+            },
+            GRANT_TYPE_REFRESH_TOKEN => self.refresh(conn, &oauth_client, command).await,
+            _ => Err(AppError::BadRequestError("unsupported_grant_type".to_string())),
+        }
+    }
+
+    async fn userinfo(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+    ) -> AppResult<UserInfoResponse> {
+        // First-party tokens are not limited by scopes
+        let granted = |wanted: &str| {
+            claims.scope.as_deref().map_or(!claims.is_delegated(), |scope| scope::contains(scope, wanted))
+        };
+        if !granted(scope::SCOPE_OPENID) {
+            return Err(AppError::PermissionDeniedError("The openid scope is required".to_string()));
+        }
+        let user_res = user::Entity::find_user_by_id(conn, claims.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(|| AppError::InvalidSessionError("User not found".to_string()))?;
+
+        let with_email = granted(scope::SCOPE_EMAIL);
+        Ok(UserInfoResponse {
+            sub: user_res.id.to_string(),
+            preferred_username: granted(scope::SCOPE_PROFILE).then(|| user_res.username.clone()),
+            email: with_email.then(|| user_res.email.clone()),
+            email_verified: with_email.then(|| user_res.email_verified_at.is_some()),
+        })
+    }
+
+    async fn list_consents(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<ConsentResponse>> {
+        let consents = consent::Entity::list_consents(conn, user_id).await?;
+        Ok(consents
+            .into_iter()
+            .map(|consent| ConsentResponse {
+                scopes: scope::parse(&consent.scopes).into_iter().collect(),
+                client_id: consent.client_id,
+                created_at: consent.created_at,
+                updated_at: consent.updated_at,
+            })
+            .collect())
+    }
+
+    async fn revoke_consent(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        client_id: &str,
+    ) -> AppResult<()> {
+        if !consent::Entity::delete_consent(conn, user_id, client_id).await? {
+            return Err(AppError::EntityNotFoundError {
+                detail: format!("No consent for client {client_id}"),
+            });
+        }
+        Ok(())
+    }
+
+    fn openid_configuration(&self) -> OpenIdConfigurationResponse {
+        let issuer = self.issuer();
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        OpenIdConfigurationResponse {
+            issuer: issuer.to_string(),
+            authorization_endpoint: format!("{issuer}/oauth/authorize"),
+            token_endpoint: format!("{issuer}/oauth/token"),
+            userinfo_endpoint: format!("{issuer}/oauth/userinfo"),
+            jwks_uri: format!("{issuer}/.well-known/jwks.json"),
+            introspection_endpoint: format!("{issuer}/oauth/introspect"),
+            revocation_endpoint: format!("{issuer}/oauth/revoke"),
+            response_types_supported: strings(&[RESPONSE_TYPE_CODE]),
+            grant_types_supported: strings(&[GRANT_TYPE_AUTHORIZATION_CODE, GRANT_TYPE_REFRESH_TOKEN]),
+            subject_types_supported: strings(&["public"]),
+            id_token_signing_alg_values_supported: strings(&["RS256"]),
+            scopes_supported: strings(&scope::SUPPORTED_SCOPES),
+            claims_supported: strings(&[
+                "iss", "sub", "aud", "exp", "iat", "auth_time", "nonce", "sid",
+                "preferred_username", "email", "email_verified",
+            ]),
+            code_challenge_methods_supported: strings(&[pkce::CODE_CHALLENGE_METHOD_S256]),
+            token_endpoint_auth_methods_supported: strings(&[
+                "client_secret_basic", "client_secret_post", "none",
+            ]),
+        }
+    }
+}
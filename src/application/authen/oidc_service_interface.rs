@@ -0,0 +1,56 @@
+use crate::application::authen::oidc_command::{AuthorizeCommand, ConsentCommand, OidcTokenCommand};
+use crate::core::error::AppResult;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::authen::oidc::{
+    AuthorizeResponse, ConsentResponse, OidcTokenResponse, OpenIdConfigurationResponse,
+    UserInfoResponse,
+};
+use crate::util::claim::UserClaims;
+use sea_orm::DatabaseTransaction;
+
+pub trait OidcServiceInterface: Send + Sync + 'static {
+    /// Issues a code for the signed-in user, or asks for consent first.
+    async fn authorize(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        command: &AuthorizeCommand,
+    ) -> AppResult<AuthorizeResponse>;
+
+    async fn consent(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        command: &ConsentCommand,
+    ) -> AppResult<AuthorizeResponse>;
+
+    async fn token(
+        &self,
+        conn: &DatabaseTransaction,
+        client_id: &str,
+        client_secret: Option<&str>,
+        command: &OidcTokenCommand,
+        client: &ClientInfo,
+    ) -> AppResult<OidcTokenResponse>;
+
+    async fn userinfo(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+    ) -> AppResult<UserInfoResponse>;
+
+    async fn list_consents(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<ConsentResponse>>;
+
+    async fn revoke_consent(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        client_id: &str,
+    ) -> AppResult<()>;
+
+    fn openid_configuration(&self) -> OpenIdConfigurationResponse;
+}
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SendMagicLinkCommand {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct MagicLinkLoginCommand {
+    /// The `token` query parameter of the emailed link
+    #[validate(length(min = 30))]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SendPhoneOtpCommand {
+    /// In the format stored on the account, e.g. `+84912345678`
+    #[validate(length(min = 6, max = 20))]
+    pub phone_number: String,
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PhoneOtpLoginCommand {
+    #[validate(length(min = 6, max = 20))]
+    pub phone_number: String,
+    #[validate(length(equal = 6))]
+    pub code: String,
+}
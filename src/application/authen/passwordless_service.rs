@@ -0,0 +1,261 @@
+use crate::application::authen::authen_service::AuthenService;
+use crate::application::authen::passwordless_command::{
+    MagicLinkLoginCommand, PhoneOtpLoginCommand, SendMagicLinkCommand, SendPhoneOtpCommand,
+};
+use crate::application::authen::passwordless_service_interface::PasswordlessServiceInterface;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::user;
+use crate::domain::user::user::ModelEx as UserModel;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::persistence::redis_client::passwordless::{self, MagicLink, PhoneOtp};
+use crate::infrastructure::third_party::mail::{MailMessage, MailSender};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::sms::{SmsMessage, SmsSender};
+use crate::presentation::authen::authen::LoginResponse;
+use crate::util::claim::{Authentication, MagicLinkClaims, AMR_EMAIL, AMR_SMS};
+use crate::util::constant::{
+    ACCESS_TOKEN_KEYS, EXPIRE_MAGIC_LINK_SECS, EXPIRE_PHONE_OTP_SECS, MAX_PHONE_OTP_ATTEMPTS,
+    PHONE_OTP_LEN,
+};
+use crate::util::{hash, random};
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Application service - sign-in without a password, by emailed link or texted code
+pub struct PasswordlessService {
+    pub config: Arc<AppConfig>,
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+    pub mail_sender: Arc<dyn MailSender>,
+    pub sms_sender: Arc<dyn SmsSender>,
+    pub authen_service: Arc<AuthenService>,
+}
+
+impl PasswordlessService {
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        mail_sender: Arc<dyn MailSender>,
+        sms_sender: Arc<dyn SmsSender>,
+        authen_service: Arc<AuthenService>,
+    ) -> Self {
+        Self { config, redis, kafka_producer, mail_sender, sms_sender, authen_service }
+    }
+
+    fn magic_link_url(&self, token: &str) -> String {
+        let base = &self.config.auth.magic_link_url;
+        let separator = if base.contains('?') { '&' } else { '?' };
+        format!("{base}{separator}token={token}")
+    }
+
+    async fn find_live_user(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Option<UserModel>> {
+        Ok(user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted && !user_res.is_service_account))
+    }
+}
+
+fn phone_otp_text(brand_name: &str, code: &str) -> String {
+    format!(
+        "{code} is your {brand_name} sign-in code. It expires in {} minutes.",
+        EXPIRE_PHONE_OTP_SECS.as_secs() / 60
+    )
+}
+
+impl PasswordlessServiceInterface for PasswordlessService {
+    async fn send_magic_link(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &SendMagicLinkCommand,
+    ) -> AppResult<()> {
+        // Counted before the lookup, so the limit does not reveal which addresses exist
+        passwordless::check_send_allowed(&self.redis, "email", &command.email).await?;
+        let user_res = match user::Entity::find_user_by_email(conn, &command.email).await? {
+            Some(user_res) if !user_res.is_deleted && !user_res.is_service_account => user_res,
+            _ => {
+                log::info!("Magic link requested for unknown email.");
+                return Ok(());
+            },
+        };
+
+        let claims = MagicLinkClaims::new(EXPIRE_MAGIC_LINK_SECS, user_res.id);
+        passwordless::store_magic_link(
+            &self.redis,
+            &claims.jti,
+            &MagicLink { user_id: user_res.id, device_name: command.device_name.clone() },
+        )
+        .await?;
+        let token = claims.encode(&ACCESS_TOKEN_KEYS)?;
+
+        self.mail_sender
+            .send(MailMessage {
+                to: user_res.email.clone(),
+                subject: "Your sign-in link".to_string(),
+                body: format!(
+                    "Open this link to sign in: {}\n\n\
+                     The link expires in {} minutes and can be used only once. \
+                     If you did not ask to sign in, you can ignore this email.",
+                    self.magic_link_url(&token),
+                    EXPIRE_MAGIC_LINK_SECS.as_secs() / 60
+                ),
+            })
+            .await
+    }
+
+    async fn login_by_magic_link(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &MagicLinkLoginCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse> {
+        let invalid_link =
+            || AppError::UnauthorizedError("Sign-in link is invalid or has expired".to_string());
+        let claims =
+            MagicLinkClaims::decode(&command.token, &ACCESS_TOKEN_KEYS).map_err(|_| invalid_link())?;
+        let link = passwordless::take_magic_link(&self.redis, &claims.jti)
+            .await?
+            .filter(|link| link.user_id == claims.user_id)
+            .ok_or_else(invalid_link)?;
+        let mut user_res = Self::find_live_user(conn, link.user_id).await?.ok_or_else(invalid_link)?;
+
+        // Opening the link proves the address is the user's
+        if user_res.email_verified_at.is_none() {
+            let previous_status = user_res.status.clone();
+            user_res = user_res.verify_email()?;
+            user::Entity::update_user(conn, user_res.clone().into_active_model()).await?;
+            if user_res.status != previous_status {
+                // Pending to active only unblocks, safe to do before the commit
+                self.authen_service
+                    .user_status_service
+                    .record_status_change(
+                        conn,
+                        &user_res,
+                        &previous_status,
+                        Some(user_res.id),
+                        Some("Email address verified".to_string()),
+                    )
+                    .await?
+                    .run()
+                    .await?;
+            }
+        }
+
+        self.authen_service
+            .complete_login(
+                conn,
+                &user_res,
+                Authentication::now(&[AMR_EMAIL]),
+                link.device_name,
+                client,
+            )
+            .await
+    }
+
+    async fn send_phone_otp(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &SendPhoneOtpCommand,
+    ) -> AppResult<()> {
+        let phone_number = command.phone_number.trim();
+        passwordless::check_send_allowed(&self.redis, "sms", phone_number).await?;
+        let Some(user_res) = user::Entity::find_user_by_phone_number(conn, phone_number)
+            .await?
+            .filter(|user_res| !user_res.is_service_account)
+        else {
+            log::info!("Phone code requested for unknown number.");
+            return Ok(());
+        };
+
+        let code = random::generate_random_code(PHONE_OTP_LEN);
+        passwordless::store_phone_otp(
+            &self.redis,
+            phone_number,
+            &PhoneOtp {
+                user_id: user_res.id,
+                code_hash: hash::sha256_hex(&code),
+                device_name: command.device_name.clone(),
+            },
+        )
+        .await?;
+
+        self.sms_sender
+            .send(SmsMessage {
+                to: phone_number.to_string(),
+                body: phone_otp_text(&self.config.sms.brand_name, &code),
+            })
+            .await
+    }
+
+    async fn login_by_phone_otp(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &PhoneOtpLoginCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse> {
+        let invalid_code =
+            || AppError::UnauthorizedError("Sign-in code is invalid or has expired".to_string());
+        let phone_number = command.phone_number.trim();
+        let otp = passwordless::find_phone_otp(&self.redis, phone_number)
+            .await?
+            .ok_or_else(invalid_code)?;
+
+        // Six digits are guessable, so each code allows only a few tries
+        let attempts = self
+            .redis
+            .increment_key(
+                &passwordless::phone_otp_attempts_key(phone_number).into(),
+                EXPIRE_PHONE_OTP_SECS.as_secs() as i64,
+            )
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        if attempts > MAX_PHONE_OTP_ATTEMPTS {
+            passwordless::delete_phone_otp(&self.redis, phone_number).await?;
+            return Err(AppError::TooManyRequestsError(
+                "Too many wrong codes, request a new one".to_string(),
+            ));
+        }
+        if hash::sha256_hex(command.code.trim()) != otp.code_hash {
+            return Err(invalid_code());
+        }
+
+        let otp = passwordless::take_phone_otp(&self.redis, phone_number)
+            .await?
+            .filter(|taken| taken.code_hash == otp.code_hash)
+            .ok_or_else(invalid_code)?;
+        let user_res = Self::find_live_user(conn, otp.user_id).await?.ok_or_else(invalid_code)?;
+
+        self.authen_service
+            .complete_login(
+                conn,
+                &user_res,
+                Authentication::now(&[AMR_SMS]),
+                otp.device_name,
+                client,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phone_otp_text_names_the_brand() {
+        assert_eq!(
+            phone_otp_text("Acme", "123456"),
+            format!(
+                "123456 is your Acme sign-in code. It expires in {} minutes.",
+                EXPIRE_PHONE_OTP_SECS.as_secs() / 60
+            )
+        );
+    }
+}
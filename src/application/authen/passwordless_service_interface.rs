@@ -0,0 +1,37 @@
+use crate::application::authen::passwordless_command::{
+    MagicLinkLoginCommand, PhoneOtpLoginCommand, SendMagicLinkCommand, SendPhoneOtpCommand,
+};
+use crate::core::error::AppResult;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::authen::authen::LoginResponse;
+use sea_orm::DatabaseTransaction;
+
+pub trait PasswordlessServiceInterface: Send + Sync + 'static {
+    /// Emails a single-use sign-in link. Unknown addresses succeed without sending anything.
+    async fn send_magic_link(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &SendMagicLinkCommand,
+    ) -> AppResult<()>;
+
+    async fn login_by_magic_link(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &MagicLinkLoginCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse>;
+
+    /// Texts a one-time code. Unknown numbers succeed without sending anything.
+    async fn send_phone_otp(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &SendPhoneOtpCommand,
+    ) -> AppResult<()>;
+
+    async fn login_by_phone_otp(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &PhoneOtpLoginCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse>;
+}
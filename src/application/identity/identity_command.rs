@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate, IntoParams)]
+pub struct ExternalLoginStartQuery {
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
+}
+
+/// What the provider appended to our redirect URI
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct ExternalLoginCallbackCommand {
+    #[validate(length(min = 1, max = 2048))]
+    pub code: String,
+    #[validate(length(min = 20, max = 100))]
+    pub state: String,
+}
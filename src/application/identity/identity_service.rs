@@ -0,0 +1,318 @@
+use crate::application::authen::authen_service::AuthenService;
+use crate::application::identity::identity_command::ExternalLoginCallbackCommand;
+use crate::application::identity::identity_service_interface::IdentityServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::configure::auth::IdentityProviderConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::identity;
+use crate::domain::user::identity_repository_interface::IdentityRepositoryInterface;
+use crate::domain::user::user;
+use crate::domain::user::user::ModelEx as UserModel;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::persistence::redis_client::external_login::{self, ExternalLoginState};
+use crate::infrastructure::third_party::oidc::{ExternalIdentity, OidcRelyingParty};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::authen::authen::LoginResponse;
+use crate::presentation::identity::identity::{ExternalLoginStartResponse, IdentitySerializer};
+use crate::util::claim::{Authentication, AMR_FEDERATED};
+use crate::util::constant::EXPIRE_EXTERNAL_LOGIN_SECS;
+use crate::util::{pkce, random};
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Application service - sign-in with external OpenID providers and linked identities
+pub struct IdentityService {
+    pub config: Arc<AppConfig>,
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+    pub relying_party: OidcRelyingParty,
+    pub authen_service: Arc<AuthenService>,
+}
+
+impl IdentityService {
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        relying_party: OidcRelyingParty,
+        authen_service: Arc<AuthenService>,
+    ) -> Self {
+        Self { config, redis, kafka_producer, relying_party, authen_service }
+    }
+
+    fn provider(&self, name: &str) -> AppResult<&IdentityProviderConfig> {
+        self.config.auth.identity_provider(name).ok_or_else(|| AppError::EntityNotFoundError {
+            detail: format!("Identity provider {name} is not configured"),
+        })
+    }
+
+    async fn start(
+        &self,
+        provider: &IdentityProviderConfig,
+        link_user_id: Option<i64>,
+        device_name: Option<String>,
+    ) -> AppResult<ExternalLoginStartResponse> {
+        let metadata = self.relying_party.discover(provider).await?;
+        let nonce = random::generate_random_string(32);
+        let code_verifier = random::generate_random_string(64);
+        let state = external_login::create_external_login(
+            &self.redis,
+            &ExternalLoginState {
+                provider: provider.name.clone(),
+                nonce: nonce.clone(),
+                code_verifier: code_verifier.clone(),
+                link_user_id,
+                device_name,
+            },
+        )
+        .await?;
+
+        Ok(ExternalLoginStartResponse {
+            authorization_url: self.relying_party.authorization_url(
+                &metadata,
+                provider,
+                &state,
+                &nonce,
+                &pkce::challenge_s256(&code_verifier),
+            ),
+            expire_in: EXPIRE_EXTERNAL_LOGIN_SECS.as_secs(),
+        })
+    }
+
+    /// Matches the callback with the flow it belongs to and validates the provider's answer.
+    async fn finish(
+        &self,
+        provider: &IdentityProviderConfig,
+        command: &ExternalLoginCallbackCommand,
+    ) -> AppResult<(ExternalLoginState, ExternalIdentity)> {
+        let login = external_login::take_external_login(&self.redis, &command.state)
+            .await?
+            .filter(|login| login.provider == provider.name)
+            .ok_or_else(|| {
+                AppError::BadRequestError("Sign-in has expired, please start again".to_string())
+            })?;
+        let metadata = self.relying_party.discover(provider).await?;
+        let external = self
+            .relying_party
+            .exchange_code(&metadata, provider, &command.code, &login.code_verifier, &login.nonce)
+            .await?;
+        Ok((login, external))
+    }
+
+    /// Just-in-time account for a provider account seen for the first time.
+    async fn create_user_from(
+        &self,
+        conn: &DatabaseTransaction,
+        provider: &IdentityProviderConfig,
+        external: &ExternalIdentity,
+    ) -> AppResult<UserModel> {
+        let email = external.email.as_deref().ok_or_else(|| {
+            AppError::BadRequestError(format!("{} did not share an email address", provider.name))
+        })?;
+        // Taking over an existing account only because the emails match would let whoever
+        // controls the provider account into it, so the owner has to link it themselves
+        if user::Entity::email_exists(conn, email).await? {
+            return Err(AppError::EntityExistsError {
+                detail: format!(
+                    "An account with this email already exists. Sign in to it and link {} from your profile.",
+                    provider.name
+                ),
+            });
+        }
+
+        let username = self.available_username(conn, external, email).await?;
+        let user_res = user::ModelEx::create_external_user(
+            &username,
+            email,
+            external.given_name.as_deref().unwrap_or_default(),
+            external.family_name.as_deref().unwrap_or_default(),
+            external.email_verified,
+        )?;
+        user::Entity::create_user(conn, user_res.into_active_model()).await?;
+        let user_res = user::Entity::find_user_by_username(conn, &username)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User {username} not found after insert"),
+            })?;
+
+        log::info!("Created user {} from {} sign-in.", user_res.id, provider.name);
+        Ok(user_res)
+    }
+
+    /// The provider's username (or the email's local part), suffixed when already taken.
+    async fn available_username(
+        &self,
+        conn: &DatabaseTransaction,
+        external: &ExternalIdentity,
+        email: &str,
+    ) -> AppResult<String> {
+        let base: String = external
+            .preferred_username
+            .as_deref()
+            .unwrap_or_else(|| email.split('@').next().unwrap_or_default())
+            .chars()
+            .filter(|c| c.is_alphanumeric() || "._-".contains(*c))
+            .take(40)
+            .collect();
+        let base = if base.is_empty() { "user".to_string() } else { base };
+
+        let mut candidate = base.clone();
+        for _ in 0..5 {
+            if !user::Entity::username_exists(conn, &candidate).await? {
+                return Ok(candidate);
+            }
+            candidate = format!("{base}_{}", random::generate_random_code(6));
+        }
+        Err(AppError::EntityExistsError { detail: format!("Username {base} already exists") })
+    }
+}
+
+impl IdentityServiceInterface for IdentityService {
+    async fn start_login(
+        &self,
+        provider: &str,
+        device_name: Option<String>,
+    ) -> AppResult<ExternalLoginStartResponse> {
+        let provider = self.provider(provider)?;
+        self.start(provider, None, device_name).await
+    }
+
+    async fn complete_login(
+        &self,
+        conn: &DatabaseTransaction,
+        provider: &str,
+        command: &ExternalLoginCallbackCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse> {
+        let provider = self.provider(provider)?;
+        let (login, external) = self.finish(provider, command).await?;
+        if login.link_user_id.is_some() {
+            return Err(AppError::BadRequestError(
+                "This callback belongs to linking an identity".to_string(),
+            ));
+        }
+
+        let user_res = match identity::Entity::find_identity(conn, &provider.name, &external.subject).await? {
+            Some(linked) => {
+                identity::Entity::touch_identity_login(conn, linked.id).await?;
+                user::Entity::find_user_by_id(conn, linked.user_id)
+                    .await?
+                    .filter(|user_res| !user_res.is_deleted)
+                    .ok_or_else(|| {
+                        AppError::InvalidCredentialsError("The linked account no longer exists".to_string())
+                    })?
+            },
+            None => {
+                let user_res = self.create_user_from(conn, provider, &external).await?;
+                identity::Entity::create_identity(
+                    conn,
+                    identity::ActiveModel::new_identity(
+                        user_res.id,
+                        provider.name.clone(),
+                        external.subject.clone(),
+                        external.email.clone(),
+                    ),
+                )
+                .await?;
+                user_res
+            },
+        };
+
+        self.authen_service
+            .complete_login(
+                conn,
+                &user_res,
+                Authentication::now(&[AMR_FEDERATED]),
+                login.device_name,
+                client,
+            )
+            .await
+    }
+
+    async fn start_link(&self, user_id: i64, provider: &str) -> AppResult<ExternalLoginStartResponse> {
+        let provider = self.provider(provider)?;
+        self.start(provider, Some(user_id), None).await
+    }
+
+    async fn complete_link(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        provider: &str,
+        command: &ExternalLoginCallbackCommand,
+    ) -> AppResult<IdentitySerializer> {
+        let provider = self.provider(provider)?;
+        let (login, external) = self.finish(provider, command).await?;
+        // The flow must have been started by the same signed-in user
+        if login.link_user_id != Some(user_id) {
+            return Err(AppError::BadRequestError("Sign-in has expired, please start again".to_string()));
+        }
+
+        match identity::Entity::find_identity(conn, &provider.name, &external.subject).await? {
+            Some(linked) if linked.user_id == user_id => {
+                return Err(AppError::BadRequestError(format!(
+                    "This {} account is already linked",
+                    provider.name
+                )))
+            },
+            Some(_) => {
+                return Err(AppError::EntityExistsError {
+                    detail: format!("This {} account is linked to another user", provider.name),
+                })
+            },
+            None => (),
+        }
+
+        identity::Entity::create_identity(
+            conn,
+            identity::ActiveModel::new_identity(
+                user_id,
+                provider.name.clone(),
+                external.subject.clone(),
+                external.email.clone(),
+            ),
+        )
+        .await?;
+        let linked = identity::Entity::find_identity(conn, &provider.name, &external.subject)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: "Identity not found after insert".to_string(),
+            })?;
+        log::info!("User {user_id} linked a {} identity.", provider.name);
+        Ok(linked.into())
+    }
+
+    async fn list_identities(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<IdentitySerializer>> {
+        let identities = identity::Entity::list_identities(conn, user_id).await?;
+        Ok(identities.into_iter().map(IdentitySerializer::from).collect())
+    }
+
+    async fn unlink(&self, conn: &DatabaseTransaction, user_id: i64, identity_id: i64) -> AppResult<()> {
+        let user_res = user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", user_id),
+            })?;
+        let identities = identity::Entity::list_identities(conn, user_id).await?;
+        // Never remove the only way the user can still sign in
+        if user_res.password.is_none() && identities.len() <= 1 {
+            return Err(AppError::BadRequestError(
+                "Set a password before removing your last sign-in method".to_string(),
+            ));
+        }
+
+        if !identity::Entity::delete_identity(conn, user_id, identity_id).await? {
+            return Err(AppError::EntityNotFoundError {
+                detail: format!("Identity with id {} not found", identity_id),
+            });
+        }
+        log::info!("User {user_id} unlinked identity {identity_id}.");
+        Ok(())
+    }
+}
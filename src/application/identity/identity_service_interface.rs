@@ -0,0 +1,41 @@
+use crate::application::identity::identity_command::ExternalLoginCallbackCommand;
+use crate::core::error::AppResult;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::authen::authen::LoginResponse;
+use crate::presentation::identity::identity::{ExternalLoginStartResponse, IdentitySerializer};
+use sea_orm::DatabaseTransaction;
+
+pub trait IdentityServiceInterface: Send + Sync + 'static {
+    async fn start_login(
+        &self,
+        provider: &str,
+        device_name: Option<String>,
+    ) -> AppResult<ExternalLoginStartResponse>;
+
+    /// Signs in the user linked to the provider account, creating one on first sign-in.
+    async fn complete_login(
+        &self,
+        conn: &DatabaseTransaction,
+        provider: &str,
+        command: &ExternalLoginCallbackCommand,
+        client: &ClientInfo,
+    ) -> AppResult<LoginResponse>;
+
+    async fn start_link(&self, user_id: i64, provider: &str) -> AppResult<ExternalLoginStartResponse>;
+
+    async fn complete_link(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        provider: &str,
+        command: &ExternalLoginCallbackCommand,
+    ) -> AppResult<IdentitySerializer>;
+
+    async fn list_identities(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<Vec<IdentitySerializer>>;
+
+    async fn unlink(&self, conn: &DatabaseTransaction, user_id: i64, identity_id: i64) -> AppResult<()>;
+}
@@ -0,0 +1,3 @@
+pub mod identity_command;
+pub mod identity_service;
+pub mod identity_service_interface;
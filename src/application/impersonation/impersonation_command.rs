@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct StartImpersonationCommand {
+    /// Why support needs to see the account, e.g. the ticket number. Kept in the audit log.
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+}
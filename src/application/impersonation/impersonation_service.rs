@@ -0,0 +1,147 @@
+use crate::application::after_commit::AfterCommit;
+use crate::application::authen::authen_service::load_access;
+use crate::application::impersonation::impersonation_command::StartImpersonationCommand;
+use crate::application::impersonation::impersonation_service_interface::ImpersonationServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::audit_log::{self, AuditAction};
+use crate::domain::user::audit_log_repository_interface::AuditLogRepositoryInterface;
+use crate::domain::user::user;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::persistence::redis_client::{session, token_denylist};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::impersonation::impersonation::ImpersonationResponse;
+use crate::util::claim::UserClaims;
+use crate::util::constant::{ACCESS_TOKEN_KEYS, BEARER, EXPIRE_IMPERSONATION_SECS};
+use rdkafka::producer::FutureProducer;
+use sea_orm::DatabaseTransaction;
+use std::sync::Arc;
+
+/// Application service - support staff signing in as a customer
+pub struct ImpersonationService {
+    pub config: Arc<AppConfig>,
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+}
+
+impl ImpersonationService {
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+    ) -> Self {
+        Self { config, redis, kafka_producer }
+    }
+}
+
+impl ImpersonationServiceInterface for ImpersonationService {
+    async fn start(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &StartImpersonationCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(ImpersonationResponse, AfterCommit)> {
+        admin.require_not_impersonated()?;
+        if admin.user_id == user_id {
+            return Err(AppError::BadRequestError("You cannot impersonate yourself".to_string()));
+        }
+        user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", user_id),
+            })?;
+        // Acting as another staff member would hand out their permissions
+        let access = load_access(conn, user_id).await?;
+        if !access.permissions.is_empty() {
+            return Err(AppError::PermissionDeniedError(
+                "Staff accounts cannot be impersonated".to_string(),
+            ));
+        }
+
+        let record = session::impersonation_session(user_id, admin.user_id, client);
+        let access_token = UserClaims::new(EXPIRE_IMPERSONATION_SECS, &user_id, &record.session_id)
+            .with_access(&access)
+            .impersonated_by(admin.user_id)
+            .encode(&ACCESS_TOKEN_KEYS)?;
+        audit_log::Entity::create_audit_log(
+            conn,
+            audit_log::ActiveModel::new_audit_log(
+                admin.user_id,
+                user_id,
+                AuditAction::ImpersonationStarted,
+                client.ip_address.clone(),
+                client.user_agent.clone(),
+                Some(command.reason.clone()),
+            ),
+        )
+        .await?;
+
+        log::info!("Admin {} started impersonating user {user_id}.", admin.user_id);
+        let response = ImpersonationResponse {
+            token_type: BEARER.to_string(),
+            access_token,
+            expire_in: EXPIRE_IMPERSONATION_SECS.as_secs(),
+            user_id,
+            session_id: record.session_id,
+        };
+
+        // The token only works once its session exists, and that waits for the audit row
+        let mut after_commit = AfterCommit::new();
+        let redis = self.redis.clone();
+        after_commit.push(async move { session::store_new_session(&redis, &record).await });
+        Ok((response, after_commit))
+    }
+
+    async fn stop(&self, conn: &DatabaseTransaction, claims: &UserClaims, client: &ClientInfo) -> AppResult<()> {
+        let actor = claims.act.as_ref().ok_or_else(|| {
+            AppError::BadRequestError("This token is not an impersonation token".to_string())
+        })?;
+        session::revoke_session(&self.redis, claims.user_id, &claims.sid).await?;
+        token_denylist::revoke_jti(&self.redis, &claims.jti, claims.exp).await?;
+        audit_log::Entity::create_audit_log(
+            conn,
+            audit_log::ActiveModel::new_audit_log(
+                actor.user_id,
+                claims.user_id,
+                AuditAction::ImpersonationStopped,
+                client.ip_address.clone(),
+                client.user_agent.clone(),
+                None,
+            ),
+        )
+        .await?;
+
+        log::info!("Admin {} stopped impersonating user {}.", actor.user_id, claims.user_id);
+        Ok(())
+    }
+
+    async fn record_request(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        method: &str,
+        path: &str,
+        client: &ClientInfo,
+    ) -> AppResult<()> {
+        let Some(actor) = claims.act.as_ref() else {
+            return Ok(());
+        };
+        audit_log::Entity::create_audit_log(
+            conn,
+            audit_log::ActiveModel::new_audit_log(
+                actor.user_id,
+                claims.user_id,
+                AuditAction::ImpersonatedRequest,
+                client.ip_address.clone(),
+                client.user_agent.clone(),
+                None,
+            )
+            .with_request(method, path),
+        )
+        .await
+    }
+}
@@ -0,0 +1,33 @@
+use crate::application::after_commit::AfterCommit;
+use crate::application::impersonation::impersonation_command::StartImpersonationCommand;
+use crate::core::error::AppResult;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::presentation::impersonation::impersonation::ImpersonationResponse;
+use crate::util::claim::UserClaims;
+use sea_orm::DatabaseTransaction;
+
+pub trait ImpersonationServiceInterface: Send + Sync + 'static {
+    /// Issues `admin` a short-lived access token acting as `user_id`. The token's session
+    /// is returned to be created after commit, so no token outlives a lost audit entry.
+    async fn start(
+        &self,
+        conn: &DatabaseTransaction,
+        admin: &UserClaims,
+        user_id: i64,
+        command: &StartImpersonationCommand,
+        client: &ClientInfo,
+    ) -> AppResult<(ImpersonationResponse, AfterCommit)>;
+
+    /// Ends the impersonation `claims` belong to; the token stops working at once.
+    async fn stop(&self, conn: &DatabaseTransaction, claims: &UserClaims, client: &ClientInfo) -> AppResult<()>;
+
+    /// Audits one request made with an impersonation token.
+    async fn record_request(
+        &self,
+        conn: &DatabaseTransaction,
+        claims: &UserClaims,
+        method: &str,
+        path: &str,
+        client: &ClientInfo,
+    ) -> AppResult<()>;
+}
@@ -0,0 +1,3 @@
+pub mod impersonation_command;
+pub mod impersonation_service;
+pub mod impersonation_service_interface;
@@ -1,3 +1,12 @@
 pub mod authen;
 pub mod user;
 pub mod address;
+pub mod session;
+pub mod two_factor;
+pub mod oauth;
+pub mod identity;
+pub mod api_key;
+pub mod impersonation;
+pub mod policy;
+pub mod admin;
+pub mod after_commit;
@@ -0,0 +1,3 @@
+pub mod oauth_command;
+pub mod oauth_service;
+pub mod oauth_service_interface;
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const TOKEN_TYPE_ACCESS: &str = "access_token";
+pub const TOKEN_TYPE_REFRESH: &str = "refresh_token";
+
+/// Credentials of the calling client, from HTTP Basic auth or the form body.
+#[derive(Debug, Clone)]
+pub struct ClientCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// RFC 7662 section 2.1 request, sent as `application/x-www-form-urlencoded`
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+pub struct IntrospectTokenCommand {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+/// RFC 7009 section 2.1 request, sent as `application/x-www-form-urlencoded`
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+pub struct RevokeTokenCommand {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct CreateOauthClientCommand {
+    #[validate(length(min = 2, max = 100))]
+    pub name: String,
+    /// Where the OIDC authorization code flow may send users back to
+    #[serde(default)]
+    #[validate(length(max = 10))]
+    pub redirect_uris: Vec<String>,
+    /// Scopes the client may request, out of `openid`, `profile` and `email`
+    #[serde(default)]
+    pub allowed_scopes: Vec<String>,
+    /// SPA or native app that cannot keep a secret; it only uses the authorization code flow with PKCE
+    #[serde(default)]
+    pub is_public: bool,
+}
@@ -0,0 +1,243 @@
+use crate::application::oauth::oauth_command::{
+    ClientCredentials, CreateOauthClientCommand, IntrospectTokenCommand, RevokeTokenCommand,
+    TOKEN_TYPE_ACCESS, TOKEN_TYPE_REFRESH,
+};
+use crate::application::oauth::oauth_service_interface::OauthServiceInterface;
+use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
+use crate::domain::oauth_client::oauth_client;
+use crate::domain::oauth_client::oauth_client::ModelEx as OauthClientModel;
+use crate::domain::oauth_client::oauth_client_repository_interface::OauthClientRepositoryInterface;
+use crate::domain::user::user;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::persistence::redis_client::{session, token_denylist};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::oauth::oauth::{IntrospectionResponse, OauthClientCreatedResponse};
+use crate::util::claim::UserClaims;
+use crate::util::constant::{ACCESS_TOKEN_KEYS, REFRESH_TOKEN_KEYS};
+use crate::util::{password, random};
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Application service - token introspection and revocation for other services
+pub struct OauthService {
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+}
+
+impl OauthService {
+    pub fn new(redis: Arc<RedisConnectionPool>, kafka_producer: Arc<FutureProducer>) -> Self {
+        Self { redis, kafka_producer }
+    }
+
+    /// Tries the token type named by the hint first, then the other one (RFC 7662 2.1).
+    fn decode_token(token: &str, token_type_hint: Option<&str>) -> Option<(UserClaims, &'static str)> {
+        let access = (TOKEN_TYPE_ACCESS, &*ACCESS_TOKEN_KEYS);
+        let refresh = (TOKEN_TYPE_REFRESH, &*REFRESH_TOKEN_KEYS);
+        let order = match token_type_hint {
+            Some(TOKEN_TYPE_REFRESH) => [refresh, access],
+            _ => [access, refresh],
+        };
+        order.into_iter().find_map(|(token_type, keys)| {
+            UserClaims::decode(token, keys).ok().map(|data| (data.claims, token_type))
+        })
+    }
+
+    /// A token is active in the tenant it was issued in while it is not denylisted, its
+    /// session is alive and, for refresh tokens, it is still the one the session accepts.
+    async fn is_active(&self, claims: &UserClaims, token_type: &str) -> AppResult<bool> {
+        if claims.tenant_id != tenant::current_tenant() {
+            return Ok(false);
+        }
+        if token_denylist::is_revoked(&self.redis, &claims.jti).await? {
+            return Ok(false);
+        }
+        match session::find_session(&self.redis, &claims.sid).await? {
+            Some(record) if record.user_id == claims.user_id => (),
+            _ => return Ok(false),
+        }
+        if token_type == TOKEN_TYPE_REFRESH {
+            let current = session::current_refresh_jti(&self.redis, &claims.sid).await?;
+            return Ok(current == Some(claims.jti));
+        }
+        Ok(true)
+    }
+
+    /// Only the client a token was delegated to may revoke it (RFC 7009 2.1), first-party
+    /// tokens and those of other tenants belong to no client.
+    fn issued_to(claims: &UserClaims, client_id: &str) -> bool {
+        claims.azp.as_deref() == Some(client_id) && claims.tenant_id == tenant::current_tenant()
+    }
+}
+
+impl OauthServiceInterface for OauthService {
+    async fn authenticate_client(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+    ) -> AppResult<OauthClientModel> {
+        let invalid_client = || AppError::UnauthorizedError("invalid_client".to_string());
+        let client = oauth_client::Entity::find_client_by_client_id(conn, &credentials.client_id)
+            .await?
+            .filter(|client| client.is_active && !client.is_public)
+            .ok_or_else(invalid_client)?;
+        password::verify(credentials.client_secret.clone(), client.client_secret_hash.clone())
+            .await
+            .map_err(|_| invalid_client())?;
+        Ok(client)
+    }
+
+    async fn introspect(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+        command: &IntrospectTokenCommand,
+    ) -> AppResult<IntrospectionResponse> {
+        let client = self.authenticate_client(conn, credentials).await?;
+
+        let Some((claims, token_type)) =
+            Self::decode_token(&command.token, command.token_type_hint.as_deref())
+        else {
+            return Ok(IntrospectionResponse::inactive());
+        };
+        if !self.is_active(&claims, token_type).await? {
+            return Ok(IntrospectionResponse::inactive());
+        }
+        let Some(user_res) = user::Entity::find_user_by_id(conn, claims.user_id)
+            .await?
+            .filter(|user_res| !user_res.is_deleted)
+        else {
+            return Ok(IntrospectionResponse::inactive());
+        };
+
+        log::info!("Client {} introspected a token of user {}.", client.client_id, claims.user_id);
+        Ok(IntrospectionResponse {
+            active: true,
+            sub: Some(claims.user_id.to_string()),
+            username: Some(user_res.username),
+            token_type: Some(token_type.to_string()),
+            exp: Some(claims.exp),
+            iat: Some(claims.iat),
+            jti: Some(claims.jti),
+            sid: Some(claims.sid),
+            client_id: claims.azp,
+            scope: claims.scope,
+        })
+    }
+
+    async fn revoke(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+        command: &RevokeTokenCommand,
+    ) -> AppResult<()> {
+        let client = self.authenticate_client(conn, credentials).await?;
+
+        // Invalid or expired tokens need no revocation and are answered the same (RFC 7009 2.2)
+        let Some((claims, token_type)) =
+            Self::decode_token(&command.token, command.token_type_hint.as_deref())
+        else {
+            return Ok(());
+        };
+        // Answered like a successful revocation so the caller learns nothing about the token
+        if !Self::issued_to(&claims, &client.client_id) {
+            log::warn!(
+                "Client {} tried to revoke {} {} it was not issued.",
+                client.client_id,
+                token_type,
+                claims.jti
+            );
+            return Ok(());
+        }
+
+        token_denylist::revoke_jti(&self.redis, &claims.jti, claims.exp).await?;
+        if token_type == TOKEN_TYPE_REFRESH {
+            // Ending the session also invalidates every access token issued for it
+            session::revoke_session(&self.redis, claims.user_id, &claims.sid).await?;
+        }
+        log::warn!(
+            "Client {} revoked {} {} of user {}.",
+            client.client_id,
+            token_type,
+            claims.jti,
+            claims.user_id
+        );
+        Ok(())
+    }
+
+    async fn create_client(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &CreateOauthClientCommand,
+    ) -> AppResult<OauthClientCreatedResponse> {
+        let client_id = format!("client_{}", random::generate_random_string(24));
+        let (client_secret, client_secret_hash) = if command.is_public {
+            (None, String::new())
+        } else {
+            let client_secret = random::generate_random_string(48);
+            let client_secret_hash = password::hash(client_secret.clone()).await?;
+            (Some(client_secret), client_secret_hash)
+        };
+
+        let client = oauth_client::ModelEx::create_new_client(
+            &command.name,
+            client_id,
+            client_secret_hash,
+            &command.redirect_uris,
+            &command.allowed_scopes,
+            command.is_public,
+        )?;
+        let response = OauthClientCreatedResponse {
+            client_id: client.client_id.clone(),
+            client_secret,
+            name: client.name.clone(),
+            redirect_uris: client.redirect_uris.split_whitespace().map(str::to_string).collect(),
+            allowed_scopes: client.allowed_scopes.split_whitespace().map(str::to_string).collect(),
+            is_public: client.is_public,
+        };
+        oauth_client::Entity::create_client(conn, client.into_active_model()).await?;
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn claims() -> UserClaims {
+        UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4())
+    }
+
+    #[test]
+    fn test_decode_token_tells_access_from_refresh_tokens() {
+        let access = claims().encode(&ACCESS_TOKEN_KEYS).unwrap();
+        let refresh = claims().encode(&REFRESH_TOKEN_KEYS).unwrap();
+
+        for hint in [None, Some(TOKEN_TYPE_ACCESS), Some(TOKEN_TYPE_REFRESH)] {
+            let (_, token_type) = OauthService::decode_token(&access, hint).unwrap();
+            assert_eq!(token_type, TOKEN_TYPE_ACCESS);
+            let (_, token_type) = OauthService::decode_token(&refresh, hint).unwrap();
+            assert_eq!(token_type, TOKEN_TYPE_REFRESH);
+        }
+        assert!(OauthService::decode_token("not-a-token", None).is_none());
+    }
+
+    #[test]
+    fn test_only_the_delegated_client_may_revoke() {
+        let delegated = claims().delegated_to("client_a", "profile");
+        assert!(OauthService::issued_to(&delegated, "client_a"));
+        assert!(!OauthService::issued_to(&delegated, "client_b"));
+        assert!(!OauthService::issued_to(&claims(), "client_a"));
+    }
+
+    #[tokio::test]
+    async fn test_tokens_of_another_tenant_cannot_be_revoked() {
+        let delegated = claims().delegated_to("client_a", "profile");
+        let issued = tenant::scope("acme".to_string(), async { OauthService::issued_to(&delegated, "client_a") });
+        assert!(!issued.await);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::application::oauth::oauth_command::{
+    ClientCredentials, CreateOauthClientCommand, IntrospectTokenCommand, RevokeTokenCommand,
+};
+use crate::core::error::AppResult;
+use crate::domain::oauth_client::oauth_client::ModelEx as OauthClientModel;
+use crate::presentation::oauth::oauth::{IntrospectionResponse, OauthClientCreatedResponse};
+use sea_orm::DatabaseTransaction;
+
+pub trait OauthServiceInterface: Send + Sync + 'static {
+    async fn authenticate_client(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+    ) -> AppResult<OauthClientModel>;
+
+    async fn introspect(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+        command: &IntrospectTokenCommand,
+    ) -> AppResult<IntrospectionResponse>;
+
+    async fn revoke(
+        &self,
+        conn: &DatabaseTransaction,
+        credentials: &ClientCredentials,
+        command: &RevokeTokenCommand,
+    ) -> AppResult<()>;
+
+    async fn create_client(
+        &self,
+        conn: &DatabaseTransaction,
+        command: &CreateOauthClientCommand,
+    ) -> AppResult<OauthClientCreatedResponse>;
+}
@@ -0,0 +1 @@
+pub mod ownership_policy;
@@ -0,0 +1,48 @@
+//! Who may act on records that belong to a user: the user themselves, or staff holding
+//! the permission the operation names. Services check it before reading or changing
+//! such a record, so every route that reaches them gets the same rule.
+
+use crate::core::error::{AppError, AppResult};
+use crate::util::claim::UserClaims;
+
+/// The caller owns the record or holds `permission`.
+pub fn authorize_owner(caller: &UserClaims, owner_id: i64, permission: &str) -> AppResult<()> {
+    if caller.user_id == owner_id || caller.has_permission(permission) {
+        return Ok(());
+    }
+    Err(AppError::PermissionDeniedError(format!(
+        "You can only access your own records without permission `{permission}`"
+    )))
+}
+
+/// Staff-only changes, even to the caller's own record (e.g. the account status).
+pub fn authorize_staff(caller: &UserClaims, permission: &str) -> AppResult<()> {
+    caller.require_permission(permission)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::user::permission;
+    use crate::util::claim::Access;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn caller(user_id: i64, permissions: &[&str]) -> UserClaims {
+        let access = Access {
+            roles: Vec::new(),
+            permissions: permissions.iter().map(|name| name.to_string()).collect(),
+        };
+        UserClaims::new(Duration::from_secs(60), &user_id, &Uuid::new_v4()).with_access(&access)
+    }
+
+    #[test]
+    fn test_owner_or_permission_holder_is_allowed() {
+        assert!(authorize_owner(&caller(7, &[]), 7, permission::USERS_WRITE).is_ok());
+        assert!(authorize_owner(&caller(1, &[permission::USERS_WRITE]), 7, permission::USERS_WRITE).is_ok());
+        assert!(matches!(
+            authorize_owner(&caller(8, &[permission::USERS_READ]), 7, permission::USERS_WRITE),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+    }
+}
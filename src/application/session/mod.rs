@@ -0,0 +1,2 @@
+pub mod session_service;
+pub mod session_service_interface;
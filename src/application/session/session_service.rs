@@ -0,0 +1,56 @@
+use crate::application::session::session_service_interface::SessionServiceInterface;
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::persistence::redis_client::session;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::session::session::SessionSerializer;
+use rdkafka::producer::FutureProducer;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Application service - manages the signed-in devices of a user
+pub struct SessionService {
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+}
+
+impl SessionService {
+    pub fn new(redis: Arc<RedisConnectionPool>, kafka_producer: Arc<FutureProducer>) -> Self {
+        Self { redis, kafka_producer }
+    }
+}
+
+impl SessionServiceInterface for SessionService {
+    async fn list_sessions(
+        &self,
+        user_id: i64,
+        current_session_id: &Uuid,
+    ) -> AppResult<Vec<SessionSerializer>> {
+        let sessions = session::list_sessions(&self.redis, user_id).await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|record| SessionSerializer::from_record(record, current_session_id))
+            .collect())
+    }
+
+    async fn revoke_session(&self, user_id: i64, session_id: &Uuid) -> AppResult<bool> {
+        // A user may only revoke their own sessions
+        match session::find_session(&self.redis, session_id).await? {
+            Some(record) if record.user_id == user_id => {
+                session::revoke_session(&self.redis, user_id, session_id).await?;
+                Ok(true)
+            },
+            _ => Err(AppError::EntityNotFoundError {
+                detail: format!("Session {} not found", session_id),
+            }),
+        }
+    }
+
+    async fn revoke_other_sessions(
+        &self,
+        user_id: i64,
+        current_session_id: &Uuid,
+    ) -> AppResult<usize> {
+        session::revoke_all_sessions(&self.redis, user_id, Some(current_session_id)).await
+    }
+}
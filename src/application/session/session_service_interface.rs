@@ -0,0 +1,19 @@
+use crate::core::error::AppResult;
+use crate::presentation::session::session::SessionSerializer;
+use uuid::Uuid;
+
+pub trait SessionServiceInterface: Send + Sync + 'static {
+    async fn list_sessions(
+        &self,
+        user_id: i64,
+        current_session_id: &Uuid,
+    ) -> AppResult<Vec<SessionSerializer>>;
+
+    async fn revoke_session(&self, user_id: i64, session_id: &Uuid) -> AppResult<bool>;
+
+    async fn revoke_other_sessions(
+        &self,
+        user_id: i64,
+        current_session_id: &Uuid,
+    ) -> AppResult<usize>;
+}
@@ -0,0 +1,3 @@
+pub mod two_factor_command;
+pub mod two_factor_service;
+pub mod two_factor_service_interface;
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ConfirmTotpCommand {
+    #[validate(length(equal = 6))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct TwoFactorCodeCommand {
+    /// A current TOTP code or an unused recovery code
+    #[validate(length(min = 6, max = 20))]
+    pub code: String,
+}
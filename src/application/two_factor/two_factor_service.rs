@@ -0,0 +1,206 @@
+use crate::application::two_factor::two_factor_command::{ConfirmTotpCommand, TwoFactorCodeCommand};
+use crate::application::two_factor::two_factor_service_interface::TwoFactorServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::recovery_code;
+use crate::domain::user::recovery_code_repository_interface::RecoveryCodeRepositoryInterface;
+use crate::domain::user::user;
+use crate::domain::user::user::ModelEx as UserModel;
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::persistence::redis_client::two_factor;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::presentation::two_factor::two_factor::{
+    RecoveryCodesResponse, TotpEnrollmentResponse, TwoFactorStatusResponse,
+};
+use crate::util::constant::{EXPIRE_TOTP_ENROLLMENT_SECS, RECOVERY_CODE_COUNT, RECOVERY_CODE_LEN};
+use crate::util::{hash, random, totp};
+use rdkafka::producer::FutureProducer;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Application service - TOTP enrollment and recovery codes
+pub struct TwoFactorService {
+    pub config: Arc<AppConfig>,
+    pub redis: Arc<RedisConnectionPool>,
+    pub kafka_producer: Arc<FutureProducer>,
+}
+
+impl TwoFactorService {
+    pub fn new(
+        config: Arc<AppConfig>,
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+    ) -> Self {
+        Self { config, redis, kafka_producer }
+    }
+
+    async fn find_active_user(&self, conn: &DatabaseTransaction, user_id: i64) -> AppResult<UserModel> {
+        user::Entity::find_user_by_id(conn, user_id)
+            .await?
+            .filter(|user| !user.is_deleted)
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User with id {} not found", user_id),
+            })
+    }
+
+    /// Replaces the user's recovery codes and returns the new plain codes.
+    async fn issue_recovery_codes(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<RecoveryCodesResponse> {
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| format_recovery_code(&random::generate_random_string(RECOVERY_CODE_LEN)))
+            .collect();
+        let code_hashes = recovery_codes
+            .iter()
+            .map(|code| hash::sha256_hex(normalize_recovery_code(code)))
+            .collect();
+        recovery_code::Entity::replace_recovery_codes(conn, user_id, code_hashes).await?;
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+
+    async fn clear_profile_cache(&self, user_id: i64) {
+        let _ = self.redis.delete_key(&format!("profile:user_id:{}", user_id).into()).await;
+    }
+}
+
+/// `abcde12345` -> `ABCDE-12345`, easier to read back from paper
+fn format_recovery_code(code: &str) -> String {
+    let code = code.to_uppercase();
+    let (head, tail) = code.split_at(code.len() / 2);
+    format!("{head}-{tail}")
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_uppercase()
+}
+
+impl TwoFactorServiceInterface for TwoFactorService {
+    async fn status(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<TwoFactorStatusResponse> {
+        let user = self.find_active_user(conn, user_id).await?;
+        let recovery_codes_remaining = if user.has_two_factor() {
+            recovery_code::Entity::count_unused_recovery_codes(conn, user_id).await?
+        } else {
+            0
+        };
+        Ok(TwoFactorStatusResponse { enabled: user.has_two_factor(), recovery_codes_remaining })
+    }
+
+    async fn enroll_totp(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<TotpEnrollmentResponse> {
+        let user = self.find_active_user(conn, user_id).await?;
+        if user.has_two_factor() {
+            return Err(AppError::BadRequestError(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        // The secret only becomes active once the user proves the authenticator has it
+        let secret = totp::generate_secret();
+        two_factor::store_pending_totp_secret(&self.redis, user_id, &secret).await?;
+
+        Ok(TotpEnrollmentResponse {
+            otpauth_uri: totp::otpauth_uri(&self.config.auth.totp_issuer, &user.email, &secret),
+            secret,
+            expire_in: EXPIRE_TOTP_ENROLLMENT_SECS.as_secs(),
+        })
+    }
+
+    async fn confirm_totp(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &ConfirmTotpCommand,
+    ) -> AppResult<RecoveryCodesResponse> {
+        let secret = two_factor::find_pending_totp_secret(&self.redis, user_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequestError("No pending enrollment, please start again".to_string())
+            })?;
+        if !two_factor::accept_totp_code(&self.redis, user_id, &secret, &command.code).await? {
+            return Err(AppError::BadRequestError("The code is not correct".to_string()));
+        }
+
+        let user = self.find_active_user(conn, user_id).await?.enable_two_factor(secret)?;
+        user::Entity::update_user(conn, user.into_active_model()).await?;
+        let recovery_codes = self.issue_recovery_codes(conn, user_id).await?;
+
+        two_factor::delete_pending_totp_secret(&self.redis, user_id).await?;
+        self.clear_profile_cache(user_id).await;
+        Ok(recovery_codes)
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &TwoFactorCodeCommand,
+    ) -> AppResult<RecoveryCodesResponse> {
+        let user = self.find_active_user(conn, user_id).await?;
+        if !user.has_two_factor() {
+            return Err(AppError::BadRequestError(
+                "Two-factor authentication is not enabled".to_string(),
+            ));
+        }
+        if !self.verify_second_factor(conn, &user, &command.code).await? {
+            return Err(AppError::BadRequestError("The code is not correct".to_string()));
+        }
+        self.issue_recovery_codes(conn, user_id).await
+    }
+
+    async fn disable(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &TwoFactorCodeCommand,
+    ) -> AppResult<()> {
+        let user = self.find_active_user(conn, user_id).await?;
+        if !self.verify_second_factor(conn, &user, &command.code).await? {
+            return Err(AppError::BadRequestError("The code is not correct".to_string()));
+        }
+        user::Entity::update_user(conn, user.disable_two_factor()?.into_active_model()).await?;
+        recovery_code::Entity::delete_recovery_codes(conn, user_id).await?;
+
+        two_factor::forget_totp_state(&self.redis, user_id).await?;
+        self.clear_profile_cache(user_id).await;
+        Ok(())
+    }
+
+    async fn admin_reset(&self, conn: &DatabaseTransaction, user_id: i64) -> AppResult<()> {
+        let user = self.find_active_user(conn, user_id).await?;
+        user::Entity::update_user(conn, user.disable_two_factor()?.into_active_model()).await?;
+        recovery_code::Entity::delete_recovery_codes(conn, user_id).await?;
+
+        two_factor::forget_totp_state(&self.redis, user_id).await?;
+        self.clear_profile_cache(user_id).await;
+        log::warn!("Two-factor authentication reset by an admin for user {user_id}.");
+        Ok(())
+    }
+
+    async fn verify_second_factor(
+        &self,
+        conn: &DatabaseTransaction,
+        user: &UserModel,
+        code: &str,
+    ) -> AppResult<bool> {
+        let Some(secret) = user.totp_secret.as_deref().filter(|_| user.has_two_factor()) else {
+            return Ok(false);
+        };
+
+        let code = code.trim();
+        if code.len() == totp::TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+            return two_factor::accept_totp_code(&self.redis, user.id, secret, code).await;
+        }
+
+        let code_hash = hash::sha256_hex(normalize_recovery_code(code));
+        recovery_code::Entity::consume_recovery_code(conn, user.id, &code_hash).await
+    }
+}
@@ -0,0 +1,52 @@
+use crate::application::two_factor::two_factor_command::{ConfirmTotpCommand, TwoFactorCodeCommand};
+use crate::core::error::AppResult;
+use crate::domain::user::user::ModelEx as UserModel;
+use crate::presentation::two_factor::two_factor::{
+    RecoveryCodesResponse, TotpEnrollmentResponse, TwoFactorStatusResponse,
+};
+use sea_orm::DatabaseTransaction;
+
+pub trait TwoFactorServiceInterface: Send + Sync + 'static {
+    async fn status(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<TwoFactorStatusResponse>;
+
+    async fn enroll_totp(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+    ) -> AppResult<TotpEnrollmentResponse>;
+
+    async fn confirm_totp(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &ConfirmTotpCommand,
+    ) -> AppResult<RecoveryCodesResponse>;
+
+    async fn regenerate_recovery_codes(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &TwoFactorCodeCommand,
+    ) -> AppResult<RecoveryCodesResponse>;
+
+    async fn disable(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        command: &TwoFactorCodeCommand,
+    ) -> AppResult<()>;
+
+    async fn admin_reset(&self, conn: &DatabaseTransaction, user_id: i64) -> AppResult<()>;
+
+    /// Accepts either a TOTP code or an unused recovery code, which is spent.
+    async fn verify_second_factor(
+        &self,
+        conn: &DatabaseTransaction,
+        user: &UserModel,
+        code: &str,
+    ) -> AppResult<bool>;
+}
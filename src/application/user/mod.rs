@@ -1,3 +1,7 @@
+pub mod password_policy_service;
+pub mod password_policy_service_interface;
 pub mod user_command;
 pub mod user_service;
 pub mod user_service_interface;
+pub mod user_status_service;
+pub mod user_status_service_interface;
@@ -0,0 +1,86 @@
+use crate::application::user::password_policy_service_interface::PasswordPolicyServiceInterface;
+use crate::core::configure::app::AppConfig;
+use crate::core::configure::auth::PasswordPolicyConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::password_history;
+use crate::domain::user::password_history_repository_interface::PasswordHistoryRepositoryInterface;
+use crate::domain::user::password_policy::{self, PasswordViolation};
+use crate::domain::user::user::ModelEx as UserModel;
+use crate::util::password;
+use sea_orm::DatabaseTransaction;
+use std::sync::Arc;
+
+/// Application service - the password policy every new password goes through
+pub struct PasswordPolicyService {
+    pub config: Arc<AppConfig>,
+}
+
+impl PasswordPolicyService {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        Self { config }
+    }
+
+    fn policy(&self) -> &PasswordPolicyConfig {
+        &self.config.auth.password_policy
+    }
+
+    /// The current password counts as used, even for users who predate the history.
+    async fn is_reused(&self, conn: &DatabaseTransaction, password: &str, user_res: &UserModel) -> AppResult<bool> {
+        let history_size = self.policy().history_size;
+        if history_size == 0 {
+            return Ok(false);
+        }
+        let mut hashes =
+            password_history::Entity::list_recent_password_hashes(conn, user_res.id, history_size).await?;
+        hashes.extend(user_res.password.clone());
+
+        for hash in hashes {
+            if password::verify(password.to_string(), hash).await.is_ok() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl PasswordPolicyServiceInterface for PasswordPolicyService {
+    async fn validate_new_password(
+        &self,
+        conn: &DatabaseTransaction,
+        password: &str,
+        username: &str,
+        email: &str,
+        existing: Option<&UserModel>,
+    ) -> AppResult<()> {
+        let mut violations = password_policy::check(self.policy(), password, username, email);
+        if let Some(user_res) = existing {
+            if self.is_reused(conn, password, user_res).await? {
+                violations.push(PasswordViolation::RecentlyUsed { history_size: self.policy().history_size });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::PasswordPolicyError(violations))
+        }
+    }
+
+    async fn remember_password(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        password_hash: &str,
+    ) -> AppResult<()> {
+        let history_size = self.policy().history_size;
+        if history_size == 0 {
+            return Ok(());
+        }
+        password_history::Entity::create_password_history(
+            conn,
+            password_history::ActiveModel::new_password_history(user_id, password_hash.to_string()),
+        )
+        .await?;
+        password_history::Entity::prune_password_history(conn, user_id, history_size).await
+    }
+}
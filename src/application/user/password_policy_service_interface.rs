@@ -0,0 +1,24 @@
+use crate::core::error::AppResult;
+use crate::domain::user::user::ModelEx as UserModel;
+use sea_orm::DatabaseTransaction;
+
+pub trait PasswordPolicyServiceInterface: Send + Sync + 'static {
+    /// Fails with every unmet rule of the policy. `existing` is the user whose password
+    /// changes, absent on sign-up; only then can reuse be checked.
+    async fn validate_new_password(
+        &self,
+        conn: &DatabaseTransaction,
+        password: &str,
+        username: &str,
+        email: &str,
+        existing: Option<&UserModel>,
+    ) -> AppResult<()>;
+
+    /// Adds the hash of a password the user just set to the history.
+    async fn remember_password(
+        &self,
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        password_hash: &str,
+    ) -> AppResult<()>;
+}
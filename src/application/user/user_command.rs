@@ -1,3 +1,5 @@
+use crate::domain::user::user::Status;
+use crate::util::constant::CODE_LEN;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -7,21 +9,28 @@ use validator::Validate;
 pub struct AdminCreateUserCommand {
     #[validate(email)]
     pub email: String,
+    /// Left out to have the user choose one through the password reset mail
     #[validate(length(min = 8, max = 25, message = "Mật khẩu phải từ 8 đến 25 ký tự"))]
-    pub password: String,
-    #[validate(length(min = 2, max = 30, message = "Họ và tên phải từ 2 đến 30 ký tự"))]
-    pub fullname: String,
+    pub password: Option<String>,
+    #[validate(length(min = 1, max = 30, message = "Tên phải từ 1 đến 30 ký tự"))]
+    pub first_name: String,
+    #[validate(length(min = 1, max = 30, message = "Họ phải từ 1 đến 30 ký tự"))]
+    pub last_name: String,
     #[validate(length(min = 3, max = 50, message = "Tên đăng nhập phải từ 3 đến 50 ký tự"))]
     pub username: String,
     pub birthday: Option<NaiveDate>,
     #[validate(url)]
     pub picture: Option<String>,
+    #[validate(length(max = 20, message = "Giới tính tối đa 20 ký tự"))]
     pub gender: Option<String>,
     pub phone_number: Option<String>,
-    pub address: Option<String>,
+    #[validate(length(min = 2, max = 10, message = "Mã ngôn ngữ phải từ 2 đến 10 ký tự"))]
     pub language: Option<String>,
-    #[validate(range(min = 0, max = 1))]
-    pub status: Option<i16>,
+    /// `ACTIVE` when left out. Only `PENDING` accounts still have to confirm their email.
+    pub status: Option<Status>,
+    /// Role granted right away, e.g. `support`
+    #[validate(length(min = 1, max = 50, message = "Tên vai trò phải từ 1 đến 50 ký tự"))]
+    pub role: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
@@ -51,3 +60,33 @@ pub struct UpdateProfileCommand {
     pub address: Option<String>,
     pub language: Option<String>,
 }
+
+/// `validator` compares lengths as `u64`
+const VERIFY_CODE_LEN: u64 = CODE_LEN as u64;
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct VerifyEmailCommand {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(equal = VERIFY_CODE_LEN))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ResendVerificationEmailCommand {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_code_must_have_the_issued_length() {
+        let command = |code: String| VerifyEmailCommand { email: "jane@example.com".to_string(), code };
+        assert!(command("1".repeat(CODE_LEN)).validate().is_ok());
+        assert!(command("1".repeat(CODE_LEN - 1)).validate().is_err());
+        assert!(command("1".repeat(CODE_LEN + 1)).validate().is_err());
+    }
+}
@@ -1,13 +1,30 @@
 use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::persistence::redis_client::session;
+use crate::infrastructure::third_party::mail::{MailMessage, MailSender};
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::SetnxReply;
+use crate::application::after_commit::AfterCommit;
+use crate::application::policy::ownership_policy;
+use crate::application::user::password_policy_service::PasswordPolicyService;
+use crate::application::user::password_policy_service_interface::PasswordPolicyServiceInterface;
+use crate::application::user::user_command::{ResendVerificationEmailCommand, VerifyEmailCommand};
 use crate::application::user::user_service_interface::UserServiceInterface;
+use crate::application::user::user_status_service::UserStatusService;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
 use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::domain::user::permission;
 use crate::presentation::user::user::{UserSerializer, CreateUserRequest, UpdateUserRequest};
-use crate::util::password;
+use crate::util::claim::UserClaims;
+use crate::util::constant::{
+    CODE_LEN, EXPIRE_RESEND_VERIFY_EMAIL_SECS, EXPIRE_SESSION_CODE_SECS, MAX_VERIFY_EMAIL_ATTEMPTS,
+};
+use crate::util::string::normalize_email;
+use crate::util::{password, random};
 use log::error;
 use rdkafka::producer::FutureProducer;
 use sea_orm::{DatabaseTransaction, IntoActiveModel};
 use std::sync::Arc;
+use uuid::Uuid;
 use crate::domain::user;
 
 /// Application service - orchestrates domain logic, database, and external services
@@ -15,14 +32,85 @@ use crate::domain::user;
 pub struct UserService {
     pub redis: Arc<RedisConnectionPool>,
     pub kafka_producer: Arc<FutureProducer>,
+    pub mail_sender: Arc<dyn MailSender>,
+    pub password_policy_service: Arc<PasswordPolicyService>,
+    pub user_status_service: Arc<UserStatusService>,
 }
 
 impl UserService {
-    pub fn new(redis: Arc<RedisConnectionPool>, kafka_producer: Arc<FutureProducer>) -> Self {
-        Self { redis, kafka_producer }
+    pub fn new(
+        redis: Arc<RedisConnectionPool>,
+        kafka_producer: Arc<FutureProducer>,
+        mail_sender: Arc<dyn MailSender>,
+        password_policy_service: Arc<PasswordPolicyService>,
+        user_status_service: Arc<UserStatusService>,
+    ) -> Self {
+        Self { redis, kafka_producer, mail_sender, password_policy_service, user_status_service }
     }
 }
 
+/// Issues a fresh verification code for the user, replacing any previous one, and mails it.
+/// Failed attempts are kept, a new code does not buy more guesses.
+async fn send_verification_code(
+    redis: &RedisConnectionPool,
+    mail_sender: &dyn MailSender,
+    user: &user::user::ModelEx,
+) -> AppResult<()> {
+    let code = random::generate_random_code(CODE_LEN);
+    redis
+        .set_key_with_expiry::<String>(
+            &verify_email_key(user.id).into(),
+            code.clone(),
+            EXPIRE_SESSION_CODE_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+
+    mail_sender
+        .send(MailMessage {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Your verification code is: {code}\n\n\
+                 The code expires in {} hours.",
+                EXPIRE_SESSION_CODE_SECS.as_secs() / 3600
+            ),
+        })
+        .await
+}
+
+fn verify_email_key(user_id: i64) -> String {
+    format!("verify_email:user_id:{user_id}")
+}
+
+fn verify_email_attempts_key(user_id: i64) -> String {
+    format!("verify_email:attempts:user_id:{user_id}")
+}
+
+fn verify_email_resend_key(email: &str) -> String {
+    format!("verify_email:resend:email:{email}")
+}
+
+/// Allows one resend per address every `EXPIRE_RESEND_VERIFY_EMAIL_SECS`, whether or not
+/// an account uses it.
+async fn throttle_resend(redis: &RedisConnectionPool, email: &str) -> AppResult<()> {
+    let accepted = redis
+        .set_key_if_not_exists_with_expiry::<String>(
+            &verify_email_resend_key(&normalize_email(email)).into(),
+            "1".to_string(),
+            Some(EXPIRE_RESEND_VERIFY_EMAIL_SECS.as_secs() as i64),
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    if !matches!(accepted, SetnxReply::KeySet) {
+        return Err(AppError::TooManyRequestsError(format!(
+            "Please wait {} seconds before requesting another code",
+            EXPIRE_RESEND_VERIFY_EMAIL_SECS.as_secs()
+        )));
+    }
+    Ok(())
+}
+
 impl UserServiceInterface for UserService {
     async fn create_user(
         &self,
@@ -43,18 +131,31 @@ impl UserServiceInterface for UserService {
             });
         }
 
+        self.password_policy_service
+            .validate_new_password(conn, &request.password, &request.username, &request.email, None)
+            .await?;
+
         // External service: Hash password
         let hashed_password = password::hash(request.password.clone()).await?;
 
         let user = user::user::ModelEx::create_new_user(
             &request
-        )?;
-
-
+        )?
+        .change_password(hashed_password);
 
         // Infrastructure: Persist user (Model → ActiveModel in repository)
-        let created_user = user::user::Entity::create_user(conn, user.into_active_model()).await?;
-
+        user::user::Entity::create_user(conn, user.into_active_model()).await?;
+
+        // External service: Send the email verification code
+        let created_user = user::user::Entity::find_user_by_username(conn, &request.username)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
+                detail: format!("User {} not found after insert", request.username),
+            })?;
+        if let Some(ref hashed_password) = created_user.password {
+            self.password_policy_service.remember_password(conn, created_user.id, hashed_password).await?;
+        }
+        send_verification_code(&self.redis, self.mail_sender.as_ref(), &created_user).await?;
 
         // TODO: External service - Kafka event publishing
         // self.kafka_producer.send(...)
@@ -65,9 +166,16 @@ impl UserServiceInterface for UserService {
     async fn update_user(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
         request: UpdateUserRequest,
-    ) -> AppResult<bool> {
+    ) -> AppResult<AfterCommit> {
+        // Policy: users edit their own profile, staff anyone's; only staff change the status
+        ownership_policy::authorize_owner(caller, id, permission::USERS_WRITE)?;
+        if request.status.is_some() {
+            ownership_policy::authorize_staff(caller, permission::USERS_WRITE)?;
+        }
+
         // Database: Get existing user
         let existing_user_opt = user::user::Entity::find_user_by_id(conn, id).await?;
         let existing_user = existing_user_opt.ok_or_else(|| AppError::EntityNotFoundError {
@@ -76,7 +184,7 @@ impl UserServiceInterface for UserService {
 
         // Database: Check email uniqueness if changing
         if let Some(ref email) = request.email {
-            if email != &existing_user.email {
+            if normalize_email(email) != existing_user.email {
                 if user::user::Entity::email_exists(conn, email).await? {
                     return Err(AppError::EntityExistsError {
                         detail: format!("Email {} already exists", email),
@@ -88,12 +196,37 @@ impl UserServiceInterface for UserService {
         // Convert ModelEx to Model (remove relationships for update)
 
         // Domain: Update model with validation
+        let previous_status = existing_user.status.clone();
+        let previous_email = existing_user.email.clone();
         let updated_model = existing_user.update_from(
             &request
         )?;
 
         // Infrastructure: Persist updated user (Model → ActiveModel in repository)
-        let updated_user = user::user::Entity::update_user(conn, updated_model.into_active_model()).await?;
+        user::user::Entity::update_user(conn, updated_model.clone().into_active_model()).await?;
+        let mut after_commit = AfterCommit::new();
+        if updated_model.email != previous_email {
+            // The code goes to the new address, once it is saved
+            let redis = self.redis.clone();
+            let mail_sender = self.mail_sender.clone();
+            let user_res = updated_model.clone();
+            after_commit.push(async move {
+                send_verification_code(&redis, mail_sender.as_ref(), &user_res).await
+            });
+        }
+        if updated_model.status != previous_status {
+            let status_effects = self
+                .user_status_service
+                .record_status_change(
+                    conn,
+                    &updated_model,
+                    &previous_status,
+                    Some(caller.user_id),
+                    request.status_reason.clone(),
+                )
+                .await?;
+            after_commit.append(status_effects);
+        }
 
         // External service: Clear Redis cache
         let _ = self.redis.delete_key(&format!("profile:user_id:{}", id).to_string().into()).await;
@@ -101,7 +234,7 @@ impl UserServiceInterface for UserService {
         // TODO: External service - Kafka event publishing
         // self.kafka_producer.send(...)
 
-        Ok(true)
+        Ok(after_commit)
     }
 
     async fn get_profile(
@@ -126,7 +259,9 @@ impl UserServiceInterface for UserService {
                 // Database: Fetch from database
                 match user::user::Entity::find_user_by_id(conn, user_id).await {
                     Ok(Some(profile)) => {
-                        // External service: Cache in Redis
+                        // External service: Cache in Redis. Only the serializer goes there, the
+                        // model carries the password hash and the TOTP secret
+                        let profile = UserSerializer::from(profile);
                         let _ = self
                             .redis
                             .serialize_and_set_key_with_expiry(
@@ -137,7 +272,7 @@ impl UserServiceInterface for UserService {
                                 88640,
                             )
                             .await;
-                        Ok(UserSerializer::from(profile))
+                        Ok(profile)
                     },
                     Err(_error) => Err(AppError::EntityNotFoundError {
                         detail: format!("User not found by id {}", user_id),
@@ -152,11 +287,26 @@ impl UserServiceInterface for UserService {
         }
     }
 
+    async fn get_user(
+        &self,
+        conn: &DatabaseTransaction,
+        caller: &UserClaims,
+        id: i64,
+    ) -> AppResult<UserSerializer> {
+        // Policy: users see their own profile, staff anyone's
+        ownership_policy::authorize_owner(caller, id, permission::USERS_READ)?;
+        self.get_profile(conn, id).await
+    }
+
     async fn delete_user(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<bool> {
+        // Policy: users close their own account, staff anyone's
+        ownership_policy::authorize_owner(caller, id, permission::USERS_DELETE)?;
+
         // Database: Check if user exists
         let user = user::user::Entity::find_user_by_id(conn, id).await?;
         if user.is_none() {
@@ -168,8 +318,9 @@ impl UserServiceInterface for UserService {
         // Database: Soft delete
         user::user::Entity::delete_user(conn, id).await?;
 
-        // External service: Clear Redis cache
+        // External service: Clear Redis cache and sign the user out everywhere
         let _ = self.redis.delete_key(&format!("profile:user_id:{}", id).to_string().into()).await;
+        session::revoke_all_sessions(&self.redis, id, None).await?;
 
         // TODO: External service - Kafka event publishing
         // self.kafka_producer.send(...)
@@ -197,12 +348,119 @@ impl UserServiceInterface for UserService {
         Ok(user_serializers)
     }
 
-    async fn logout(&self, _conn: &DatabaseTransaction, user_id: i64) -> AppResult<bool> {
-        // External service: Clear Redis cache (session invalidation)
-        match self.redis.delete_key(&format!("profile:user_id:{user_id}").to_string().into()).await
-        {
-            Ok(_) => Ok(true),
-            Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    async fn logout(
+        &self,
+        _conn: &DatabaseTransaction,
+        user_id: i64,
+        session_id: &Uuid,
+    ) -> AppResult<bool> {
+        // External service: Revoke only the session of the calling device
+        session::revoke_session(&self.redis, user_id, session_id).await?;
+        Ok(true)
+    }
+
+    async fn verify_email(
+        &self,
+        conn: &DatabaseTransaction,
+        request: VerifyEmailCommand,
+    ) -> AppResult<bool> {
+        let invalid_code =
+            || AppError::BadRequestError("Verification code is invalid or has expired".to_string());
+
+        let existing_user = user::user::Entity::find_user_by_email(conn, &request.email)
+            .await?
+            .filter(|user| !user.is_deleted)
+            .ok_or_else(invalid_code)?;
+        let user_id = existing_user.id;
+
+        // External service: Count attempts so the short code cannot be brute forced
+        let attempts = self
+            .redis
+            .increment_key(
+                &verify_email_attempts_key(user_id).into(),
+                EXPIRE_SESSION_CODE_SECS.as_secs() as i64,
+            )
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        if attempts > MAX_VERIFY_EMAIL_ATTEMPTS {
+            return Err(AppError::TooManyRequestsError(
+                "Too many attempts, please try again later".to_string(),
+            ));
+        }
+
+        let stored_code = self
+            .redis
+            .get_key::<Option<String>>(&verify_email_key(user_id).into())
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?
+            .ok_or_else(invalid_code)?;
+        if stored_code != request.code {
+            return Err(invalid_code());
+        }
+
+        // Domain: Mark the email as verified and activate the account
+        let previous_status = existing_user.status.clone();
+        let verified_user = existing_user.verify_email()?;
+        user::user::Entity::update_user(conn, verified_user.clone().into_active_model()).await?;
+        if verified_user.status != previous_status {
+            // Pending to active only unblocks, safe to do before the commit
+            self.user_status_service
+                .record_status_change(
+                    conn,
+                    &verified_user,
+                    &previous_status,
+                    Some(user_id),
+                    Some("Email address verified".to_string()),
+                )
+                .await?
+                .run()
+                .await?;
         }
+
+        // External service: Drop the spent code and the cached profile
+        let _ = self
+            .redis
+            .delete_multiple_keys(&[
+                verify_email_key(user_id).into(),
+                verify_email_attempts_key(user_id).into(),
+            ])
+            .await;
+        let _ = self.redis.delete_key(&format!("profile:user_id:{}", user_id).into()).await;
+
+        Ok(true)
+    }
+
+    async fn resend_verification_email(
+        &self,
+        conn: &DatabaseTransaction,
+        request: ResendVerificationEmailCommand,
+    ) -> AppResult<bool> {
+        // Throttled before the lookup, so unknown and real addresses answer the same way
+        throttle_resend(&self.redis, &request.email).await?;
+
+        // Unknown or already verified emails succeed silently so accounts are not revealed
+        let existing_user = match user::user::Entity::find_user_by_email(conn, &request.email).await? {
+            Some(user) if !user.is_deleted && user.email_verified_at.is_none() => user,
+            _ => return Ok(true),
+        };
+
+        send_verification_code(&self.redis, self.mail_sender.as_ref(), &existing_user).await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resends_are_throttled_per_address_before_any_lookup() {
+        let redis = RedisConnectionPool::mock().await;
+        assert!(throttle_resend(&redis, "nobody@example.com").await.is_ok());
+        assert!(matches!(
+            throttle_resend(&redis, " Nobody@Example.com").await,
+            Err(AppError::TooManyRequestsError(_))
+        ));
+        assert!(throttle_resend(&redis, "jane@example.com").await.is_ok());
     }
 }
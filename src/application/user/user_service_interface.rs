@@ -1,6 +1,10 @@
+use crate::application::after_commit::AfterCommit;
+use crate::application::user::user_command::{ResendVerificationEmailCommand, VerifyEmailCommand};
 use crate::core::error::AppResult;
 use crate::presentation::user::user::{UserSerializer, CreateUserRequest, UpdateUserRequest};
+use crate::util::claim::UserClaims;
 use sea_orm::DatabaseTransaction;
+use uuid::Uuid;
 
 pub trait UserServiceInterface: Send + Sync + 'static {
     async fn create_user(
@@ -12,9 +16,10 @@ pub trait UserServiceInterface: Send + Sync + 'static {
     async fn update_user(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
         request: UpdateUserRequest,
-    ) -> AppResult<bool>;
+    ) -> AppResult<AfterCommit>;
 
     async fn get_profile(
         &self,
@@ -22,9 +27,18 @@ pub trait UserServiceInterface: Send + Sync + 'static {
         user_id: i64,
     ) -> AppResult<UserSerializer>;
 
+    /// Another user's profile, for the user themselves or staff with `users:read`.
+    async fn get_user(
+        &self,
+        conn: &DatabaseTransaction,
+        caller: &UserClaims,
+        id: i64,
+    ) -> AppResult<UserSerializer>;
+
     async fn delete_user(
         &self,
         conn: &DatabaseTransaction,
+        caller: &UserClaims,
         id: i64,
     ) -> AppResult<bool>;
 
@@ -35,5 +49,22 @@ pub trait UserServiceInterface: Send + Sync + 'static {
         page_size: u64,
     ) -> AppResult<Vec<UserSerializer>>;
 
-    async fn logout(&self, conn: &DatabaseTransaction, id: i64) -> AppResult<bool>;
+    async fn logout(
+        &self,
+        conn: &DatabaseTransaction,
+        id: i64,
+        session_id: &Uuid,
+    ) -> AppResult<bool>;
+
+    async fn verify_email(
+        &self,
+        conn: &DatabaseTransaction,
+        request: VerifyEmailCommand,
+    ) -> AppResult<bool>;
+
+    async fn resend_verification_email(
+        &self,
+        conn: &DatabaseTransaction,
+        request: ResendVerificationEmailCommand,
+    ) -> AppResult<bool>;
 }
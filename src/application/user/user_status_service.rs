@@ -0,0 +1,88 @@
+use crate::application::after_commit::AfterCommit;
+use crate::application::user::user_status_service_interface::UserStatusServiceInterface;
+use crate::core::error::AppResult;
+use crate::domain::user::status_change;
+use crate::domain::user::status_change_repository_interface::StatusChangeRepositoryInterface;
+use crate::domain::user::user::{self, ModelEx as UserModel, Status};
+use crate::domain::user::user_repository_interface::UserRepositoryInterface;
+use crate::infrastructure::persistence::redis_client::account_status::{self, BlockedAccount};
+use crate::infrastructure::persistence::redis_client::session;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use chrono::Utc;
+use sea_orm::{DatabaseTransaction, IntoActiveModel};
+use std::sync::Arc;
+
+/// Application service - the account status machine and who may sign in
+pub struct UserStatusService {
+    pub redis: Arc<RedisConnectionPool>,
+}
+
+impl UserStatusService {
+    pub fn new(redis: Arc<RedisConnectionPool>) -> Self {
+        Self { redis }
+    }
+}
+
+impl UserStatusServiceInterface for UserStatusService {
+    async fn record_status_change(
+        &self,
+        conn: &DatabaseTransaction,
+        user_res: &UserModel,
+        previous: &Status,
+        actor_id: Option<i64>,
+        reason: Option<String>,
+    ) -> AppResult<AfterCommit> {
+        status_change::Entity::create_status_change(
+            conn,
+            status_change::ActiveModel::new_status_change(
+                user_res.id,
+                previous.clone(),
+                user_res.status.clone(),
+                actor_id,
+                reason,
+                user_res.suspended_until,
+            ),
+        )
+        .await?;
+
+        let redis = self.redis.clone();
+        let user_id = user_res.id;
+        let blocked = (!user_res.status.allows_sign_in())
+            .then(|| BlockedAccount { status: user_res.status.clone(), suspended_until: user_res.suspended_until });
+        let mut after_commit = AfterCommit::new();
+        after_commit.push(async move {
+            match blocked {
+                Some(blocked) => {
+                    account_status::block_account(&redis, user_id, &blocked).await?;
+                    session::revoke_all_sessions(&redis, user_id, None).await?;
+                },
+                None => account_status::unblock_account(&redis, user_id).await?,
+            }
+            let _ = redis.delete_key(&format!("profile:user_id:{user_id}").into()).await;
+            Ok(())
+        });
+
+        log::info!(
+            "User {} went from {:?} to {:?}, changed by {:?}.",
+            user_res.id,
+            previous,
+            user_res.status,
+            actor_id
+        );
+        Ok(after_commit)
+    }
+
+    async fn ensure_can_sign_in(&self, conn: &DatabaseTransaction, user_res: &UserModel) -> AppResult<()> {
+        if user_res.suspension_expired(Utc::now().naive_utc()) {
+            let lifted = user_res.clone().reactivate()?;
+            user::Entity::update_user(conn, lifted.clone().into_active_model()).await?;
+            // Only unblocks, and an ended suspension no longer counts even if this rolls back
+            return self
+                .record_status_change(conn, &lifted, &user_res.status, None, Some("Suspension ended".to_string()))
+                .await?
+                .run()
+                .await;
+        }
+        user_res.ensure_can_sign_in()
+    }
+}
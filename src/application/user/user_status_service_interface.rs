@@ -0,0 +1,21 @@
+use crate::application::after_commit::AfterCommit;
+use crate::core::error::AppResult;
+use crate::domain::user::user::{ModelEx as UserModel, Status};
+use sea_orm::DatabaseTransaction;
+
+pub trait UserStatusServiceInterface: Send + Sync + 'static {
+    /// Records that `user_res`, already saved, moved from `previous` to its current status.
+    /// Accounts that may no longer sign in lose their sessions and tokens once the returned
+    /// effects run, after commit. `actor_id` is absent when the system made the change.
+    async fn record_status_change(
+        &self,
+        conn: &DatabaseTransaction,
+        user_res: &UserModel,
+        previous: &Status,
+        actor_id: Option<i64>,
+        reason: Option<String>,
+    ) -> AppResult<AfterCommit>;
+
+    /// Lifts a suspension whose end has passed, then refuses accounts that may not sign in.
+    async fn ensure_can_sign_in(&self, conn: &DatabaseTransaction, user_res: &UserModel) -> AppResult<()>;
+}
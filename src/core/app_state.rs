@@ -1,13 +1,29 @@
+use crate::core::client::http::{ClientBuilder, HttpClient};
 use crate::core::configure::app::AppConfig;
 use crate::core::configure::kafka::KafkaConfig;
 use crate::core::error::{AppError, AppResult};
 use crate::infrastructure::persistence::postgres::{DatabaseClient, DatabaseClientExt};
+use crate::infrastructure::persistence::redis_client::tenant_keys;
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::infrastructure::third_party::redis::types::RedisSettings;
 use crate::application::user::user_service::UserService;
 use crate::application::authen::authen_service::AuthenService;
+use crate::application::authen::oidc_service::OidcService;
+use crate::application::authen::passwordless_service::PasswordlessService;
 use crate::application::address::address_service::AddressService;
+use crate::application::admin::admin_service::AdminService;
+use crate::application::api_key::api_key_service::ApiKeyService;
+use crate::application::identity::identity_service::IdentityService;
+use crate::application::impersonation::impersonation_service::ImpersonationService;
+use crate::application::oauth::oauth_service::OauthService;
+use crate::application::session::session_service::SessionService;
+use crate::application::two_factor::two_factor_service::TwoFactorService;
+use crate::application::user::password_policy_service::PasswordPolicyService;
+use crate::application::user::user_status_service::UserStatusService;
 use crate::infrastructure::gateway::service_registry::ServiceRegistry;
+use crate::infrastructure::third_party::mail::{build_mail_sender, MailSender};
+use crate::infrastructure::third_party::oidc::OidcRelyingParty;
+use crate::infrastructure::third_party::sms::{build_sms_sender, SmsSender};
 
 use rdkafka::producer::FutureProducer;
 use std::sync::Arc;
@@ -18,9 +34,22 @@ pub struct AppState {
     pub db: Arc<DatabaseClient>,
     pub redis: Arc<RedisConnectionPool>,
     pub kafka_producer: Arc<FutureProducer>,
+    pub mail_sender: Arc<dyn MailSender>,
+    pub sms_sender: Arc<dyn SmsSender>,
     pub user_service: Arc<UserService>,
     pub authen_service: Arc<AuthenService>,
+    pub passwordless_service: Arc<PasswordlessService>,
     pub address_service: Arc<AddressService>,
+    pub session_service: Arc<SessionService>,
+    pub two_factor_service: Arc<TwoFactorService>,
+    pub password_policy_service: Arc<PasswordPolicyService>,
+    pub user_status_service: Arc<UserStatusService>,
+    pub oauth_service: Arc<OauthService>,
+    pub oidc_service: Arc<OidcService>,
+    pub identity_service: Arc<IdentityService>,
+    pub api_key_service: Arc<ApiKeyService>,
+    pub impersonation_service: Arc<ImpersonationService>,
+    pub admin_service: Arc<AdminService>,
     pub gateway_registry: Arc<ServiceRegistry>,
 }
 
@@ -34,13 +63,81 @@ impl AppState {
                 .await
                 .map_err(|e| AppError::BadRequestError(e.to_string()))?,
         );
+        // Before any request can read the old keys under their new names
+        tenant_keys::migrate_legacy_keys(&redis).await?;
         let kafka_producer = Arc::new(KafkaConfig::new().create_kafka_producer());
-        let authen_service =
-            Arc::new(AuthenService::new(redis.clone(), kafka_producer.clone()));
-        let user_service =
-            Arc::new(UserService::new(redis.clone(), kafka_producer.clone()));
+        let mail_sender = build_mail_sender(
+            config.profile,
+            &config.mail,
+            HttpClient::build_from_config(&config)?,
+        )?;
+        let sms_sender =
+            build_sms_sender(config.profile, &config.sms, HttpClient::build_from_config(&config)?)?;
+        let password_policy_service = Arc::new(PasswordPolicyService::new(config.clone()));
+        let user_status_service = Arc::new(UserStatusService::new(redis.clone()));
+        let two_factor_service = Arc::new(TwoFactorService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+        ));
+        let authen_service = Arc::new(AuthenService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+            mail_sender.clone(),
+            two_factor_service.clone(),
+            password_policy_service.clone(),
+            user_status_service.clone(),
+        ));
+        let passwordless_service = Arc::new(PasswordlessService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+            mail_sender.clone(),
+            sms_sender.clone(),
+            authen_service.clone(),
+        ));
+        let user_service = Arc::new(UserService::new(
+            redis.clone(),
+            kafka_producer.clone(),
+            mail_sender.clone(),
+            password_policy_service.clone(),
+            user_status_service.clone(),
+        ));
         let address_service =
             Arc::new(AddressService::new(redis.clone(), kafka_producer.clone()));
+        let session_service =
+            Arc::new(SessionService::new(redis.clone(), kafka_producer.clone()));
+        let oauth_service =
+            Arc::new(OauthService::new(redis.clone(), kafka_producer.clone()));
+        let oidc_service = Arc::new(OidcService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+            oauth_service.clone(),
+            authen_service.clone(),
+        ));
+        let identity_service = Arc::new(IdentityService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+            OidcRelyingParty::new(HttpClient::build_from_config(&config)?),
+            authen_service.clone(),
+        ));
+        let api_key_service =
+            Arc::new(ApiKeyService::new(redis.clone(), kafka_producer.clone()));
+        let impersonation_service = Arc::new(ImpersonationService::new(
+            config.clone(),
+            redis.clone(),
+            kafka_producer.clone(),
+        ));
+        let admin_service = Arc::new(AdminService::new(
+            redis.clone(),
+            kafka_producer.clone(),
+            authen_service.clone(),
+            password_policy_service.clone(),
+            user_status_service.clone(),
+        ));
         let gateway_registry = Arc::new(ServiceRegistry::with_defaults().await);
 
         Ok(Self {
@@ -48,9 +145,22 @@ impl AppState {
             db,
             redis,
             authen_service,
+            passwordless_service,
             kafka_producer,
+            mail_sender,
+            sms_sender,
             user_service,
             address_service,
+            session_service,
+            two_factor_service,
+            password_policy_service,
+            user_status_service,
+            oauth_service,
+            oidc_service,
+            identity_service,
+            api_key_service,
+            impersonation_service,
+            admin_service,
             gateway_registry,
         })
     }
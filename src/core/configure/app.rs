@@ -1,10 +1,14 @@
+use crate::core::configure::auth::AuthConfig;
 use crate::core::configure::db::DatabaseConfig;
 use crate::core::configure::env::get_env_source;
 use crate::core::configure::http::HttpClientConfig;
 use crate::core::configure::kafka::KafkaConfig;
+use crate::core::configure::mail::MailConfig;
 use crate::core::configure::redis::RedisConfig;
 use crate::core::configure::secret::SecretConfig;
 use crate::core::configure::server::ServerConfig;
+use crate::core::configure::sms::SmsConfig;
+use crate::core::configure::tenant::TenantConfig;
 use crate::util::dir::get_project_root;
 use config::{ConfigError, Environment};
 use serde::{Deserialize, Serialize};
@@ -19,6 +23,11 @@ pub struct AppConfig {
     pub secret: SecretConfig,
     pub http: HttpClientConfig,
     pub kafka: KafkaConfig,
+    pub mail: MailConfig,
+    pub sms: SmsConfig,
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub tenant: TenantConfig,
 }
 
 impl AppConfig {
@@ -88,4 +97,9 @@ impl Profile {
     fn env_source(&self) -> Environment {
         get_env_source(&format!("{}_APP", self.to_string().to_uppercase()))
     }
+
+    /// Profiles whose messages never need to reach anyone, so the outbox is enough
+    pub fn allows_file_delivery(&self) -> bool {
+        matches!(self, Profile::Dev | Profile::Test | Profile::Local)
+    }
 }
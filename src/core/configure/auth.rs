@@ -0,0 +1,129 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthConfig {
+    /// Refuse to log in accounts whose email address has not been confirmed yet
+    pub require_verified_email: bool,
+    /// Issuer shown by authenticator apps next to the TOTP code
+    pub totp_issuer: String,
+    /// Public base URL of this service, the `iss` of ID tokens and the root of the OIDC endpoints
+    pub issuer: String,
+    /// Frontend page that receives magic sign-in links, the token is appended as `?token=`
+    pub magic_link_url: String,
+    /// External OpenID providers users may sign in with
+    #[serde(default)]
+    pub identity_providers: Vec<IdentityProviderConfig>,
+    /// Rules every new password must meet
+    #[serde(default)]
+    pub password_policy: PasswordPolicyConfig,
+    /// Argon2id cost of new password hashes. Stored hashes with other parameters are
+    /// upgraded at the next successful login.
+    #[serde(default)]
+    pub password_hash: PasswordHashConfig,
+    /// Token delivery in cookies for browser clients
+    #[serde(default)]
+    pub cookie_session: CookieSessionConfig,
+}
+
+impl AuthConfig {
+    pub fn identity_provider(&self, name: &str) -> Option<&IdentityProviderConfig> {
+        self.identity_providers.iter().find(|provider| provider.name == name)
+    }
+}
+
+/// One external OpenID provider. Its endpoints and keys are read from
+/// `{issuer}/.well-known/openid-configuration`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct IdentityProviderConfig {
+    /// Short name used in URLs and stored with each linked identity, e.g. `corporate`
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Page of our frontend the provider sends the user back to
+    pub redirect_uri: String,
+    #[serde(default = "default_identity_provider_scope")]
+    pub scope: String,
+}
+
+fn default_identity_provider_scope() -> String {
+    "openid email profile".to_string()
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Refuse passwords that contain the username or the local part of the email
+    pub reject_personal_info: bool,
+    /// Refuse passwords on the bundled list of breached passwords
+    pub reject_breached: bool,
+    /// How many previous passwords may not be reused, 0 turns the history off
+    pub history_size: u64,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 10,
+            max_length: 128,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            reject_personal_info: true,
+            reject_breached: true,
+            history_size: 5,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordHashConfig {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashConfig {
+    /// The `argon2` crate defaults (OWASP's minimum for Argon2id)
+    fn default() -> Self {
+        Self {
+            memory_kib: argon2::Params::DEFAULT_M_COST,
+            iterations: argon2::Params::DEFAULT_T_COST,
+            parallelism: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordHashConfig {
+    pub fn argon2(&self) -> Result<argon2::Argon2<'static>, argon2::Error> {
+        let params = argon2::Params::new(self.memory_kib, self.iterations, self.parallelism, None)?;
+        Ok(argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CookieSessionConfig {
+    /// Let browser clients ask for cookies instead of JSON tokens with `X-Session-Mode: cookie`
+    pub enabled: bool,
+    /// Only send the cookies over HTTPS. Turn it off for plain-HTTP local development only.
+    pub secure: bool,
+    /// `Strict`, `Lax` or `None` (which browsers only accept together with `secure`)
+    pub same_site: String,
+    /// Share the cookies with subdomains, e.g. `.june18.com`. Host-only when absent.
+    pub domain: Option<String>,
+}
+
+impl Default for CookieSessionConfig {
+    fn default() -> Self {
+        Self { enabled: false, secure: true, same_site: "Lax".to_string(), domain: None }
+    }
+}
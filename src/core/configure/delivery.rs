@@ -0,0 +1,78 @@
+use crate::core::configure::app::Profile;
+use config::ConfigError;
+use serde::Deserialize;
+
+/// How mail and text messages leave the service. `file` only writes them to an outbox and
+/// is refused outside the local, dev and test profiles, so a deployment cannot silently
+/// drop every verification code.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryTransport {
+    File,
+    /// JSON `POST` to a provider's API, authenticated with a bearer key
+    Http,
+}
+
+/// The transport `channel` ("mail", "sms") uses under `profile`, or why startup must fail.
+pub fn select_transport(
+    profile: Profile,
+    channel: &str,
+    transport: Option<DeliveryTransport>,
+) -> Result<DeliveryTransport, ConfigError> {
+    match transport {
+        None => Err(ConfigError::Message(format!("No {channel} transport is configured"))),
+        Some(DeliveryTransport::File) if !profile.allows_file_delivery() => Err(ConfigError::Message(
+            format!("The file {channel} transport is for local development and tests, not {profile}"),
+        )),
+        Some(transport) => Ok(transport),
+    }
+}
+
+/// `api_url` and `api_key` of the `http` transport, both required.
+pub fn http_endpoint(
+    channel: &str,
+    api_url: &Option<String>,
+    api_key: &Option<String>,
+) -> Result<(String, String), ConfigError> {
+    let required = |value: &Option<String>, name: &str| {
+        value.clone().filter(|value| !value.trim().is_empty()).ok_or_else(|| {
+            ConfigError::Message(format!("The http {channel} transport needs `{name}`"))
+        })
+    };
+    Ok((required(api_url, "api_url")?, required(api_key, "api_key")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deployments_need_a_real_transport() {
+        for profile in [Profile::Stag, Profile::Prod] {
+            assert!(select_transport(profile, "mail", None).is_err());
+            assert!(select_transport(profile, "mail", Some(DeliveryTransport::File)).is_err());
+            assert_eq!(
+                select_transport(profile, "mail", Some(DeliveryTransport::Http)).unwrap(),
+                DeliveryTransport::Http
+            );
+        }
+        assert_eq!(
+            select_transport(Profile::Local, "sms", Some(DeliveryTransport::File)).unwrap(),
+            DeliveryTransport::File
+        );
+        assert!(select_transport(Profile::Test, "sms", None).is_err());
+    }
+
+    #[test]
+    fn test_http_transport_needs_url_and_key() {
+        let url = Some("https://provider.test/messages".to_string());
+        let key = Some("key".to_string());
+        assert_eq!(
+            http_endpoint("sms", &url, &key).unwrap(),
+            ("https://provider.test/messages".to_string(), "key".to_string())
+        );
+        assert!(http_endpoint("sms", &url, &None).is_err());
+        assert!(http_endpoint("sms", &None, &key).is_err());
+        assert!(http_endpoint("sms", &url, &Some(" ".to_string())).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use crate::core::configure::delivery::DeliveryTransport;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailConfig {
+    pub from_address: String,
+    /// Startup fails when missing
+    #[serde(default)]
+    pub transport: Option<DeliveryTransport>,
+    /// Directory the file mail sender writes messages to, relative to the project root
+    pub outbox_dir: PathBuf,
+    #[serde(default)]
+    pub api_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
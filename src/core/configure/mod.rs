@@ -1,8 +1,13 @@
 pub mod app;
+pub mod auth;
 pub mod db;
+pub mod delivery;
 pub mod env;
 pub mod http;
 pub mod kafka;
+pub mod mail;
 pub mod redis;
 pub mod secret;
 pub mod server;
+pub mod sms;
+pub mod tenant;
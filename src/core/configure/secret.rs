@@ -1,29 +1,102 @@
 use crate::core::configure::app::get_static_dir;
+use crate::core::error::AppResult;
+use crate::util::key_ring::KeyRing;
 use serde::Deserialize;
-use std::{fs, path::PathBuf};
+use std::fs;
+use std::path::{Path, PathBuf};
 
+/// Rotating a key pair without downtime:
+/// 1. Add the new public key to `previous_*_keys` and deploy, so every instance accepts it.
+/// 2. Point the active key paths and `*_kid` at the new pair, and move the old public key
+///    into `previous_*_keys`. Deploy.
+/// 3. Once every token signed with the old key expired, drop it from `previous_*_keys`.
 #[derive(Debug, Deserialize, Clone)]
 pub struct SecretConfig {
+    /// Key paths are relative to the static directory unless absolute
     pub private_access_key: PathBuf,
     pub public_access_key: PathBuf,
     pub private_refresh_key: PathBuf,
     pub public_refresh_key: PathBuf,
+    pub access_kid: String,
+    pub refresh_kid: String,
+    #[serde(default)]
+    pub previous_access_keys: Vec<PreviousKeyConfig>,
+    #[serde(default)]
+    pub previous_refresh_keys: Vec<PreviousKeyConfig>,
+}
+
+/// A public key that is still accepted for verification but no longer signs.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PreviousKeyConfig {
+    pub kid: String,
+    pub public_key: PathBuf,
 }
 
 impl SecretConfig {
-    pub fn read_private_access_key(&self) -> Result<String, std::io::Error> {
-        fs::read_to_string(get_static_dir().unwrap().join("secret_key/private_access_rsa_key.pem"))
+    pub fn read_private_access_key(&self) -> AppResult<String> {
+        read_key(&self.private_access_key)
+    }
+
+    pub fn read_public_access_key(&self) -> AppResult<String> {
+        read_key(&self.public_access_key)
+    }
+
+    pub fn read_private_refresh_key(&self) -> AppResult<String> {
+        read_key(&self.private_refresh_key)
+    }
+
+    pub fn read_public_refresh_key(&self) -> AppResult<String> {
+        read_key(&self.public_refresh_key)
     }
 
-    pub fn read_public_access_key(&self) -> Result<String, std::io::Error> {
-        fs::read_to_string(get_static_dir().unwrap().join("secret_key/public_access_rsa_key.pem"))
+    pub fn read_access_key_ring(&self) -> AppResult<KeyRing> {
+        KeyRing::from_pems(
+            &self.access_kid,
+            &self.read_private_access_key()?,
+            &self.read_public_access_key()?,
+            &read_previous_keys(&self.previous_access_keys)?,
+        )
     }
 
-    pub fn read_private_refresh_key(&self) -> Result<String, std::io::Error> {
-        fs::read_to_string(get_static_dir().unwrap().join("secret_key/private_refresh_rsa_key.pem"))
+    pub fn read_refresh_key_ring(&self) -> AppResult<KeyRing> {
+        KeyRing::from_pems(
+            &self.refresh_kid,
+            &self.read_private_refresh_key()?,
+            &self.read_public_refresh_key()?,
+            &read_previous_keys(&self.previous_refresh_keys)?,
+        )
     }
+}
+
+/// Relative paths are resolved against the static directory, absolute ones are kept.
+fn read_key(path: &Path) -> AppResult<String> {
+    Ok(fs::read_to_string(get_static_dir()?.join(path))?)
+}
+
+fn read_previous_keys(keys: &[PreviousKeyConfig]) -> AppResult<Vec<(String, String)>> {
+    keys.iter().map(|key| Ok((key.kid.clone(), read_key(&key.public_key)?))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_read_from_the_configured_paths() {
+        let refresh_key = get_static_dir().unwrap().join("secret_key/public_refresh_rsa_key.pem");
+        let config = SecretConfig {
+            private_access_key: "secret_key/private_access_rsa_key.pem".into(),
+            public_access_key: refresh_key.clone(),
+            private_refresh_key: "secret_key/private_refresh_rsa_key.pem".into(),
+            public_refresh_key: "secret_key/missing.pem".into(),
+            access_kid: "access".to_string(),
+            refresh_kid: "refresh".to_string(),
+            previous_access_keys: vec![],
+            previous_refresh_keys: vec![],
+        };
 
-    pub fn read_public_refresh_key(&self) -> Result<String, std::io::Error> {
-        fs::read_to_string(get_static_dir().unwrap().join("secret_key/public_refresh_rsa_key.pem"))
+        assert_eq!(config.read_public_access_key().unwrap(), fs::read_to_string(refresh_key).unwrap());
+        assert!(config.read_private_access_key().unwrap().contains("PRIVATE KEY"));
+        assert!(config.read_public_refresh_key().is_err());
     }
 }
@@ -1,10 +1,24 @@
 use serde::Deserialize;
-use std::net::{AddrParseError, SocketAddr};
+use std::net::{AddrParseError, IpAddr, SocketAddr};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerConfig {
     pub addr: String,
     pub port: u16,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    /// Load balancers and gateways in front of the server. Only requests coming from one of
+    /// these may say who the client is through `X-Forwarded-For` or `X-Real-IP`.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Exact origins (scheme, host and port) browsers may call the API from, with
+    /// credentials. Empty allows no cross-origin requests at all.
+    pub allowed_origins: Vec<String>,
 }
 
 impl ServerConfig {
@@ -0,0 +1,20 @@
+use crate::core::configure::delivery::DeliveryTransport;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SmsConfig {
+    /// Alphanumeric sender shown on the recipient's phone
+    pub sender_id: String,
+    /// Product name the message text refers to, e.g. "123456 is your June18 sign-in code"
+    pub brand_name: String,
+    /// Startup fails when missing
+    #[serde(default)]
+    pub transport: Option<DeliveryTransport>,
+    /// Directory the file SMS sender writes messages to, relative to the project root
+    pub outbox_dir: PathBuf,
+    #[serde(default)]
+    pub api_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
@@ -0,0 +1,30 @@
+use crate::core::tenant::DEFAULT_TENANT;
+use serde::Deserialize;
+
+/// Where requests say which tenant they act for. A token's tenant claim always has to
+/// agree with the subdomain or header, if the request has one.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct TenantConfig {
+    /// Request header naming the tenant. On a tenant's subdomain it may only repeat it.
+    pub header: String,
+    /// Domain the tenants' subdomains hang off, e.g. `erp.example.com` resolves
+    /// `acme.erp.example.com` to `acme`. Unset turns subdomain resolution off.
+    pub base_domain: Option<String>,
+    /// Tenants requests may act for, besides the default one. Empty allows any.
+    pub known: Vec<String>,
+}
+
+impl Default for TenantConfig {
+    fn default() -> Self {
+        Self { header: "x-tenant-id".to_string(), base_domain: None, known: Vec::new() }
+    }
+}
+
+impl TenantConfig {
+    pub fn is_known(&self, tenant: &str) -> bool {
+        self.known.is_empty()
+            || tenant == DEFAULT_TENANT
+            || self.known.iter().any(|known| known == tenant)
+    }
+}
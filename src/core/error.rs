@@ -1,4 +1,5 @@
 use crate::core::response::ClientResponseError;
+use crate::domain::user::password_policy::PasswordViolation;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -23,11 +24,25 @@ pub enum AppError {
     #[error("{0}")]
     UserNotActiveError(String),
     #[error("{0}")]
+    AccountSuspendedError(String),
+    #[error("{0}")]
+    AccountBannedError(String),
+    #[error("{0}")]
+    AccountClosedError(String),
+    #[error("{0}")]
     InvalidSessionError(String),
     #[error("{0}")]
+    InvalidCredentialsError(String),
+    #[error("{0}")]
+    AccountLockedError(String),
+    #[error("{0}")]
+    TooManyRequestsError(String),
+    #[error("{0}")]
     ConflictError(String),
     #[error("{0}")]
     UnauthorizedError(String),
+    #[error("{0}")]
+    ReauthenticationRequiredError(String),
     #[error("Bad request {0}")]
     BadRequestError(String),
     #[error("{0}")]
@@ -36,6 +51,8 @@ pub enum AppError {
     InvalidPayloadError(String),
     #[error("{0}")]
     HashError(String),
+    #[error("Password does not meet the password policy")]
+    PasswordPolicyError(Vec<PasswordViolation>),
     #[error(transparent)]
     DatabaseError(#[from] sea_orm::error::DbErr),
     #[error("query_error!")]
@@ -97,6 +114,10 @@ impl AppError {
                 StatusCode::BAD_REQUEST,
                 ClientResponseError::BadRequest { detail: err.to_string() },
             ),
+            PasswordPolicyError(violations) => (
+                StatusCode::BAD_REQUEST,
+                ClientResponseError::PasswordPolicyViolation { violations: violations.clone() },
+            ),
             EntityNotFoundError { detail } => (
                 StatusCode::BAD_REQUEST,
                 ClientResponseError::EntityNotFound { detail: detail.to_string() },
@@ -143,12 +164,47 @@ impl AppError {
                 StatusCode::UNAUTHORIZED,
                 ClientResponseError::BadRequest { detail: err.to_string() },
             ),
+            InvalidCredentialsError(err) => (
+                StatusCode::UNAUTHORIZED,
+                ClientResponseError::InvalidCredentials { detail: err.to_string() },
+            ),
+            AccountLockedError(err) => (
+                StatusCode::LOCKED,
+                ClientResponseError::AccountLocked { detail: err.to_string() },
+            ),
+            TooManyRequestsError(err) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                ClientResponseError::TooManyRequests { detail: err.to_string() },
+            ),
+            PermissionDeniedError(_err) => {
+                (StatusCode::FORBIDDEN, ClientResponseError::PermissionDenied)
+            },
+            UserNotActiveError(err) => (
+                StatusCode::FORBIDDEN,
+                ClientResponseError::UserNotActive { detail: err.to_string() },
+            ),
+            AccountSuspendedError(err) => (
+                StatusCode::FORBIDDEN,
+                ClientResponseError::AccountSuspended { detail: err.to_string() },
+            ),
+            AccountBannedError(err) => (
+                StatusCode::FORBIDDEN,
+                ClientResponseError::AccountBanned { detail: err.to_string() },
+            ),
+            AccountClosedError(err) => (
+                StatusCode::FORBIDDEN,
+                ClientResponseError::AccountClosed { detail: err.to_string() },
+            ),
             ConflictError(_err) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, ClientResponseError::InternalServerError)
             },
             UnauthorizedError(_err) => {
                 (StatusCode::UNAUTHORIZED, ClientResponseError::Unauthorized)
             },
+            ReauthenticationRequiredError(err) => (
+                StatusCode::UNAUTHORIZED,
+                ClientResponseError::ReauthenticationRequired { detail: err.to_string() },
+            ),
             UuidError(_err) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, ClientResponseError::InternalServerError)
             },
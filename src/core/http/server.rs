@@ -1,15 +1,19 @@
 use crate::api::build_routes;
 use crate::core::app_state::AppState;
 use crate::core::configure::app::AppConfig;
+use crate::core::configure::server::CorsConfig;
 use crate::core::error::AppResult;
+use crate::infrastructure::middleware::cookie_session::{CSRF_HEADER, SESSION_MODE_HEADER};
+use crate::infrastructure::middleware::tenant::tenant_middleware;
 use axum::body::Bytes;
 use axum::extract::DefaultBodyLimit;
-use axum::http::{header, HeaderValue};
+use axum::http::{header, HeaderName, HeaderValue, Method};
 use fred::tracing;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tower::ServiceBuilder;
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tower_http::ServiceBuilderExt;
@@ -21,6 +25,46 @@ pub struct AppServer {
     tcp: tokio::net::TcpListener,
 }
 
+/// Cross-origin requests are only allowed from the configured origins, which may then
+/// send credentials (the session cookies). An empty list allows none.
+fn cors_layer(config: &CorsConfig, tenant_header: &str) -> CorsLayer {
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log::warn!("Ignoring invalid CORS origin {origin}: {err}");
+                None
+            },
+        })
+        .collect();
+    let mut headers = vec![
+        header::AUTHORIZATION,
+        header::CONTENT_TYPE,
+        header::ACCEPT,
+        HeaderName::from_static(CSRF_HEADER),
+        HeaderName::from_static(SESSION_MODE_HEADER),
+    ];
+    match HeaderName::from_bytes(tenant_header.as_bytes()) {
+        Ok(name) => headers.push(name),
+        Err(err) => log::warn!("Ignoring invalid tenant header {tenant_header}: {err}"),
+    }
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_credentials(true)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(headers)
+        .max_age(Duration::from_secs(3600))
+}
+
 impl AppServer {
     pub async fn new(mut config: AppConfig) -> AppResult<Self> {
         let tcp = tokio::net::TcpListener::bind(config.server.get_socket_addr()?).await?;
@@ -61,11 +105,12 @@ impl AppServer {
 
         let app = router
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", api.clone()))
-            .layer(CorsLayer::permissive())
+            .layer(axum::middleware::from_fn_with_state(self.state.clone(), tenant_middleware))
+            .layer(cors_layer(&self.state.config.server.cors, &self.state.config.tenant.header))
             .layer(middleware)
             .with_state(self.state);
 
-        axum::serve(self.tcp, app.into_make_service()).await?;
+        axum::serve(self.tcp, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
         Ok(())
     }
 }
@@ -4,3 +4,4 @@ pub mod configure;
 pub mod error;
 pub mod http;
 pub mod response;
+pub mod tenant;
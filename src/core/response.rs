@@ -1,3 +1,4 @@
+use crate::domain::user::password_policy::PasswordViolation;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -32,11 +33,25 @@ pub enum ClientResponseError {
     EntityNotAvailable { detail: String },
     EntityAlreadyExists { detail: String },
     BadRequest { detail: String },
+    PasswordPolicyViolation { violations: Vec<PasswordViolation> },
     DatabaseError { detail: String },
     Unauthorized,
+    /// The token is valid but too old for this operation, see `/v1/me/reauthenticate`
+    ReauthenticationRequired { detail: String },
     TokenExpiredError,
     AccountBadRequest,
     PermissionDenied,
+    TooManyRequests { detail: String },
+    InvalidCredentials { detail: String },
+    AccountLocked { detail: String },
+    /// The account is deactivated or its email address is not verified yet
+    UserNotActive { detail: String },
+    /// Staff suspended the account; `detail` says until when, if the suspension ends
+    AccountSuspended { detail: String },
+    /// Staff banned the account; only they can lift it
+    AccountBanned { detail: String },
+    /// The account is closed for good
+    AccountClosed { detail: String },
     InternalServerError,
     UnprocessableEntity { detail: String },
 }
@@ -0,0 +1,61 @@
+//! The tenant a request acts for. The tenant middleware resolves it once per request and
+//! runs the handler inside [`scope`], so repositories and the Redis pool read it from here
+//! instead of every call site passing it along.
+
+use crate::core::error::{AppError, AppResult};
+use std::future::Future;
+
+/// Tenant of requests that name none, and of everything created before tenants existed
+pub const DEFAULT_TENANT: &str = "default";
+
+tokio::task_local! {
+    static CURRENT_TENANT: String;
+}
+
+/// Tenant of the running request, [`DEFAULT_TENANT`] outside of one (startup, CLI tasks).
+pub fn current_tenant() -> String {
+    CURRENT_TENANT
+        .try_with(|tenant| tenant.clone())
+        .unwrap_or_else(|_| DEFAULT_TENANT.to_string())
+}
+
+/// Runs `future` on behalf of `tenant`. Tasks spawned from a request lose the tenant
+/// unless they are wrapped in this too.
+pub async fn scope<F: Future>(tenant: String, future: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant, future).await
+}
+
+/// Tenant ids end up in Redis keys and subdomains: 1 to 63 lowercase letters, digits or `-`.
+pub fn validate_tenant_id(tenant: &str) -> AppResult<()> {
+    let valid = (1..=63).contains(&tenant.len())
+        && tenant.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !tenant.starts_with('-')
+        && !tenant.ends_with('-');
+    if !valid {
+        return Err(AppError::BadRequestError(format!("Invalid tenant id {tenant}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_current_tenant_follows_scope() {
+        assert_eq!(current_tenant(), DEFAULT_TENANT);
+        let inside = scope("acme".to_string(), async { current_tenant() }).await;
+        assert_eq!(inside, "acme");
+        assert_eq!(current_tenant(), DEFAULT_TENANT);
+    }
+
+    #[test]
+    fn test_tenant_id_validation() {
+        assert!(validate_tenant_id("acme-2").is_ok());
+        assert!(validate_tenant_id("").is_err());
+        assert!(validate_tenant_id("Acme").is_err());
+        assert!(validate_tenant_id("acme:other").is_err());
+        assert!(validate_tenant_id("-acme").is_err());
+        assert!(validate_tenant_id(&"a".repeat(64)).is_err());
+    }
+}
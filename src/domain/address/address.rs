@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use crate::core::tenant;
 use crate::domain;
 use crate::presentation::address::address::{CreateAddressRequest, UpdateAddressRequest};
 
@@ -11,6 +12,8 @@ use crate::presentation::address::address::{CreateAddressRequest, UpdateAddressR
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
+    /// Always the tenant of the owning user
+    pub tenant_id: String,
     pub user_id: i64,
     #[sea_orm(belongs_to, from = "user_id", to = "id")]
     pub user: HasOne<super::super::user::user::Entity>,
@@ -45,6 +48,7 @@ impl ActiveModelBehavior for ActiveModel {}
 impl ModelEx {
     /// Business Rule: Create a new address model with validation
     pub fn create_new_address(
+        user_id: i64,
         request: &CreateAddressRequest,
     ) -> crate::core::error::AppResult<Self> {
         use crate::core::error::AppError;
@@ -67,7 +71,8 @@ impl ModelEx {
 
         Ok(Self {
             id: 0, // Will be set by the database
-            user_id: request.user_id,
+            tenant_id: tenant::current_tenant(),
+            user_id,
             user: Default::default(),
             title: request.title.clone(),
             address_line_1: request.address_line_1.clone(),
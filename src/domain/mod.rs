@@ -1,2 +1,3 @@
 pub mod user;
 pub mod address;
+pub mod oauth_client;
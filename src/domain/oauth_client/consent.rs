@@ -0,0 +1,40 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use crate::core::tenant;
+
+/// Scopes a user agreed to share with a client. Asked again only for new scopes.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_consents")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Tenant of both the user and the client
+    pub tenant_id: String,
+    pub user_id: i64,
+    pub client_id: String,
+    /// Space-separated granted scopes
+    pub scopes: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Record the first consent of a user for a client
+    pub fn new_consent(user_id: i64, client_id: String, scopes: String) -> Self {
+        let now = Utc::now().naive_utc();
+        Self {
+            tenant_id: Set(tenant::current_tenant()),
+            user_id: Set(user_id),
+            client_id: Set(client_id),
+            scopes: Set(scopes),
+            created_at: Set(Some(now)),
+            updated_at: Set(Some(now)),
+            ..Default::default()
+        }
+    }
+}
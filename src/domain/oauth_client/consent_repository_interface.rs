@@ -0,0 +1,14 @@
+use super::consent;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait ConsentRepositoryInterface: Send + Sync {
+    async fn find_consent(conn: &DatabaseTransaction, user_id: i64, client_id: &str) -> AppResult<Option<consent::Model>>;
+    /// Stores `scopes` as the granted set, creating the record on first consent.
+    async fn save_consent(conn: &DatabaseTransaction, user_id: i64, client_id: &str, scopes: String) -> AppResult<()>;
+    async fn list_consents(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<consent::Model>>;
+    /// Returns `false` when the user had not consented to the client.
+    async fn delete_consent(conn: &DatabaseTransaction, user_id: i64, client_id: &str) -> AppResult<bool>;
+}
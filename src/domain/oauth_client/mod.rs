@@ -0,0 +1,5 @@
+pub mod consent;
+pub mod consent_repository_interface;
+pub mod oauth_client;
+pub mod oauth_client_repository_interface;
+pub mod scope;
@@ -0,0 +1,100 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
+use crate::domain::oauth_client::scope;
+
+/// A registered client: a service calling the token endpoints with its credentials,
+/// or an application users sign in to through the OIDC authorization code flow.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_clients")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Client ids are unique within a tenant
+    pub tenant_id: String,
+    pub client_id: String,
+    /// Argon2 hash, the plain secret is only shown when the client is created.
+    /// Empty for public clients, which cannot keep a secret.
+    #[serde(skip_serializing)]
+    pub client_secret_hash: String,
+    pub name: String,
+    pub is_active: bool,
+    pub created_at: Option<NaiveDateTime>,
+    /// Space-separated, compared exactly against the `redirect_uri` of each request
+    pub redirect_uris: String,
+    /// Space-separated scopes the client may ask users for
+    pub allowed_scopes: String,
+    /// SPA and native apps: no secret, PKCE is what binds the code to the client
+    pub is_public: bool,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ModelEx {
+    /// Business Rule: Create a new client from an already hashed secret
+    pub fn create_new_client(
+        name: &str,
+        client_id: String,
+        client_secret_hash: String,
+        redirect_uris: &[String],
+        allowed_scopes: &[String],
+        is_public: bool,
+    ) -> AppResult<Self> {
+        if name.trim().is_empty() {
+            return Err(AppError::BadRequestError("Client name cannot be empty".to_string()));
+        }
+        if let Some(uri) = redirect_uris.iter().find(|uri| !is_valid_redirect_uri(uri)) {
+            return Err(AppError::BadRequestError(format!(
+                "Redirect URI {uri} must be an absolute https URL (http only for localhost) without fragment"
+            )));
+        }
+        if is_public && redirect_uris.is_empty() {
+            return Err(AppError::BadRequestError(
+                "A public client needs at least one redirect URI".to_string(),
+            ));
+        }
+        let allowed_scopes: BTreeSet<String> = allowed_scopes.iter().cloned().collect();
+        if let Some(unknown) = allowed_scopes.iter().find(|s| !scope::SUPPORTED_SCOPES.contains(&s.as_str())) {
+            return Err(AppError::BadRequestError(format!("Unsupported scope {unknown}")));
+        }
+
+        Ok(Self {
+            id: 0, // Will be set by the database
+            tenant_id: tenant::current_tenant(),
+            client_id,
+            client_secret_hash,
+            name: name.trim().to_string(),
+            is_active: true,
+            created_at: Some(Utc::now().naive_utc()),
+            redirect_uris: redirect_uris.join(" "),
+            allowed_scopes: scope::join(&allowed_scopes),
+            is_public,
+        })
+    }
+
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.split_whitespace().any(|uri| uri == redirect_uri)
+    }
+
+    /// Business Rule: A client only gets scopes it was registered for
+    pub fn allows_scopes(&self, scopes: &BTreeSet<String>) -> bool {
+        let allowed = scope::parse(&self.allowed_scopes);
+        scopes.is_subset(&allowed)
+    }
+}
+
+fn is_valid_redirect_uri(uri: &str) -> bool {
+    let is_local = ["http://localhost", "http://127.0.0.1", "http://[::1]"]
+        .iter()
+        .any(|prefix| {
+            uri.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(':') || rest.starts_with('/'))
+        });
+    (uri.starts_with("https://") || is_local)
+        && !uri.contains('#')
+        && !uri.chars().any(char::is_whitespace)
+}
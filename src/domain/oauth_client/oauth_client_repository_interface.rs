@@ -0,0 +1,10 @@
+use super::oauth_client;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait OauthClientRepositoryInterface: Send + Sync {
+    async fn create_client(conn: &DatabaseTransaction, model: oauth_client::ActiveModelEx) -> AppResult<bool>;
+    async fn find_client_by_client_id(conn: &DatabaseTransaction, client_id: &str) -> AppResult<Option<oauth_client::ModelEx>>;
+}
@@ -0,0 +1,154 @@
+use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
+use axum::http::Method;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+
+/// Every key starts with this, so the extractors can tell keys from JWTs and secret
+/// scanners can recognise a leaked one.
+pub const KEY_PREFIX: &str = "j18_";
+/// Safe methods (`GET`, `HEAD`, `OPTIONS`)
+pub const SCOPE_READ: &str = "read";
+/// Every other method
+pub const SCOPE_WRITE: &str = "write";
+pub const SUPPORTED_SCOPES: [&str; 2] = [SCOPE_READ, SCOPE_WRITE];
+
+/// Long-lived credential for scripts and other services, owned by a user or a service
+/// account. Only the SHA-256 digest of the key is stored; `prefix` identifies it in listings.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Always the tenant of the owning user
+    pub tenant_id: String,
+    pub user_id: i64,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    /// Space separated, out of `read` and `write`
+    pub scopes: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Issue a key to its owner
+    pub fn new_api_key(
+        user_id: i64,
+        name: String,
+        prefix: String,
+        key_hash: String,
+        scopes: String,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Self {
+        Self {
+            tenant_id: Set(tenant::current_tenant()),
+            user_id: Set(user_id),
+            name: Set(name),
+            prefix: Set(prefix),
+            key_hash: Set(key_hash),
+            scopes: Set(scopes),
+            expires_at: Set(expires_at),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+}
+
+impl Model {
+    pub fn is_usable(&self) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| expires_at > Utc::now().naive_utc())
+    }
+
+    /// Business Rule: `read` covers safe methods, `write` everything else
+    pub fn allows_method(&self, method: &Method) -> bool {
+        let needed = if method.is_safe() { SCOPE_READ } else { SCOPE_WRITE };
+        self.scopes.split_whitespace().any(|scope| scope == needed)
+    }
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(KEY_PREFIX)
+}
+
+/// Business Rule: Validate requested scopes, returning them deduplicated and space separated
+pub fn normalize_scopes(scopes: &[String]) -> AppResult<String> {
+    if scopes.is_empty() {
+        return Err(AppError::BadRequestError("At least one scope is required".to_string()));
+    }
+    if let Some(unknown) = scopes.iter().find(|scope| !SUPPORTED_SCOPES.contains(&scope.as_str())) {
+        return Err(AppError::BadRequestError(format!("Unsupported scope {unknown}")));
+    }
+    Ok(SUPPORTED_SCOPES
+        .into_iter()
+        .filter(|supported| scopes.iter().any(|scope| scope == supported))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn key_with(scopes: &str) -> Model {
+        Model {
+            id: 1,
+            tenant_id: tenant::DEFAULT_TENANT.to_string(),
+            user_id: 1,
+            name: "ci".to_string(),
+            prefix: format!("{KEY_PREFIX}abcd"),
+            key_hash: String::new(),
+            scopes: scopes.to_string(),
+            expires_at: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_scopes_are_validated_and_deduplicated() {
+        let scopes = |scopes: &[&str]| scopes.iter().map(|scope| String::from(*scope)).collect::<Vec<_>>();
+        assert_eq!(normalize_scopes(&scopes(&["write", "read", "write"])).unwrap(), "read write");
+        assert!(normalize_scopes(&scopes(&[])).is_err());
+        assert!(normalize_scopes(&scopes(&["read", "admin"])).is_err());
+    }
+
+    #[test]
+    fn test_read_scope_covers_only_safe_methods() {
+        let read_only = key_with("read");
+        assert!(read_only.allows_method(&Method::GET));
+        assert!(read_only.allows_method(&Method::HEAD));
+        assert!(!read_only.allows_method(&Method::POST));
+        assert!(!read_only.allows_method(&Method::DELETE));
+        assert!(key_with("write").allows_method(&Method::PATCH));
+        assert!(!key_with("write").allows_method(&Method::GET));
+    }
+
+    #[test]
+    fn test_revoked_or_expired_keys_are_not_usable() {
+        let now = Utc::now().naive_utc();
+        assert!(key_with("read").is_usable());
+        assert!(Model { expires_at: Some(now + Duration::hours(1)), ..key_with("read") }.is_usable());
+        assert!(!Model { expires_at: Some(now - Duration::hours(1)), ..key_with("read") }.is_usable());
+        assert!(!Model { revoked_at: Some(now), ..key_with("read") }.is_usable());
+    }
+
+    #[test]
+    fn test_keys_are_told_from_jwts_by_prefix() {
+        assert!(is_api_key(&format!("{KEY_PREFIX}0123456789")));
+        assert!(!is_api_key("eyJhbGciOiJSUzI1NiJ9.e30.sig"));
+    }
+}
@@ -0,0 +1,17 @@
+use super::api_key;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait ApiKeyRepositoryInterface: Send + Sync {
+    async fn create_api_key(conn: &DatabaseTransaction, model: api_key::ActiveModel) -> AppResult<i64>;
+    async fn find_api_key_by_id(conn: &DatabaseTransaction, id: i64) -> AppResult<Option<api_key::Model>>;
+    async fn find_api_key_by_hash(conn: &DatabaseTransaction, key_hash: &str) -> AppResult<Option<api_key::Model>>;
+    /// Newest first, revoked keys included.
+    async fn list_api_keys(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<api_key::Model>>;
+    /// Records the use at most once a minute, so busy keys do not write on every request.
+    async fn touch_api_key(conn: &DatabaseTransaction, id: i64) -> AppResult<()>;
+    /// Returns `false` when the key does not belong to the user or is already revoked.
+    async fn revoke_api_key(conn: &DatabaseTransaction, user_id: i64, id: i64) -> AppResult<bool>;
+}
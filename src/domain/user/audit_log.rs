@@ -0,0 +1,81 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{EnumIter, Set};
+use serde::{Deserialize, Serialize};
+
+/// What an admin or support agent did to or as a user. Unlike security events these
+/// are never mailed, they are for the operators reviewing staff access.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_logs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Who acted
+    pub actor_id: i64,
+    /// Whose account it was about
+    pub user_id: i64,
+    pub action: AuditAction,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(EnumIter, DeriveActiveEnum, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, utoipa::ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(40))")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "impersonation_started")]
+    ImpersonationStarted,
+    #[sea_orm(string_value = "impersonation_stopped")]
+    ImpersonationStopped,
+    #[sea_orm(string_value = "impersonated_request")]
+    ImpersonatedRequest,
+    #[sea_orm(string_value = "user_created")]
+    UserCreated,
+    #[sea_orm(string_value = "password_reset_forced")]
+    PasswordResetForced,
+    #[sea_orm(string_value = "user_suspended")]
+    UserSuspended,
+    #[sea_orm(string_value = "user_reactivated")]
+    UserReactivated,
+    #[sea_orm(string_value = "user_banned")]
+    UserBanned,
+    #[sea_orm(string_value = "user_closed")]
+    UserClosed,
+    #[sea_orm(string_value = "user_restored")]
+    UserRestored,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Record an action taken by `actor_id` on the user's account
+    pub fn new_audit_log(
+        actor_id: i64,
+        user_id: i64,
+        action: AuditAction,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        detail: Option<String>,
+    ) -> Self {
+        Self {
+            actor_id: Set(actor_id),
+            user_id: Set(user_id),
+            action: Set(action),
+            ip_address: Set(ip_address),
+            user_agent: Set(user_agent),
+            detail: Set(detail),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+
+    /// The request an actor sent, for actions taken per request
+    pub fn with_request(self, method: &str, path: &str) -> Self {
+        Self { method: Set(Some(method.to_string())), path: Set(Some(path.to_string())), ..self }
+    }
+}
@@ -0,0 +1,9 @@
+use super::audit_log;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait AuditLogRepositoryInterface: Send + Sync {
+    async fn create_audit_log(conn: &DatabaseTransaction, model: audit_log::ActiveModel) -> AppResult<()>;
+}
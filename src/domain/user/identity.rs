@@ -0,0 +1,56 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use crate::core::tenant;
+
+/// An account at an external OpenID provider the user signs in with.
+/// `subject` is the provider's stable `sub`, never the email address.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_identities")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// The same provider account can be linked once in each tenant
+    pub tenant_id: String,
+    pub user_id: i64,
+    pub provider: String,
+    pub subject: String,
+    /// Email the provider reported when the identity was linked, for display only
+    pub email: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Link a provider account to the user
+    pub fn new_identity(user_id: i64, provider: String, subject: String, email: Option<String>) -> Self {
+        Self {
+            tenant_id: Set(tenant::current_tenant()),
+            user_id: Set(user_id),
+            provider: Set(provider),
+            subject: Set(subject),
+            email: Set(email),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            last_login_at: Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_new_identity_is_linked_in_the_current_tenant() {
+        let identity = tenant::scope("acme".to_string(), async {
+            ActiveModel::new_identity(1, "google".to_string(), "sub-1".to_string(), None)
+        })
+        .await;
+        assert_eq!(identity.tenant_id, Set("acme".to_string()));
+    }
+}
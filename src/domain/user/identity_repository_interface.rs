@@ -0,0 +1,14 @@
+use super::identity;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait IdentityRepositoryInterface: Send + Sync {
+    async fn create_identity(conn: &DatabaseTransaction, model: identity::ActiveModel) -> AppResult<()>;
+    async fn find_identity(conn: &DatabaseTransaction, provider: &str, subject: &str) -> AppResult<Option<identity::Model>>;
+    async fn list_identities(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<identity::Model>>;
+    async fn touch_identity_login(conn: &DatabaseTransaction, id: i64) -> AppResult<()>;
+    /// Returns `false` when the identity does not belong to the user.
+    async fn delete_identity(conn: &DatabaseTransaction, user_id: i64, id: i64) -> AppResult<bool>;
+}
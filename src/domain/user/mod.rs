@@ -1,4 +1,24 @@
+pub mod api_key;
+pub mod api_key_repository_interface;
+pub mod audit_log;
+pub mod audit_log_repository_interface;
 pub mod events;
+pub mod identity;
+pub mod identity_repository_interface;
+pub mod password_history;
+pub mod password_history_repository_interface;
+pub mod password_policy;
+pub mod permission;
+pub mod recovery_code;
+pub mod recovery_code_repository_interface;
+pub mod role;
+pub mod role_permission;
+pub mod role_repository_interface;
 pub mod rules;
+pub mod security_event;
+pub mod security_event_repository_interface;
+pub mod status_change;
+pub mod status_change_repository_interface;
 pub mod user;
 pub mod user_repository_interface;
+pub mod user_role;
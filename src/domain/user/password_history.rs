@@ -0,0 +1,30 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+
+/// Hash of a password the user had, kept to refuse reusing it.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_password_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub password_hash: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Remember a password the user just set
+    pub fn new_password_history(user_id: i64, password_hash: String) -> Self {
+        Self {
+            user_id: Set(user_id),
+            password_hash: Set(password_hash),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use super::password_history;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait PasswordHistoryRepositoryInterface: Send + Sync {
+    async fn create_password_history(conn: &DatabaseTransaction, model: password_history::ActiveModel) -> AppResult<()>;
+    /// The `limit` most recent hashes, newest first.
+    async fn list_recent_password_hashes(conn: &DatabaseTransaction, user_id: i64, limit: u64) -> AppResult<Vec<String>>;
+    /// Drops everything but the `keep` most recent entries.
+    async fn prune_password_history(conn: &DatabaseTransaction, user_id: i64, keep: u64) -> AppResult<()>;
+}
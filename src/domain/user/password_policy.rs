@@ -0,0 +1,123 @@
+use crate::core::configure::auth::PasswordPolicyConfig;
+use crate::util::breached_password;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One unmet rule of the password policy. Clients map `rule` to their own message.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PasswordViolation {
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol,
+    ContainsUsername,
+    ContainsEmail,
+    Breached,
+    /// Matches one of the last `history_size` passwords of the user
+    RecentlyUsed { history_size: u64 },
+}
+
+/// Business Rule: Check a new password against the configured policy. Every unmet
+/// rule is reported, so the user can fix them all at once. Reuse is checked separately
+/// because it needs the stored hashes.
+pub fn check(
+    policy: &PasswordPolicyConfig,
+    password: &str,
+    username: &str,
+    email: &str,
+) -> Vec<PasswordViolation> {
+    let mut violations = Vec::new();
+    let length = password.chars().count();
+    if length < policy.min_length {
+        violations.push(PasswordViolation::TooShort { min_length: policy.min_length });
+    }
+    if length > policy.max_length {
+        violations.push(PasswordViolation::TooLong { max_length: policy.max_length });
+    }
+    if policy.require_uppercase && !password.chars().any(char::is_uppercase) {
+        violations.push(PasswordViolation::MissingUppercase);
+    }
+    if policy.require_lowercase && !password.chars().any(char::is_lowercase) {
+        violations.push(PasswordViolation::MissingLowercase);
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push(PasswordViolation::MissingDigit);
+    }
+    if policy.require_symbol && password.chars().all(char::is_alphanumeric) {
+        violations.push(PasswordViolation::MissingSymbol);
+    }
+
+    if policy.reject_personal_info {
+        let lowered = password.to_lowercase();
+        // Very short names would match half the dictionary
+        let contains = |part: &str| part.chars().count() >= 3 && lowered.contains(&part.to_lowercase());
+        if contains(username.trim()) {
+            violations.push(PasswordViolation::ContainsUsername);
+        }
+        if contains(email.split('@').next().unwrap_or_default().trim()) {
+            violations.push(PasswordViolation::ContainsEmail);
+        }
+    }
+    if policy.reject_breached && breached_password::is_breached(password) {
+        violations.push(PasswordViolation::Breached);
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> PasswordPolicyConfig {
+        PasswordPolicyConfig {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_strong_password_passes() {
+        assert!(check(&strict(), "Tr0ub4dor&3-horse", "jane", "jane@example.com").is_empty());
+    }
+
+    #[test]
+    fn test_every_unmet_rule_is_reported() {
+        assert_eq!(
+            check(&strict(), "abc", "jane", "jane@example.com"),
+            vec![
+                PasswordViolation::TooShort { min_length: 12 },
+                PasswordViolation::MissingUppercase,
+                PasswordViolation::MissingDigit,
+                PasswordViolation::MissingSymbol,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_personal_info_is_rejected_case_insensitively() {
+        let violations = check(&strict(), "Xx-JaneDoe-2024!", "janedoe", "jane.doe@example.com");
+        assert!(violations.contains(&PasswordViolation::ContainsUsername));
+
+        let violations = check(&strict(), "Xx-Jane.Doe-2024!", "jd", "jane.doe@example.com");
+        assert_eq!(violations, vec![PasswordViolation::ContainsEmail]);
+    }
+
+    #[test]
+    fn test_breached_password_is_rejected() {
+        let policy = PasswordPolicyConfig { min_length: 1, ..Default::default() };
+        assert_eq!(check(&policy, "letmein", "jane", "jane@example.com"), vec![PasswordViolation::Breached]);
+    }
+
+    #[test]
+    fn test_violations_serialize_with_rule_tag() {
+        let json = serde_json::to_value(PasswordViolation::TooShort { min_length: 12 }).unwrap();
+        assert_eq!(json, serde_json::json!({ "rule": "too_short", "min_length": 12 }));
+    }
+}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Permissions seeded by the migration. Routes declare the one they require.
+pub const USERS_READ: &str = "users:read";
+pub const USERS_WRITE: &str = "users:write";
+pub const USERS_DELETE: &str = "users:delete";
+pub const USERS_SECURITY: &str = "users:security";
+pub const USERS_IMPERSONATE: &str = "users:impersonate";
+pub const OAUTH_CLIENTS_WRITE: &str = "oauth_clients:write";
+pub const SERVICE_ACCOUNTS_WRITE: &str = "service_accounts:write";
+
+/// Something a role allows, named `<resource>:<action>`.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "permissions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
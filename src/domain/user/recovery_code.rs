@@ -0,0 +1,33 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+
+/// One-time code that stands in for a TOTP code when the authenticator is lost.
+/// Only the SHA-256 digest of the code is stored.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_recovery_codes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub code_hash: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Create an unused recovery code for the user
+    pub fn new_recovery_code(user_id: i64, code_hash: String) -> Self {
+        Self {
+            user_id: Set(user_id),
+            code_hash: Set(code_hash),
+            used_at: Set(None),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+}
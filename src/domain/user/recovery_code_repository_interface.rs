@@ -0,0 +1,13 @@
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait RecoveryCodeRepositoryInterface: Send + Sync {
+    /// Drops every existing code of the user and stores the new digests.
+    async fn replace_recovery_codes(conn: &DatabaseTransaction, user_id: i64, code_hashes: Vec<String>) -> AppResult<()>;
+    /// Marks the matching unused code as used. Returns `false` when no such code exists.
+    async fn consume_recovery_code(conn: &DatabaseTransaction, user_id: i64, code_hash: &str) -> AppResult<bool>;
+    async fn delete_recovery_codes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<()>;
+    async fn count_unused_recovery_codes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<u64>;
+}
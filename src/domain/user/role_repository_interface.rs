@@ -0,0 +1,14 @@
+use super::{role, user_role};
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait RoleRepositoryInterface: Send + Sync {
+    async fn find_role_by_name(conn: &DatabaseTransaction, name: &str) -> AppResult<Option<role::Model>>;
+    async fn find_roles_of_user(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<role::Model>>;
+    /// Distinct permission names granted by the roles, sorted.
+    async fn find_permission_names(conn: &DatabaseTransaction, role_ids: &[i64]) -> AppResult<Vec<String>>;
+    /// Granting a role the user already has is a no-op.
+    async fn assign_role(conn: &DatabaseTransaction, model: user_role::ActiveModel) -> AppResult<()>;
+}
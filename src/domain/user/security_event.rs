@@ -0,0 +1,60 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{EnumIter, Set};
+use serde::{Deserialize, Serialize};
+
+/// Something that happened to the account the user should know about, kept as an
+/// audit trail and mailed to the user when it is recorded.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_security_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: SecurityEventKind,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(EnumIter, DeriveActiveEnum, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, utoipa::ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(40))")]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityEventKind {
+    #[sea_orm(string_value = "password_changed")]
+    PasswordChanged,
+}
+
+impl SecurityEventKind {
+    /// Subject line of the notification mail
+    pub fn title(&self) -> &'static str {
+        match self {
+            SecurityEventKind::PasswordChanged => "Your password was changed",
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Record a security event on the user's account
+    pub fn new_security_event(
+        user_id: i64,
+        kind: SecurityEventKind,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        detail: Option<String>,
+    ) -> Self {
+        Self {
+            user_id: Set(user_id),
+            kind: Set(kind),
+            ip_address: Set(ip_address),
+            user_agent: Set(user_agent),
+            detail: Set(detail),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use super::security_event;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait SecurityEventRepositoryInterface: Send + Sync {
+    async fn create_security_event(conn: &DatabaseTransaction, model: security_event::ActiveModel) -> AppResult<()>;
+    /// Newest first.
+    async fn list_security_events(conn: &DatabaseTransaction, user_id: i64, limit: u64) -> AppResult<Vec<security_event::Model>>;
+}
@@ -0,0 +1,47 @@
+use super::user::Status;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+
+/// One step of an account through the status machine, with who took it and why.
+#[sea_orm::model]
+#[derive(Clone, Debug, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_status_changes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub from_status: Status,
+    pub to_status: Status,
+    /// Who changed it; `None` when the system did, e.g. at the end of a suspension
+    pub actor_id: Option<i64>,
+    pub reason: Option<String>,
+    pub suspended_until: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Business Rule: Record the account moving from `from_status` to `to_status`
+    pub fn new_status_change(
+        user_id: i64,
+        from_status: Status,
+        to_status: Status,
+        actor_id: Option<i64>,
+        reason: Option<String>,
+        suspended_until: Option<NaiveDateTime>,
+    ) -> Self {
+        Self {
+            user_id: Set(user_id),
+            from_status: Set(from_status),
+            to_status: Set(to_status),
+            actor_id: Set(actor_id),
+            reason: Set(reason),
+            suspended_until: Set(suspended_until),
+            created_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        }
+    }
+}
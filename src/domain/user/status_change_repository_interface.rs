@@ -0,0 +1,11 @@
+use super::status_change;
+use crate::core::error::AppResult;
+use async_trait::async_trait;
+use sea_orm::DatabaseTransaction;
+
+#[async_trait]
+pub trait StatusChangeRepositoryInterface: Send + Sync {
+    async fn create_status_change(conn: &DatabaseTransaction, model: status_change::ActiveModel) -> AppResult<()>;
+    /// Newest first.
+    async fn list_status_changes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<status_change::Model>>;
+}
@@ -3,6 +3,9 @@ use sea_orm::entity::prelude::*;
 use sea_orm::{ActiveModelBehavior, ActiveModelTrait, EnumIter};
 use serde::{Deserialize, Serialize};
 use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
+use crate::util::string::normalize_email;
+use crate::application::user::user_command::AdminCreateUserCommand;
 use crate::presentation::user::user::{CreateUserRequest, UpdateUserRequest};
 
 #[sea_orm::model]
@@ -11,6 +14,8 @@ use crate::presentation::user::user::{CreateUserRequest, UpdateUserRequest};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
+    /// Tenant the account belongs to; usernames and emails are unique within it
+    pub tenant_id: String,
     pub avatar: Option<String>,
     pub first_name: String,
     pub last_name: String,
@@ -21,7 +26,18 @@ pub struct Model {
     #[sea_orm(has_many)]
     pub address: HasMany<super::super::address::address::Entity>,
     pub phone_number: Option<String>,
+    pub gender: Option<String>,
+    /// Preferred language code, e.g. `vi` or `en`
+    pub language: Option<String>,
     pub status: Status,
+    /// End of a temporary suspension; the account is reactivated on its next sign-in after it
+    pub suspended_until: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    /// Base32 TOTP secret, only set once enrollment was confirmed
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    /// Non-human account for scripts and other services; it only authenticates with API keys
+    pub is_service_account: bool,
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub deleted_at: Option<NaiveDateTime>,
@@ -35,6 +51,54 @@ pub enum Status {
     ACTIVE,
     #[sea_orm(string_value = "inactive")]
     INACTIVE,
+    /// Registered but the email address is not confirmed yet
+    #[sea_orm(string_value = "pending")]
+    PENDING,
+    /// Locked out by staff, possibly until `suspended_until`
+    #[sea_orm(string_value = "suspended")]
+    SUSPENDED,
+    /// Barred by staff for abuse. Only staff can lift it, sign-ins never do.
+    #[sea_orm(string_value = "banned")]
+    BANNED,
+    /// Closed for good, nothing brings the account back
+    #[sea_orm(string_value = "closed")]
+    CLOSED,
+}
+
+impl Status {
+    /// Pending accounts may still sign in to confirm their email, deactivated, suspended,
+    /// banned and closed ones may not.
+    pub fn allows_sign_in(&self) -> bool {
+        matches!(self, Status::ACTIVE | Status::PENDING)
+    }
+
+    /// Business Rule: The status changes an account may go through. `CLOSED` is terminal.
+    /// Bans are set and lifted through the staff-only status changes, never by the system.
+    pub fn can_transition_to(&self, next: &Status) -> bool {
+        use Status::*;
+        matches!(
+            (self, next),
+            (PENDING, ACTIVE | INACTIVE | SUSPENDED | BANNED | CLOSED)
+                | (ACTIVE, INACTIVE | SUSPENDED | BANNED | CLOSED)
+                | (INACTIVE, ACTIVE | PENDING | SUSPENDED | BANNED | CLOSED)
+                | (SUSPENDED, ACTIVE | PENDING | INACTIVE | BANNED | CLOSED)
+                | (BANNED, ACTIVE | PENDING | CLOSED)
+        )
+    }
+
+    /// The error a request of an account in this status is refused with, if any.
+    pub fn sign_in_error(&self, suspended_until: Option<NaiveDateTime>) -> Option<AppError> {
+        match self {
+            Status::SUSPENDED => Some(AppError::AccountSuspendedError(match suspended_until {
+                Some(until) => format!("Account is suspended until {} UTC", until.format("%Y-%m-%d %H:%M")),
+                None => "Account is suspended".to_string(),
+            })),
+            Status::INACTIVE => Some(AppError::UserNotActiveError("Account is deactivated".to_string())),
+            Status::BANNED => Some(AppError::AccountBannedError("Account is banned".to_string())),
+            Status::CLOSED => Some(AppError::AccountClosedError("Account is closed".to_string())),
+            Status::ACTIVE | Status::PENDING => None,
+        }
+    }
 }
 
 
@@ -62,23 +126,151 @@ impl ModelEx {
         if !request.email.contains('@') {
             return Err(AppError::BadRequestError("Email must be valid".to_string()));
         }
+        // Login tells usernames and emails apart by the '@'
+        if request.username.trim().is_empty() || request.username.contains('@') {
+            return Err(AppError::BadRequestError("Username cannot be empty or contain '@'".to_string()));
+        }
         
         // Create and return the user model
         Ok(Self {
             id: 0, // Will be set by the database
+            tenant_id: tenant::current_tenant(),
             avatar: request.avatar.clone(),
             first_name: request.first_name.clone(),
             last_name: request.last_name.clone(),
-            username: request.username.clone(),
-            email: request.email.clone(),
+            username: request.username.trim().to_string(),
+            email: normalize_email(&request.email),
             password: Some(request.password.clone()), // Password will be set after hashing
             birth_of_date: request.birth_of_date,
             address: Default::default(),
             phone_number: request.phone_number.clone(),
-            status: Status::ACTIVE,
+            gender: None,
+            language: None,
+            status: Status::PENDING,
+            suspended_until: None,
+            email_verified_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            is_service_account: false,
             is_deleted: false,
             created_at: Some(Utc::now().naive_utc()),
-            deleted_at: Some(Utc::now().naive_utc()),
+            deleted_at: None,
+        })
+    }
+
+    /// Business Rule: Create a user signed up through an external identity provider.
+    /// There is no password; the provider vouches for the email when it says it verified it.
+    pub fn create_external_user(
+        username: &str,
+        email: &str,
+        first_name: &str,
+        last_name: &str,
+        email_verified: bool,
+    ) -> AppResult<Self> {
+        if !email.contains('@') {
+            return Err(AppError::BadRequestError("Email must be valid".to_string()));
+        }
+        if username.trim().is_empty() || username.contains('@') {
+            return Err(AppError::BadRequestError("Username cannot be empty or contain '@'".to_string()));
+        }
+
+        let now = Utc::now().naive_utc();
+        Ok(Self {
+            id: 0, // Will be set by the database
+            tenant_id: tenant::current_tenant(),
+            avatar: None,
+            first_name: first_name.trim().to_string(),
+            last_name: last_name.trim().to_string(),
+            username: username.trim().to_string(),
+            email: normalize_email(email),
+            password: None,
+            birth_of_date: None,
+            address: Default::default(),
+            phone_number: None,
+            gender: None,
+            language: None,
+            status: if email_verified { Status::ACTIVE } else { Status::PENDING },
+            suspended_until: None,
+            email_verified_at: email_verified.then_some(now),
+            totp_secret: None,
+            totp_enabled_at: None,
+            is_service_account: false,
+            is_deleted: false,
+            created_at: Some(now),
+            deleted_at: None,
+        })
+    }
+
+    /// Business Rule: Create a service account. It has no password and its email is a
+    /// placeholder under the reserved `.invalid` domain, so nothing is ever mailed to it.
+    pub fn create_service_account(username: &str, display_name: &str) -> AppResult<Self> {
+        if username.trim().is_empty() || username.contains('@') {
+            return Err(AppError::BadRequestError("Username cannot be empty or contain '@'".to_string()));
+        }
+
+        let username = username.trim().to_string();
+        let now = Utc::now().naive_utc();
+        Ok(Self {
+            id: 0, // Will be set by the database
+            tenant_id: tenant::current_tenant(),
+            avatar: None,
+            first_name: display_name.trim().to_string(),
+            last_name: String::new(),
+            email: normalize_email(&format!("{username}@service-accounts.invalid")),
+            username,
+            password: None,
+            birth_of_date: None,
+            address: Default::default(),
+            phone_number: None,
+            gender: None,
+            language: None,
+            status: Status::ACTIVE,
+            suspended_until: None,
+            email_verified_at: Some(now),
+            totp_secret: None,
+            totp_enabled_at: None,
+            is_service_account: true,
+            is_deleted: false,
+            created_at: Some(now),
+            deleted_at: None,
+        })
+    }
+
+    /// Business Rule: Create a user on an admin's behalf. The admin vouches for the email,
+    /// unless the account is left pending; the password is hashed and set afterwards.
+    pub fn create_admin_user(command: &AdminCreateUserCommand) -> AppResult<Self> {
+        if command.username.trim().is_empty() || command.username.contains('@') {
+            return Err(AppError::BadRequestError("Username cannot be empty or contain '@'".to_string()));
+        }
+        if command.first_name.trim().is_empty() {
+            return Err(AppError::BadRequestError("First name cannot be empty".to_string()));
+        }
+
+        let status = command.status.clone().unwrap_or(Status::ACTIVE);
+        let now = Utc::now().naive_utc();
+        Ok(Self {
+            id: 0, // Will be set by the database
+            tenant_id: tenant::current_tenant(),
+            avatar: command.picture.clone(),
+            first_name: command.first_name.trim().to_string(),
+            last_name: command.last_name.trim().to_string(),
+            username: command.username.trim().to_string(),
+            email: normalize_email(&command.email),
+            password: None,
+            birth_of_date: command.birthday,
+            address: Default::default(),
+            phone_number: command.phone_number.clone(),
+            gender: command.gender.clone(),
+            language: command.language.clone(),
+            email_verified_at: (status != Status::PENDING).then_some(now),
+            status,
+            suspended_until: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            is_service_account: false,
+            is_deleted: false,
+            created_at: Some(now),
+            deleted_at: None,
         })
     }
 
@@ -109,7 +301,12 @@ impl ModelEx {
             if !email.contains('@') {
                 return Err(AppError::BadRequestError("Email must be valid".to_string()));
             }
-            self.email = email.clone();
+            let email = normalize_email(email);
+            if email != self.email {
+                // The new address is not confirmed until its own code comes back
+                self.email = email;
+                self.email_verified_at = None;
+            }
         }
 
         if let Some(ref avatar) = request.avatar {
@@ -123,9 +320,254 @@ impl ModelEx {
             self.phone_number = Some(phone_number.clone());
         }
         if let Some(ref status) = request.status {
-            self.status = status.clone();
+            if *status != self.status {
+                self = self.change_status(status.clone(), None)?;
+            }
         }
 
         Ok(self)
     }
-}
\ No newline at end of file
+
+    /// Business Rule: Replace the password with an already hashed one
+    pub fn change_password(mut self, hashed_password: String) -> Self {
+        self.password = Some(hashed_password);
+        self
+    }
+
+    /// Business Rule: Drop the password so only a reset can set a new one
+    pub fn clear_password(mut self) -> Self {
+        self.password = None;
+        self
+    }
+
+    /// Business Rule: Move the account to `next` if the status machine allows it. Only a
+    /// suspension may have an end, and it must lie in the future.
+    pub fn change_status(mut self, next: Status, suspended_until: Option<NaiveDateTime>) -> AppResult<Self> {
+        if self.status == Status::CLOSED {
+            return Err(AppError::BadRequestError("A closed account cannot change status".to_string()));
+        }
+        if !self.status.can_transition_to(&next) {
+            return Err(AppError::BadRequestError(format!(
+                "User status cannot change from {:?} to {:?}",
+                self.status, next
+            )));
+        }
+        if let Some(until) = suspended_until {
+            if next != Status::SUSPENDED {
+                return Err(AppError::BadRequestError("Only a suspension can have an end".to_string()));
+            }
+            if until <= Utc::now().naive_utc() {
+                return Err(AppError::BadRequestError("The suspension must end in the future".to_string()));
+            }
+        }
+        self.status = next;
+        self.suspended_until = suspended_until;
+        Ok(self)
+    }
+
+    /// Business Rule: Lock the account out, for good or until `suspended_until`
+    pub fn suspend(self, suspended_until: Option<NaiveDateTime>) -> AppResult<Self> {
+        if self.status == Status::SUSPENDED {
+            return Err(AppError::BadRequestError("User is already suspended".to_string()));
+        }
+        self.change_status(Status::SUSPENDED, suspended_until)
+    }
+
+    /// Business Rule: Bar the account for good, until staff lift the ban
+    pub fn ban(self) -> AppResult<Self> {
+        if self.status == Status::BANNED {
+            return Err(AppError::BadRequestError("User is already banned".to_string()));
+        }
+        self.change_status(Status::BANNED, None)
+    }
+
+    /// Business Rule: Close the account for good
+    pub fn close(self) -> AppResult<Self> {
+        self.change_status(Status::CLOSED, None)
+    }
+
+    /// Business Rule: Lift a suspension, deactivation or ban. Accounts that never confirmed
+    /// their email go back to pending.
+    pub fn reactivate(self) -> AppResult<Self> {
+        if self.status.allows_sign_in() {
+            return Err(AppError::BadRequestError("User is not suspended, deactivated or banned".to_string()));
+        }
+        let next = if self.email_verified_at.is_some() { Status::ACTIVE } else { Status::PENDING };
+        self.change_status(next, None)
+    }
+
+    /// A temporary suspension whose end has passed.
+    pub fn suspension_expired(&self, now: NaiveDateTime) -> bool {
+        self.status == Status::SUSPENDED && self.suspended_until.is_some_and(|until| until <= now)
+    }
+
+    /// Refuses accounts that may not sign in or use their tokens. A suspension whose end
+    /// has passed no longer counts, even before it is lifted.
+    pub fn ensure_can_sign_in(&self) -> AppResult<()> {
+        if self.suspension_expired(Utc::now().naive_utc()) {
+            return Ok(());
+        }
+        match self.status.sign_in_error(self.suspended_until) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Business Rule: Undo a soft delete
+    pub fn restore(mut self) -> AppResult<Self> {
+        if !self.is_deleted {
+            return Err(AppError::BadRequestError("User is not deleted".to_string()));
+        }
+        self.is_deleted = false;
+        self.deleted_at = None;
+        Ok(self)
+    }
+
+    /// Business Rule: Confirm the email address and activate a pending account
+    pub fn verify_email(mut self) -> AppResult<Self> {
+        if self.email_verified_at.is_some() {
+            return Err(AppError::BadRequestError("Email is already verified".to_string()));
+        }
+        self.email_verified_at = Some(Utc::now().naive_utc());
+        if self.status == Status::PENDING {
+            self.status = Status::ACTIVE;
+        }
+        Ok(self)
+    }
+
+    pub fn has_two_factor(&self) -> bool {
+        self.totp_enabled_at.is_some() && self.totp_secret.is_some()
+    }
+
+    /// Business Rule: Turn on TOTP with a secret the user has proven to own
+    pub fn enable_two_factor(mut self, secret: String) -> AppResult<Self> {
+        if self.has_two_factor() {
+            return Err(AppError::BadRequestError("Two-factor authentication is already enabled".to_string()));
+        }
+        self.totp_secret = Some(secret);
+        self.totp_enabled_at = Some(Utc::now().naive_utc());
+        Ok(self)
+    }
+
+    /// Business Rule: Turn off TOTP
+    pub fn disable_two_factor(mut self) -> AppResult<Self> {
+        if !self.has_two_factor() {
+            return Err(AppError::BadRequestError("Two-factor authentication is not enabled".to_string()));
+        }
+        self.totp_secret = None;
+        self.totp_enabled_at = None;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn active_user() -> ModelEx {
+        ModelEx::create_external_user("jane", "jane@example.com", "Jane", "Doe", true).unwrap()
+    }
+
+    #[test]
+    fn test_status_machine_allows_only_listed_transitions() {
+        assert!(Status::PENDING.can_transition_to(&Status::ACTIVE));
+        assert!(Status::ACTIVE.can_transition_to(&Status::SUSPENDED));
+        assert!(Status::SUSPENDED.can_transition_to(&Status::ACTIVE));
+        assert!(!Status::ACTIVE.can_transition_to(&Status::PENDING));
+        assert!(!Status::SUSPENDED.can_transition_to(&Status::SUSPENDED));
+    }
+
+    #[test]
+    fn test_suspended_user_cannot_sign_in_until_reactivated() {
+        let suspended = active_user().suspend(None).unwrap();
+        assert!(matches!(suspended.ensure_can_sign_in(), Err(AppError::AccountSuspendedError(_))));
+        assert!(suspended.clone().suspend(None).is_err());
+
+        let reactivated = suspended.reactivate().unwrap();
+        assert_eq!(reactivated.status, Status::ACTIVE);
+        assert!(reactivated.ensure_can_sign_in().is_ok());
+    }
+
+    #[test]
+    fn test_temporary_suspension_must_end_in_the_future_and_then_expires() {
+        let past = Utc::now().naive_utc() - Duration::minutes(1);
+        assert!(active_user().suspend(Some(past)).is_err());
+
+        let mut suspended = active_user().suspend(Some(Utc::now().naive_utc() + Duration::hours(1))).unwrap();
+        assert!(!suspended.suspension_expired(Utc::now().naive_utc()));
+        suspended.suspended_until = Some(past);
+        assert!(suspended.suspension_expired(Utc::now().naive_utc()));
+        assert!(suspended.ensure_can_sign_in().is_ok());
+    }
+
+    #[test]
+    fn test_deactivated_user_gets_user_not_active() {
+        let inactive = active_user().change_status(Status::INACTIVE, None).unwrap();
+        assert!(matches!(inactive.ensure_can_sign_in(), Err(AppError::UserNotActiveError(_))));
+        assert!(active_user().change_status(Status::INACTIVE, Some(Utc::now().naive_utc())).is_err());
+    }
+
+    #[test]
+    fn test_banned_user_gets_its_own_error_until_reactivated() {
+        let banned = active_user().ban().unwrap();
+        assert!(matches!(banned.ensure_can_sign_in(), Err(AppError::AccountBannedError(_))));
+        assert!(banned.clone().ban().is_err());
+        assert!(banned.clone().suspend(None).is_err());
+        assert_eq!(banned.reactivate().unwrap().status, Status::ACTIVE);
+    }
+
+    #[test]
+    fn test_closed_account_is_terminal() {
+        let closed = active_user().close().unwrap();
+        assert!(matches!(closed.ensure_can_sign_in(), Err(AppError::AccountClosedError(_))));
+        assert!(closed.clone().reactivate().is_err());
+        assert!(closed.clone().ban().is_err());
+        assert!(closed.clone().close().is_err());
+        assert!(closed.change_status(Status::ACTIVE, None).is_err());
+        assert!(active_user().ban().unwrap().close().is_ok());
+    }
+
+    #[test]
+    fn test_reactivate_returns_unverified_accounts_to_pending() {
+        assert!(active_user().reactivate().is_err());
+        let unverified = ModelEx::create_external_user("joe", "joe@example.com", "Joe", "Doe", false).unwrap();
+        let suspended = unverified.change_status(Status::SUSPENDED, None).unwrap();
+        assert_eq!(suspended.reactivate().unwrap().status, Status::PENDING);
+        let inactive = active_user().change_status(Status::INACTIVE, None).unwrap();
+        assert_eq!(inactive.reactivate().unwrap().status, Status::ACTIVE);
+    }
+
+    #[test]
+    fn test_only_deleted_users_can_be_restored() {
+        assert!(active_user().restore().is_err());
+
+        let mut deleted = active_user();
+        deleted.is_deleted = true;
+        deleted.deleted_at = Some(Utc::now().naive_utc());
+        let restored = deleted.restore().unwrap();
+        assert!(!restored.is_deleted);
+        assert_eq!(restored.deleted_at, None);
+        assert_eq!(restored.status, Status::ACTIVE);
+    }
+
+    #[test]
+    fn test_email_is_stored_normalized_and_usernames_cannot_look_like_emails() {
+        let user_res = ModelEx::create_external_user("bob", "  Bob@X.com ", "Bob", "Doe", true).unwrap();
+        assert_eq!(user_res.email, "bob@x.com");
+        assert!(ModelEx::create_external_user("bob@x.com", "bob@x.com", "Bob", "Doe", true).is_err());
+    }
+
+    #[test]
+    fn test_changing_the_email_needs_it_verified_again() {
+        let request = |email: &str| UpdateUserRequest { email: Some(email.to_string()), ..Default::default() };
+
+        let same = active_user().update_from(&request(" Jane@Example.com")).unwrap();
+        assert!(same.email_verified_at.is_some());
+
+        let changed = active_user().update_from(&request("jane@example.org")).unwrap();
+        assert_eq!(changed.email, "jane@example.org");
+        assert_eq!(changed.email_verified_at, None);
+        assert_eq!(changed.status, Status::ACTIVE);
+    }
+}
@@ -10,8 +10,14 @@ pub trait UserRepositoryInterface: Send + Sync {
     async fn find_user_by_id(conn: &DatabaseTransaction, id: i64) -> AppResult<Option<user::ModelEx>>;
     async fn find_user_by_username(conn: &DatabaseTransaction, username: &str) -> AppResult<Option<user::ModelEx>>;
     async fn find_user_by_email(conn: &DatabaseTransaction, email: &str) -> AppResult<Option<user::ModelEx>>;
+    /// Resolves an email when the identifier contains `@`, a username otherwise.
+    async fn find_user_by_identifier(conn: &DatabaseTransaction, identifier: &str) -> AppResult<Option<user::ModelEx>>;
+    /// Phone numbers are not unique, so only a number held by exactly one live account resolves.
+    async fn find_user_by_phone_number(conn: &DatabaseTransaction, phone_number: &str) -> AppResult<Option<user::ModelEx>>;
     async fn delete_user(conn: &DatabaseTransaction, id: i64) -> AppResult<()>;
     async fn username_exists(conn: &DatabaseTransaction, username: &str) -> AppResult<bool>;
     async fn email_exists(conn: &DatabaseTransaction, email: &str) -> AppResult<bool>;
     async fn list_users(conn: &DatabaseTransaction, page: u64, page_size: u64) -> AppResult<Vec<user::Model>>;
+    /// Like `list_users`, soft-deleted accounts included.
+    async fn list_all_users(conn: &DatabaseTransaction, page: u64, page_size: u64) -> AppResult<Vec<user::Model>>;
 }
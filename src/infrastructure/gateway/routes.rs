@@ -1,11 +1,19 @@
 use crate::core::app_state::AppState;
 use crate::core::error::{AppError, AppResult};
 use crate::core::response::EntityResponse;
+use crate::domain::user::api_key;
 use crate::infrastructure::gateway::proxy::{check_service_health, ProxyClient};
 use crate::infrastructure::gateway::service_registry::ServiceConfig;
+use crate::infrastructure::middleware::authenticate::{
+    audit_impersonated_request, authenticate_api_key, verify_access_token,
+};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::cookie_session;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::util::claim::UserClaims;
 use axum::body::Body;
 use axum::extract::{Request, State};
+use axum::http::request::Parts;
 use axum::http::Response;
 use axum::response::IntoResponse;
 use axum::Json;
@@ -146,21 +154,65 @@ async fn proxy_to_service(
         .await
 }
 
-// Helper function to extract user claims from request
-fn extract_claims_from_request(request: &Request) -> Option<UserClaims> {
-    // Try to extract Authorization header
-    request
-        .headers()
+// Helper function to extract user claims from request. Tokens the API itself would
+// refuse, tokens delegated to a third-party client and unusable API keys count as anonymous.
+// Takes the request head only: the body is not `Sync`, so it cannot be held across awaits.
+async fn extract_claims_from_request(state: &AppState, request: &Parts) -> Option<UserClaims> {
+    // Try to extract Authorization header, then the session cookie of browser clients
+    let bearer = request
+        .headers
         .get("authorization")
         .and_then(|h| h.to_str().ok())
-        .and_then(|auth_str| {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                use crate::util::constant::ACCESS_TOKEN_DECODE_KEY;
-                UserClaims::decode(token, &ACCESS_TOKEN_DECODE_KEY).ok().map(|td| td.claims)
-            } else {
+        .and_then(|auth_str| auth_str.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let token = match bearer {
+        Some(token) => token,
+        None => cookie_session::access_token_cookie(
+            &state.config.auth.cookie_session,
+            &request.headers,
+            &request.method,
+        )
+        .unwrap_or_else(|err| {
+            error!("Rejected session cookie at the gateway: {err:?}");
+            None
+        })?,
+    };
+    let token = token.as_str();
+
+    if api_key::is_api_key(token) {
+        return match authenticate_api_key(state, token, &request.method).await {
+            Ok(claims) => Some(claims),
+            Err(err) => {
+                error!("Rejected API key at the gateway: {err:?}");
                 None
-            }
-        })
+            },
+        };
+    }
+
+    let claims = verify_gateway_token(&state.redis, token).await?;
+
+    let trusted_proxies = &state.config.server.trusted_proxies;
+    let client = ClientInfo::from_http(&request.headers, &request.extensions, trusted_proxies);
+    let path = request.uri.path();
+    match audit_impersonated_request(state, &claims, &request.method, path, &client).await {
+        Ok(()) => Some(claims),
+        Err(err) => {
+            error!("Failed to audit impersonated request: {err:?}");
+            None
+        },
+    }
+}
+
+// Access tokens get the same checks as on the API, so logging out or suspending
+// the account also locks the user out of the downstream services.
+async fn verify_gateway_token(redis: &RedisConnectionPool, token: &str) -> Option<UserClaims> {
+    match verify_access_token(redis, token).await {
+        Ok(claims) => Some(claims).filter(|claims| !claims.is_delegated()),
+        Err(err) => {
+            error!("Rejected access token at the gateway: {err:?}");
+            None
+        },
+    }
 }
 
 // Proxy handlers for each service
@@ -168,7 +220,9 @@ pub async fn proxy_to_product_service(
     State(state): State<AppState>,
     request: Request,
 ) -> AppResult<Response<Body>> {
-    let claims = extract_claims_from_request(&request);
+    let (parts, body) = request.into_parts();
+    let claims = extract_claims_from_request(&state, &parts).await;
+    let request = Request::from_parts(parts, body);
     proxy_to_service("product-service", state, claims, request).await
 }
 
@@ -176,7 +230,9 @@ pub async fn proxy_to_order_service(
     State(state): State<AppState>,
     request: Request,
 ) -> AppResult<Response<Body>> {
-    let claims = extract_claims_from_request(&request);
+    let (parts, body) = request.into_parts();
+    let claims = extract_claims_from_request(&state, &parts).await;
+    let request = Request::from_parts(parts, body);
     proxy_to_service("order-service", state, claims, request).await
 }
 
@@ -184,7 +240,9 @@ pub async fn proxy_to_inventory_service(
     State(state): State<AppState>,
     request: Request,
 ) -> AppResult<Response<Body>> {
-    let claims = extract_claims_from_request(&request);
+    let (parts, body) = request.into_parts();
+    let claims = extract_claims_from_request(&state, &parts).await;
+    let request = Request::from_parts(parts, body);
     proxy_to_service("inventory-service", state, claims, request).await
 }
 
@@ -192,6 +250,55 @@ pub async fn proxy_to_notification_service(
     State(state): State<AppState>,
     request: Request,
 ) -> AppResult<Response<Body>> {
-    let claims = extract_claims_from_request(&request);
+    let (parts, body) = request.into_parts();
+    let claims = extract_claims_from_request(&state, &parts).await;
+    let request = Request::from_parts(parts, body);
     proxy_to_service("notification-service", state, claims, request).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::user::user::Status;
+    use crate::infrastructure::persistence::redis_client::{account_status, session};
+    use crate::util::constant::{ACCESS_TOKEN_KEYS, EXPIRE_BEARER_TOKEN_SECS};
+    use uuid::Uuid;
+
+    async fn signed_in(redis: &RedisConnectionPool, user_id: i64) -> (Uuid, String) {
+        let record = session::create_session(redis, user_id, None, &ClientInfo::default()).await.unwrap();
+        let claims = UserClaims::new(EXPIRE_BEARER_TOKEN_SECS, &user_id, &record.session_id);
+        (record.session_id, claims.encode(&ACCESS_TOKEN_KEYS).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_a_token_after_logout() {
+        let redis = RedisConnectionPool::mock().await;
+        let (session_id, token) = signed_in(&redis, 7).await;
+        assert_eq!(verify_gateway_token(&redis, &token).await.map(|claims| claims.user_id), Some(7));
+
+        session::revoke_session(&redis, 7, &session_id).await.unwrap();
+        assert!(verify_gateway_token(&redis, &token).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_a_token_after_suspension() {
+        let redis = RedisConnectionPool::mock().await;
+        let (_, token) = signed_in(&redis, 7).await;
+        assert!(verify_gateway_token(&redis, &token).await.is_some());
+
+        let blocked = account_status::BlockedAccount { status: Status::SUSPENDED, suspended_until: None };
+        account_status::block_account(&redis, 7, &blocked).await.unwrap();
+        assert!(verify_gateway_token(&redis, &token).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_delegated_tokens() {
+        let redis = RedisConnectionPool::mock().await;
+        let record = session::create_session(&redis, 7, None, &ClientInfo::default()).await.unwrap();
+        let token = UserClaims::new(EXPIRE_BEARER_TOKEN_SECS, &7, &record.session_id)
+            .delegated_to("client", "profile")
+            .encode(&ACCESS_TOKEN_KEYS)
+            .unwrap();
+        assert!(verify_gateway_token(&redis, &token).await.is_none());
+    }
+}
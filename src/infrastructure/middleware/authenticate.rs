@@ -1,35 +1,158 @@
+use crate::application::api_key::api_key_service_interface::ApiKeyServiceInterface;
+use crate::application::impersonation::impersonation_service_interface::ImpersonationServiceInterface;
 use crate::core::app_state::AppState;
-use crate::core::error::AppError;
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::api_key;
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::middleware::cookie_session;
+use crate::infrastructure::persistence::redis_client;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::util::claim::UserClaims;
-use crate::util::constant::ACCESS_TOKEN_DECODE_KEY;
+use crate::util::constant::{ACCESS_TOKEN_KEYS, RECENT_AUTH_MAX_AGE_SECS};
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
+use axum::http::Method;
 use axum::RequestPartsExt;
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
 use log::error;
+use sea_orm::TransactionTrait;
+use std::time::Duration;
 
-impl FromRequestParts<AppState> for UserClaims {
+/// Any live access token, including ones a user delegated to a third-party client,
+/// or an API key. Browser clients in cookie session mode send the access token as a
+/// cookie instead of the `Authorization` header. Only endpoints meant for such clients (e.g. `/oauth/userinfo`) take
+/// this directly.
+pub struct BearerClaims(pub UserClaims);
+
+/// Looks the key up and records its use. The key's scopes must cover the request method.
+pub async fn authenticate_api_key(state: &AppState, key: &str, method: &Method) -> AppResult<UserClaims> {
+    let tx = state.db.begin().await?;
+    let user_claims = state.api_key_service.authenticate(&tx, key, method).await?;
+    tx.commit().await?;
+    Ok(user_claims)
+}
+
+/// Decodes an access token and checks it is still good to use: issued for the current
+/// tenant, not revoked, its account not blocked and its session still live.
+/// Every path that accepts access tokens goes through here.
+pub async fn verify_access_token(redis: &RedisConnectionPool, token: &str) -> AppResult<UserClaims> {
+    let user_claims = UserClaims::decode(token, &ACCESS_TOKEN_KEYS)?.claims;
+    user_claims.require_current_tenant()?;
+    // Tokens issued before the account was suspended or deactivated stop working at once
+    redis_client::account_status::ensure_not_blocked(redis, user_claims.user_id).await?;
+    if redis_client::token_denylist::is_revoked(redis, &user_claims.jti).await? {
+        return Err(AppError::InvalidSessionError("Token has been revoked".to_string()));
+    }
+    redis_client::session::is_valid_session(redis, &user_claims, false).await?;
+    Ok(user_claims)
+}
+
+/// Writes the audit entry every request made with an impersonation token gets.
+pub async fn audit_impersonated_request(
+    state: &AppState,
+    claims: &UserClaims,
+    method: &Method,
+    path: &str,
+    client: &ClientInfo,
+) -> AppResult<()> {
+    if !claims.is_impersonated() {
+        return Ok(());
+    }
+    let tx = state.db.begin().await?;
+    state.impersonation_service.record_request(&tx, claims, method.as_str(), path, client).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+impl FromRequestParts<AppState> for BearerClaims {
     type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        match parts.extract::<TypedHeader<Authorization<Bearer>>>().await {
-            Ok(header) => {
-                let TypedHeader(Authorization(bearer)) = header;
-                let user_claims =
-                    UserClaims::decode(bearer.token(), &ACCESS_TOKEN_DECODE_KEY)?.claims;
-                // redis_client::session::is_valid_session(&state.redis, &user_claims, false).await?;
-                Ok(user_claims)
+        let token = match parts.extract::<TypedHeader<Authorization<Bearer>>>().await {
+            Ok(TypedHeader(Authorization(bearer))) => {
+                if api_key::is_api_key(bearer.token()) {
+                    let user_claims = authenticate_api_key(state, bearer.token(), &parts.method).await?;
+                    return Ok(BearerClaims(user_claims));
+                }
+                bearer.token().to_string()
             },
-            Err(err) => {
-                error!("{}", err);
-                Err(AppError::UnauthorizedError(err.to_string()))?
+            Err(err) => match cookie_session::access_token_cookie(
+                &state.config.auth.cookie_session,
+                &parts.headers,
+                &parts.method,
+            )? {
+                Some(token) => token,
+                None => {
+                    error!("{}", err);
+                    return Err(AppError::UnauthorizedError(err.to_string()));
+                },
             },
+        };
+        let user_claims = verify_access_token(&state.redis, &token).await?;
+        let trusted_proxies = &state.config.server.trusted_proxies;
+        let client = ClientInfo::from_http(&parts.headers, &parts.extensions, trusted_proxies);
+        audit_impersonated_request(state, &user_claims, &parts.method, parts.uri.path(), &client).await?;
+        Ok(BearerClaims(user_claims))
+    }
+}
+
+impl FromRequestParts<AppState> for UserClaims {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let BearerClaims(user_claims) = BearerClaims::from_request_parts(parts, state).await?;
+        if user_claims.is_delegated() {
+            return Err(AppError::PermissionDeniedError(
+                "Tokens issued to a client cannot call this endpoint".to_string(),
+            ));
         }
+        Ok(user_claims)
+    }
+}
+
+/// Claims of a user who logged in or re-authenticated at most `MAX_AGE_SECS` ago.
+/// Guards operations a stolen, hours-old access token must not reach.
+pub struct RequireRecentAuth<const MAX_AGE_SECS: u64 = RECENT_AUTH_MAX_AGE_SECS>(pub UserClaims);
+
+impl<const MAX_AGE_SECS: u64> RequireRecentAuth<MAX_AGE_SECS> {
+    pub const MAX_AGE: Duration = Duration::from_secs(MAX_AGE_SECS);
+}
+
+impl<const MAX_AGE_SECS: u64> FromRequestParts<AppState> for RequireRecentAuth<MAX_AGE_SECS> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_claims = UserClaims::from_request_parts(parts, state).await?;
+        user_claims.require_recent_auth(Self::MAX_AGE)?;
+        Ok(RequireRecentAuth(user_claims))
+    }
+}
+
+/// Claims of the account owner. Impersonation tokens are refused, support may look at an
+/// account but not change how it is secured.
+pub struct NotImpersonated(pub UserClaims);
+
+impl FromRequestParts<AppState> for NotImpersonated {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_claims = UserClaims::from_request_parts(parts, state).await?;
+        user_claims.require_not_impersonated()?;
+        Ok(NotImpersonated(user_claims))
     }
 }
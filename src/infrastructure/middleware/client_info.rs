@@ -0,0 +1,120 @@
+use crate::core::app_state::AppState;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
+use axum::http::{Extensions, HeaderMap};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+/// Where a request comes from, recorded on the sessions it creates.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// `trusted_proxies` are the only peers whose forwarding headers are believed, anyone
+    /// else could rotate them to dodge the per-IP throttles.
+    pub fn from_http(headers: &HeaderMap, extensions: &Extensions, trusted_proxies: &[IpAddr]) -> Self {
+        let ip_address = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| client_ip(headers, addr.ip(), trusted_proxies).to_string());
+        let user_agent = headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Self { ip_address, user_agent }
+    }
+}
+
+/// Walks `X-Forwarded-For` from the peer backwards while the hops are trusted proxies. The
+/// first hop that is not one is the client; whatever it sent further left is not believed.
+fn client_ip(headers: &HeaderMap, peer: IpAddr, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if forwarded.is_empty() && trusted_proxies.contains(&peer) {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|ip| ip.trim().parse().ok())
+            .unwrap_or(peer);
+    }
+
+    let mut client = peer;
+    for hop in forwarded.into_iter().rev() {
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+        match hop.parse() {
+            Ok(ip) => client = ip,
+            Err(_) => break,
+        }
+    }
+    client
+}
+
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_http(&parts.headers, &parts.extensions, &state.config.server.trusted_proxies))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const PROXY: &str = "10.0.0.2";
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn client_info(peer: &str, pairs: &[(&'static str, &str)]) -> ClientInfo {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::new(ip(peer), 443)));
+        ClientInfo::from_http(&headers, &extensions, &[ip(PROXY)])
+    }
+
+    #[test]
+    fn test_forwarding_headers_from_untrusted_peers_are_ignored() {
+        let info = client_info("203.0.113.7", &[("x-forwarded-for", "198.51.100.1"), ("x-real-ip", "198.51.100.2")]);
+        assert_eq!(info.ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_trusted_proxy_names_the_client() {
+        let info = client_info(PROXY, &[("x-forwarded-for", "198.51.100.1")]);
+        assert_eq!(info.ip_address.as_deref(), Some("198.51.100.1"));
+        let info = client_info(PROXY, &[("x-real-ip", "198.51.100.2")]);
+        assert_eq!(info.ip_address.as_deref(), Some("198.51.100.2"));
+    }
+
+    #[test]
+    fn test_hops_the_client_prepended_are_not_believed() {
+        // The client sent `X-Forwarded-For: 1.2.3.4`, the proxy appended the real address
+        let info = client_info(PROXY, &[("x-forwarded-for", "1.2.3.4, 198.51.100.1")]);
+        assert_eq!(info.ip_address.as_deref(), Some("198.51.100.1"));
+        let info = client_info(PROXY, &[("x-forwarded-for", "1.2.3.4, garbage")]);
+        assert_eq!(info.ip_address.as_deref(), Some(PROXY));
+    }
+
+    #[test]
+    fn test_no_peer_address_means_no_ip() {
+        let headers = HeaderMap::new();
+        assert_eq!(ClientInfo::from_http(&headers, &Extensions::new(), &[]).ip_address, None);
+    }
+}
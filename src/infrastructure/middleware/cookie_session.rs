@@ -0,0 +1,278 @@
+//! Cookie delivery of the session tokens for browser clients. The access and refresh
+//! tokens live in HttpOnly cookies the page cannot read; a third, readable cookie holds a
+//! CSRF token the page echoes in `X-CSRF-Token` on every state-changing request
+//! (double-submit). Bearer tokens in `Authorization` keep working as before.
+
+use crate::core::app_state::AppState;
+use crate::core::configure::auth::CookieSessionConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::presentation::authen::authen::{LoginResponse, TokenResponse};
+use crate::util::constant::{EXPIRE_BEARER_TOKEN_SECS, EXPIRE_REFRESH_TOKEN_SECS};
+use crate::util::random;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, HeaderValue, Method};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use std::convert::Infallible;
+
+pub const ACCESS_TOKEN_COOKIE: &str = "j18_access";
+pub const REFRESH_TOKEN_COOKIE: &str = "j18_refresh";
+pub const CSRF_COOKIE: &str = "j18_csrf";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+/// Sent by the browser client on login requests to get cookies instead of JSON tokens
+pub const SESSION_MODE_HEADER: &str = "x-session-mode";
+/// The refresh cookie only travels to the endpoint that needs it
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/v1/refresh_token";
+
+/// Value of the cookie `name` in the request's `Cookie` headers.
+pub fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Double-submit check for a request authenticated by cookie: safe methods pass, anything
+/// else must echo the CSRF cookie in `X-CSRF-Token`. A cross-site page can make the
+/// browser send the cookies but cannot read them to fill in the header.
+pub fn verify_csrf(headers: &HeaderMap, method: &Method) -> AppResult<()> {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(());
+    }
+    let cookie = cookie_value(headers, CSRF_COOKIE);
+    let header = headers.get(CSRF_HEADER).and_then(|value| value.to_str().ok());
+    match (cookie, header) {
+        (Some(cookie), Some(header)) if constant_time_eq(cookie.as_bytes(), header.as_bytes()) => {
+            Ok(())
+        },
+        _ => Err(csrf_error()),
+    }
+}
+
+fn csrf_error() -> AppError {
+    AppError::PermissionDeniedError("CSRF token is missing or invalid".to_string())
+}
+
+/// The access token from its cookie when cookie sessions are enabled. Fails when the
+/// request carries the cookie but not a matching CSRF token.
+pub fn access_token_cookie(
+    config: &CookieSessionConfig,
+    headers: &HeaderMap,
+    method: &Method,
+) -> AppResult<Option<String>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    match cookie_value(headers, ACCESS_TOKEN_COOKIE) {
+        Some(token) => {
+            verify_csrf(headers, method)?;
+            Ok(Some(token))
+        },
+        None => Ok(None),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn set_cookie(
+    config: &CookieSessionConfig,
+    name: &str,
+    value: &str,
+    path: &str,
+    max_age: i64,
+    http_only: bool,
+) -> HeaderValue {
+    let mut cookie = format!(
+        "{name}={value}; Path={path}; Max-Age={max_age}; SameSite={}",
+        config.same_site
+    );
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    if config.secure {
+        cookie.push_str("; Secure");
+    }
+    if let Some(domain) = config.domain.as_deref() {
+        cookie.push_str(&format!("; Domain={domain}"));
+    }
+    // Tokens are base64url and dots, the CSRF value alphanumeric, so this cannot fail
+    HeaderValue::from_str(&cookie).unwrap_or_else(|_| HeaderValue::from_static(""))
+}
+
+fn token_cookies(config: &CookieSessionConfig, tokens: &TokenResponse) -> Vec<HeaderValue> {
+    let csrf_token = random::generate_random_string(32);
+    vec![
+        set_cookie(
+            config,
+            ACCESS_TOKEN_COOKIE,
+            &tokens.access_token,
+            "/",
+            EXPIRE_BEARER_TOKEN_SECS.as_secs() as i64,
+            true,
+        ),
+        set_cookie(
+            config,
+            REFRESH_TOKEN_COOKIE,
+            &tokens.refresh_token,
+            REFRESH_TOKEN_COOKIE_PATH,
+            EXPIRE_REFRESH_TOKEN_SECS.as_secs() as i64,
+            true,
+        ),
+        set_cookie(
+            config,
+            CSRF_COOKIE,
+            &csrf_token,
+            "/",
+            EXPIRE_REFRESH_TOKEN_SECS.as_secs() as i64,
+            false,
+        ),
+    ]
+}
+
+fn clear_cookies(config: &CookieSessionConfig) -> Vec<HeaderValue> {
+    vec![
+        set_cookie(config, ACCESS_TOKEN_COOKIE, "", "/", 0, true),
+        set_cookie(config, REFRESH_TOKEN_COOKIE, "", REFRESH_TOKEN_COOKIE_PATH, 0, true),
+        set_cookie(config, CSRF_COOKIE, "", "/", 0, false),
+    ]
+}
+
+/// Responses that may carry a token pair.
+pub trait CarriesTokens {
+    /// Hands the tokens over and leaves empty strings in the body.
+    fn take_tokens(&mut self) -> Option<TokenResponse>;
+}
+
+impl CarriesTokens for TokenResponse {
+    fn take_tokens(&mut self) -> Option<TokenResponse> {
+        let tokens = self.clone();
+        self.access_token.clear();
+        self.refresh_token.clear();
+        Some(tokens)
+    }
+}
+
+impl CarriesTokens for LoginResponse {
+    fn take_tokens(&mut self) -> Option<TokenResponse> {
+        match self {
+            LoginResponse::Token(tokens) => tokens.take_tokens(),
+            LoginResponse::Code { .. } => None,
+        }
+    }
+}
+
+/// How the client wants its tokens: in the JSON body (the default) or, when cookie
+/// sessions are enabled and the request says `X-Session-Mode: cookie`, in cookies.
+pub struct SessionDelivery {
+    config: Option<CookieSessionConfig>,
+    refresh_token: Option<String>,
+    csrf_checked: bool,
+}
+
+impl FromRequestParts<AppState> for SessionDelivery {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let config = &state.config.auth.cookie_session;
+        let wants_cookies = parts
+            .headers
+            .get(SESSION_MODE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.eq_ignore_ascii_case("cookie"));
+        Ok(Self {
+            config: (config.enabled && wants_cookies).then(|| config.clone()),
+            refresh_token: cookie_value(&parts.headers, REFRESH_TOKEN_COOKIE),
+            csrf_checked: verify_csrf(&parts.headers, &parts.method).is_ok(),
+        })
+    }
+}
+
+impl SessionDelivery {
+    /// The refresh token from its cookie, once the CSRF check passed. `None` for JSON clients.
+    pub fn refresh_token(&self) -> AppResult<Option<String>> {
+        match (&self.config, &self.refresh_token) {
+            (Some(_), Some(_)) if !self.csrf_checked => Err(csrf_error()),
+            (Some(_), Some(refresh_token)) => Ok(Some(refresh_token.clone())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Moves the tokens of `body` into cookies for cookie clients, sends `body` as is otherwise.
+    pub fn respond<T: CarriesTokens + Serialize>(&self, mut body: T) -> Response {
+        let cookies = match &self.config {
+            Some(config) => body.take_tokens().map(|tokens| token_cookies(config, &tokens)),
+            None => None,
+        };
+        with_cookies(cookies.unwrap_or_default(), body)
+    }
+
+    /// Removes the session cookies, e.g. on logout.
+    pub fn clear<T: Serialize>(&self, body: T) -> Response {
+        let cookies = self.config.as_ref().map(clear_cookies).unwrap_or_default();
+        with_cookies(cookies, body)
+    }
+}
+
+fn with_cookies<T: Serialize>(cookies: Vec<HeaderValue>, body: T) -> Response {
+    let mut response = Json(body).into_response();
+    for cookie in cookies {
+        response.headers_mut().append(header::SET_COOKIE, cookie);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_cookie_value_is_found_among_others() {
+        let headers = headers(&[("cookie", "theme=dark; j18_csrf=abc123; j18_access=tok")]);
+        assert_eq!(cookie_value(&headers, CSRF_COOKIE).as_deref(), Some("abc123"));
+        assert_eq!(cookie_value(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn test_csrf_requires_matching_header_on_unsafe_methods() {
+        let matching = headers(&[("cookie", "j18_csrf=abc123"), ("x-csrf-token", "abc123")]);
+        let forged = headers(&[("cookie", "j18_csrf=abc123"), ("x-csrf-token", "abc124")]);
+        let missing = headers(&[("cookie", "j18_csrf=abc123")]);
+        assert!(verify_csrf(&matching, &Method::POST).is_ok());
+        assert!(verify_csrf(&forged, &Method::DELETE).is_err());
+        assert!(verify_csrf(&missing, &Method::PUT).is_err());
+        assert!(verify_csrf(&missing, &Method::GET).is_ok());
+    }
+
+    #[test]
+    fn test_token_cookies_are_http_only_except_csrf() {
+        let config = CookieSessionConfig::default();
+        let tokens = TokenResponse::new("access".to_string(), "refresh".to_string(), 60);
+        let cookies: Vec<_> = token_cookies(&config, &tokens)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect();
+        assert!(cookies[0].starts_with("j18_access=access;") && cookies[0].contains("HttpOnly"));
+        assert!(cookies[1].contains("Path=/v1/refresh_token") && cookies[1].contains("Secure"));
+        assert!(cookies[2].starts_with("j18_csrf=") && !cookies[2].contains("HttpOnly"));
+    }
+}
@@ -1 +1,5 @@
 pub mod authenticate;
+pub mod client_info;
+pub mod cookie_session;
+pub mod permission;
+pub mod tenant;
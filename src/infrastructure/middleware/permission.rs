@@ -0,0 +1,55 @@
+//! Per-route permission checks. A route names the permission it needs in its extractor and
+//! lists it as the scope of its `jwt` security requirement, so the OpenAPI document shows it.
+
+use crate::core::app_state::AppState;
+use crate::core::error::AppError;
+use crate::domain::user::permission;
+use crate::util::claim::UserClaims;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use std::marker::PhantomData;
+
+/// A permission a route may require, see [`RequirePermission`].
+pub trait Permission: Send + Sync + 'static {
+    const NAME: &'static str;
+}
+
+macro_rules! permissions {
+    ($($marker:ident => $name:path),* $(,)?) => {
+        $(
+            pub struct $marker;
+
+            impl Permission for $marker {
+                const NAME: &'static str = $name;
+            }
+        )*
+    };
+}
+
+permissions! {
+    UsersRead => permission::USERS_READ,
+    UsersWrite => permission::USERS_WRITE,
+    UsersDelete => permission::USERS_DELETE,
+    UsersSecurity => permission::USERS_SECURITY,
+    UsersImpersonate => permission::USERS_IMPERSONATE,
+    OauthClientsWrite => permission::OAUTH_CLIENTS_WRITE,
+    ServiceAccountsWrite => permission::SERVICE_ACCOUNTS_WRITE,
+}
+
+/// Claims of a signed-in caller holding `P`, e.g.
+/// `RequirePermission(claims, _): RequirePermission<UsersDelete>` with
+/// `security(("jwt" = ["users:delete"]))` on the route.
+pub struct RequirePermission<P: Permission>(pub UserClaims, pub PhantomData<P>);
+
+impl<P: Permission> FromRequestParts<AppState> for RequirePermission<P> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_claims = UserClaims::from_request_parts(parts, state).await?;
+        user_claims.require_permission(P::NAME)?;
+        Ok(RequirePermission(user_claims, PhantomData))
+    }
+}
@@ -0,0 +1,157 @@
+//! Resolves the tenant of every request and runs the rest of the stack inside it, see
+//! [`crate::core::tenant`]. The tenant comes from the subdomain under `tenant.base_domain`,
+//! else the configured header, else the tenant claim of the access token.
+
+use crate::core::app_state::AppState;
+use crate::core::configure::tenant::TenantConfig;
+use crate::core::error::{AppError, AppResult};
+use crate::core::tenant::{self, DEFAULT_TENANT};
+use crate::domain::user::api_key;
+use crate::infrastructure::middleware::cookie_session::{cookie_value, ACCESS_TOKEN_COOKIE};
+use crate::util::claim::UserClaims;
+use crate::util::constant::ACCESS_TOKEN_KEYS;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+fn subdomain_tenant(config: &TenantConfig, headers: &HeaderMap) -> Option<String> {
+    let base_domain = config.base_domain.as_deref()?;
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    let host = host.split(':').next().unwrap_or(host).to_ascii_lowercase();
+    host.strip_suffix(base_domain)?
+        .strip_suffix('.')
+        .filter(|subdomain| !subdomain.is_empty() && !subdomain.contains('.'))
+        .map(str::to_string)
+}
+
+/// Tenant named by the request itself, through the subdomain or the header. The header
+/// cannot move a request off the tenant its host belongs to, signed in or not.
+fn requested_tenant(config: &TenantConfig, headers: &HeaderMap) -> AppResult<Option<String>> {
+    let header = headers
+        .get(config.header.as_str())
+        .and_then(|value| value.to_str().ok())
+        .map(|tenant| tenant.trim().to_ascii_lowercase());
+    match (subdomain_tenant(config, headers), header) {
+        (Some(subdomain), Some(header)) if subdomain != header => Err(AppError::PermissionDeniedError(
+            "The tenant header does not match the host".to_string(),
+        )),
+        (Some(tenant), _) | (None, Some(tenant)) => Ok(Some(tenant)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Tenant claim of the access token, if the request carries one that verifies. Anything
+/// else is left to the authentication extractors to reject.
+fn token_tenant(state: &AppState, headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let token = match bearer {
+        // API keys carry no claims, their owner is looked up in the resolved tenant
+        Some(token) if api_key::is_api_key(&token) => return None,
+        Some(token) => token,
+        None if state.config.auth.cookie_session.enabled => {
+            cookie_value(headers, ACCESS_TOKEN_COOKIE)?
+        },
+        None => return None,
+    };
+    UserClaims::decode(&token, &ACCESS_TOKEN_KEYS).ok().map(|data| data.claims.tenant_id)
+}
+
+/// A token is only good in the tenant it was issued for, whatever the request asks for.
+pub fn resolve_tenant(requested: Option<String>, claimed: Option<String>) -> AppResult<String> {
+    let tenant = match (requested, claimed) {
+        (Some(requested), Some(claimed)) if requested != claimed => {
+            return Err(AppError::PermissionDeniedError(
+                "The token was not issued for this tenant".to_string(),
+            ))
+        },
+        (Some(tenant), _) | (None, Some(tenant)) => tenant,
+        (None, None) => DEFAULT_TENANT.to_string(),
+    };
+    tenant::validate_tenant_id(&tenant)?;
+    Ok(tenant)
+}
+
+pub async fn tenant_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let tenant = requested_tenant(&state.config.tenant, request.headers()).and_then(|requested| {
+        let claimed = token_tenant(&state, request.headers());
+        resolve_tenant(requested, claimed)
+    });
+    match tenant {
+        Ok(tenant) if state.config.tenant.is_known(&tenant) => {
+            tenant::scope(tenant, next.run(request)).await
+        },
+        Ok(tenant) => AppError::EntityNotFoundError { detail: format!("Unknown tenant {tenant}") }
+            .into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn config() -> TenantConfig {
+        TenantConfig { base_domain: Some("erp.example.com".to_string()), ..TenantConfig::default() }
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_header_cannot_override_subdomain() {
+        let other = headers(&[("x-tenant-id", "Globex"), ("host", "acme.erp.example.com")]);
+        assert!(requested_tenant(&config(), &other).is_err());
+        let same = headers(&[("x-tenant-id", "Acme"), ("host", "acme.erp.example.com")]);
+        assert_eq!(requested_tenant(&config(), &same).unwrap().as_deref(), Some("acme"));
+    }
+
+    #[test]
+    fn test_header_names_tenant_off_tenant_hosts() {
+        let headers = headers(&[("x-tenant-id", "Globex"), ("host", "erp.example.com")]);
+        assert_eq!(requested_tenant(&config(), &headers).unwrap().as_deref(), Some("globex"));
+    }
+
+    #[test]
+    fn test_subdomain_of_base_domain_only() {
+        let tenant = |host| requested_tenant(&config(), &headers(&[("host", host)])).unwrap();
+        assert_eq!(tenant("acme.erp.example.com:8080").as_deref(), Some("acme"));
+        assert_eq!(tenant("erp.example.com"), None);
+        assert_eq!(tenant("a.b.erp.example.com"), None);
+        assert_eq!(tenant("acme.evil-erp.example.com"), None);
+    }
+
+    #[test]
+    fn test_only_known_tenants_when_listed() {
+        assert!(config().is_known("anything"));
+        let config = TenantConfig { known: vec!["acme".to_string()], ..config() };
+        assert!(config.is_known("acme"));
+        assert!(config.is_known(DEFAULT_TENANT));
+        assert!(!config.is_known("globex"));
+    }
+
+    #[test]
+    fn test_token_tenant_must_match_requested() {
+        let some = |tenant: &str| Some(tenant.to_string());
+        assert_eq!(resolve_tenant(None, None).unwrap(), DEFAULT_TENANT);
+        assert_eq!(resolve_tenant(some("acme"), None).unwrap(), "acme");
+        assert_eq!(resolve_tenant(None, some("acme")).unwrap(), "acme");
+        assert_eq!(resolve_tenant(some("acme"), some("acme")).unwrap(), "acme");
+        assert!(resolve_tenant(some("acme"), some("globex")).is_err());
+        assert!(resolve_tenant(some("acme:*"), None).is_err());
+    }
+}
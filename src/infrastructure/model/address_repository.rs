@@ -1,13 +1,24 @@
 use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
 use crate::domain::address::address::{ActiveModel, ActiveModelEx, Column, Entity, Model, ModelEx};
 use crate::domain::address::address_repository_interface::AddressRepositoryInterface;
 use crate::domain::user;
 use async_trait::async_trait;
+use sea_orm::sea_query::SimpleExpr;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityLoaderTrait, EntityTrait, ExprTrait, QueryFilter, Set};
 
+/// Addresses live in the tenant of their owner, the request's tenant
+fn in_current_tenant() -> SimpleExpr {
+    Column::TenantId.eq(tenant::current_tenant())
+}
+
 #[async_trait]
 impl AddressRepositoryInterface for Entity {
-    async fn create_address(conn: &DatabaseTransaction, model: ActiveModelEx) -> AppResult<bool> {
+    async fn create_address(
+        conn: &DatabaseTransaction,
+        mut model: ActiveModelEx,
+    ) -> AppResult<bool> {
+        model.tenant_id = Set(tenant::current_tenant());
         let _address = model
             .insert(conn)
             .await
@@ -16,6 +27,11 @@ impl AddressRepositoryInterface for Entity {
     }
 
     async fn update_address(conn: &DatabaseTransaction, model: ActiveModelEx) -> AppResult<bool> {
+        // Updates go by primary key, so the model must come from this tenant's queries
+        let tenant_id = model.tenant_id.try_as_ref();
+        if tenant_id.is_some_and(|tenant_id| *tenant_id != tenant::current_tenant()) {
+            return Err(AppError::EntityNotFoundError { detail: "Address not found".to_string() });
+        }
         // Convert Model to ActiveModel in infrastructure layer
         let _address = model
             .update(conn)
@@ -27,6 +43,7 @@ impl AddressRepositoryInterface for Entity {
     async fn find_address_by_id(conn: &DatabaseTransaction, id: i64) -> AppResult<Option<ModelEx>> {
         let address = Entity::load()
             .filter_by_id(id)
+            .filter(in_current_tenant())
             .with(user::user::Entity)
             .one(conn)
             .await?;
@@ -34,11 +51,13 @@ impl AddressRepositoryInterface for Entity {
     }
 
     async fn delete_address(conn: &DatabaseTransaction, id: i64) -> AppResult<()> {
-        let address = Entity::find_by_id(id).one(conn).await?.ok_or_else(|| {
-            AppError::EntityNotFoundError {
+        let address = Entity::find_by_id(id)
+            .filter(in_current_tenant())
+            .one(conn)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFoundError {
                 detail: format!("Address with id {} not found", id),
-            }
-        })?;
+            })?;
 
         let mut address: ActiveModel = address.into();
         address.is_deleted = Set(true);
@@ -55,7 +74,8 @@ impl AddressRepositoryInterface for Entity {
             .filter(
                 Column::UserId
                     .eq(user_id)
-                    .and(Column::IsDeleted.eq(false)),
+                    .and(Column::IsDeleted.eq(false))
+                    .and(in_current_tenant()),
             )
             .with(user::user::Entity)
             .all(conn)
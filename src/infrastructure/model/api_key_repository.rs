@@ -0,0 +1,74 @@
+use crate::core::error::AppResult;
+use crate::core::tenant;
+use crate::domain::user::api_key::{ActiveModel, Column, Entity, Model};
+use crate::domain::user::api_key_repository_interface::ApiKeyRepositoryInterface;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{ColumnTrait, Condition, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// A key only authenticates in the tenant it was issued in
+fn in_current_tenant() -> SimpleExpr {
+    Column::TenantId.eq(tenant::current_tenant())
+}
+
+#[async_trait]
+impl ApiKeyRepositoryInterface for Entity {
+    async fn create_api_key(conn: &DatabaseTransaction, mut model: ActiveModel) -> AppResult<i64> {
+        model.tenant_id = Set(tenant::current_tenant());
+        let result = Entity::insert(model).exec(conn).await?;
+        Ok(result.last_insert_id)
+    }
+
+    async fn find_api_key_by_id(conn: &DatabaseTransaction, id: i64) -> AppResult<Option<Model>> {
+        let api_key = Entity::find_by_id(id).filter(in_current_tenant()).one(conn).await?;
+        Ok(api_key)
+    }
+
+    async fn find_api_key_by_hash(conn: &DatabaseTransaction, key_hash: &str) -> AppResult<Option<Model>> {
+        let api_key = Entity::find()
+            .filter(Column::KeyHash.eq(key_hash))
+            .filter(in_current_tenant())
+            .one(conn)
+            .await?;
+        Ok(api_key)
+    }
+
+    async fn list_api_keys(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<Model>> {
+        let api_keys = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(in_current_tenant())
+            .order_by_desc(Column::CreatedAt)
+            .all(conn)
+            .await?;
+        Ok(api_keys)
+    }
+
+    async fn touch_api_key(conn: &DatabaseTransaction, id: i64) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+        Entity::update_many()
+            .col_expr(Column::LastUsedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(in_current_tenant())
+            .filter(
+                Condition::any()
+                    .add(Column::LastUsedAt.is_null())
+                    .add(Column::LastUsedAt.lt(now - Duration::minutes(1))),
+            )
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_api_key(conn: &DatabaseTransaction, user_id: i64, id: i64) -> AppResult<bool> {
+        let result = Entity::update_many()
+            .col_expr(Column::RevokedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::RevokedAt.is_null())
+            .filter(in_current_tenant())
+            .exec(conn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,13 @@
+use crate::core::error::AppResult;
+use crate::domain::user::audit_log::{ActiveModel, Entity};
+use crate::domain::user::audit_log_repository_interface::AuditLogRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::{DatabaseTransaction, EntityTrait};
+
+#[async_trait]
+impl AuditLogRepositoryInterface for Entity {
+    async fn create_audit_log(conn: &DatabaseTransaction, model: ActiveModel) -> AppResult<()> {
+        Entity::insert(model).exec(conn).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use crate::core::error::AppResult;
+use crate::core::tenant;
+use crate::domain::oauth_client::consent::{ActiveModel, Column, Entity, Model};
+use crate::domain::oauth_client::consent_repository_interface::ConsentRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::sea_query::{Expr, OnConflict, SimpleExpr};
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+
+/// Consents are given to a client of the request's tenant
+fn in_current_tenant() -> SimpleExpr {
+    Column::TenantId.eq(tenant::current_tenant())
+}
+
+#[async_trait]
+impl ConsentRepositoryInterface for Entity {
+    async fn find_consent(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        client_id: &str,
+    ) -> AppResult<Option<Model>> {
+        let consent = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ClientId.eq(client_id))
+            .filter(in_current_tenant())
+            .one(conn)
+            .await?;
+        Ok(consent)
+    }
+
+    async fn save_consent(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        client_id: &str,
+        scopes: String,
+    ) -> AppResult<()> {
+        Entity::insert(ActiveModel::new_consent(user_id, client_id.to_string(), scopes))
+            .on_conflict(
+                OnConflict::columns([Column::TenantId, Column::UserId, Column::ClientId])
+                    .update_column(Column::Scopes)
+                    .value(Column::UpdatedAt, Expr::value(chrono::Utc::now().naive_utc()))
+                    .to_owned(),
+            )
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_consents(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<Model>> {
+        let consents = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(in_current_tenant())
+            .order_by_desc(Column::UpdatedAt)
+            .all(conn)
+            .await?;
+        Ok(consents)
+    }
+
+    async fn delete_consent(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        client_id: &str,
+    ) -> AppResult<bool> {
+        let result = Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ClientId.eq(client_id))
+            .filter(in_current_tenant())
+            .exec(conn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::core::error::AppResult;
+use crate::core::tenant;
+use crate::domain::user::identity::{ActiveModel, Column, Entity, Model};
+use crate::domain::user::identity_repository_interface::IdentityRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// A provider account is linked per tenant, so only this tenant's links are ever seen
+fn in_current_tenant() -> SimpleExpr {
+    Column::TenantId.eq(tenant::current_tenant())
+}
+
+#[async_trait]
+impl IdentityRepositoryInterface for Entity {
+    async fn create_identity(conn: &DatabaseTransaction, mut model: ActiveModel) -> AppResult<()> {
+        model.tenant_id = Set(tenant::current_tenant());
+        Entity::insert(model).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn find_identity(
+        conn: &DatabaseTransaction,
+        provider: &str,
+        subject: &str,
+    ) -> AppResult<Option<Model>> {
+        let identity = Entity::find()
+            .filter(Column::Provider.eq(provider))
+            .filter(Column::Subject.eq(subject))
+            .filter(in_current_tenant())
+            .one(conn)
+            .await?;
+        Ok(identity)
+    }
+
+    async fn list_identities(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<Model>> {
+        let identities = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(in_current_tenant())
+            .order_by_asc(Column::CreatedAt)
+            .all(conn)
+            .await?;
+        Ok(identities)
+    }
+
+    async fn touch_identity_login(conn: &DatabaseTransaction, id: i64) -> AppResult<()> {
+        Entity::update_many()
+            .col_expr(Column::LastLoginAt, Expr::value(chrono::Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .filter(in_current_tenant())
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_identity(conn: &DatabaseTransaction, user_id: i64, id: i64) -> AppResult<bool> {
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .filter(in_current_tenant())
+            .exec(conn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -1,2 +1,12 @@
 mod user_repository;
-mod address_repository;
\ No newline at end of file
+mod address_repository;
+mod recovery_code_repository;
+mod oauth_client_repository;
+mod consent_repository;
+mod identity_repository;
+mod api_key_repository;
+mod password_history_repository;
+mod security_event_repository;
+mod audit_log_repository;
+mod role_repository;
+mod status_change_repository;
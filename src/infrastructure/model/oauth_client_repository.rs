@@ -0,0 +1,27 @@
+use crate::core::error::AppResult;
+use crate::core::tenant;
+use crate::domain::oauth_client::oauth_client::{ActiveModelEx, Column, Entity, ModelEx};
+use crate::domain::oauth_client::oauth_client_repository_interface::OauthClientRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, DatabaseTransaction, QueryFilter, Set};
+
+#[async_trait]
+impl OauthClientRepositoryInterface for Entity {
+    async fn create_client(conn: &DatabaseTransaction, mut model: ActiveModelEx) -> AppResult<bool> {
+        model.tenant_id = Set(tenant::current_tenant());
+        model.insert(conn).await?;
+        Ok(true)
+    }
+
+    async fn find_client_by_client_id(
+        conn: &DatabaseTransaction,
+        client_id: &str,
+    ) -> AppResult<Option<ModelEx>> {
+        let client = Entity::load()
+            .filter(Column::ClientId.eq(client_id))
+            .filter(Column::TenantId.eq(tenant::current_tenant()))
+            .one(conn)
+            .await?;
+        Ok(client)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::error::AppResult;
+use crate::domain::user::password_history::{ActiveModel, Column, Entity};
+use crate::domain::user::password_history_repository_interface::PasswordHistoryRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+#[async_trait]
+impl PasswordHistoryRepositoryInterface for Entity {
+    async fn create_password_history(conn: &DatabaseTransaction, model: ActiveModel) -> AppResult<()> {
+        Entity::insert(model).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn list_recent_password_hashes(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        limit: u64,
+    ) -> AppResult<Vec<String>> {
+        let hashes = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|entry| entry.password_hash)
+            .collect();
+        Ok(hashes)
+    }
+
+    async fn prune_password_history(conn: &DatabaseTransaction, user_id: i64, keep: u64) -> AppResult<()> {
+        let kept: Vec<i64> = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .limit(keep)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Id.is_not_in(kept))
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+}
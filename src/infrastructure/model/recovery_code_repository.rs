@@ -0,0 +1,55 @@
+use crate::core::error::AppResult;
+use crate::domain::user::recovery_code::{ActiveModel, Column, Entity};
+use crate::domain::user::recovery_code_repository_interface::RecoveryCodeRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, PaginatorTrait, QueryFilter};
+
+#[async_trait]
+impl RecoveryCodeRepositoryInterface for Entity {
+    async fn replace_recovery_codes(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        code_hashes: Vec<String>,
+    ) -> AppResult<()> {
+        Entity::delete_many().filter(Column::UserId.eq(user_id)).exec(conn).await?;
+        if code_hashes.is_empty() {
+            return Ok(());
+        }
+        let models = code_hashes
+            .into_iter()
+            .map(|code_hash| ActiveModel::new_recovery_code(user_id, code_hash));
+        Entity::insert_many(models).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn consume_recovery_code(
+        conn: &DatabaseTransaction,
+        user_id: i64,
+        code_hash: &str,
+    ) -> AppResult<bool> {
+        // Conditional update so the same code cannot be spent twice concurrently
+        let result = Entity::update_many()
+            .col_expr(Column::UsedAt, Expr::value(chrono::Utc::now().naive_utc()))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::CodeHash.eq(code_hash))
+            .filter(Column::UsedAt.is_null())
+            .exec(conn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete_recovery_codes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<()> {
+        Entity::delete_many().filter(Column::UserId.eq(user_id)).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn count_unused_recovery_codes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<u64> {
+        let count = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::UsedAt.is_null())
+            .count(conn)
+            .await?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,88 @@
+use crate::core::error::AppResult;
+use crate::core::tenant;
+use crate::domain::user::role::{Column, Entity, Model};
+use crate::domain::user::role_repository_interface::RoleRepositoryInterface;
+use crate::domain::user::{permission, role_permission, user_role};
+use async_trait::async_trait;
+use sea_orm::sea_query::{NullOrdering, OnConflict};
+use sea_orm::{ColumnTrait, Condition, DatabaseTransaction, EntityTrait, Order, QueryFilter, QueryOrder};
+
+/// The built-in roles plus the ones the request's tenant defined itself
+fn visible_in_current_tenant() -> Condition {
+    Condition::any()
+        .add(Column::TenantId.is_null())
+        .add(Column::TenantId.eq(tenant::current_tenant()))
+}
+
+#[async_trait]
+impl RoleRepositoryInterface for Entity {
+    async fn find_role_by_name(conn: &DatabaseTransaction, name: &str) -> AppResult<Option<Model>> {
+        // A tenant's own role shadows a built-in one of the same name
+        let role = Entity::find()
+            .filter(Column::Name.eq(name))
+            .filter(visible_in_current_tenant())
+            .order_by_with_nulls(Column::TenantId, Order::Asc, NullOrdering::Last)
+            .one(conn)
+            .await?;
+        Ok(role)
+    }
+
+    async fn find_roles_of_user(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<Model>> {
+        let role_ids: Vec<i64> = user_role::Entity::find()
+            .filter(user_role::Column::UserId.eq(user_id))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|user_role| user_role.role_id)
+            .collect();
+        if role_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let roles = Entity::find()
+            .filter(Column::Id.is_in(role_ids))
+            .filter(visible_in_current_tenant())
+            .order_by_asc(Column::Name)
+            .all(conn)
+            .await?;
+        Ok(roles)
+    }
+
+    async fn find_permission_names(conn: &DatabaseTransaction, role_ids: &[i64]) -> AppResult<Vec<String>> {
+        if role_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let permission_ids: Vec<i64> = role_permission::Entity::find()
+            .filter(role_permission::Column::RoleId.is_in(role_ids.iter().copied()))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|role_permission| role_permission.permission_id)
+            .collect();
+        if permission_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        // `is_in` on the ids already yields each permission once
+        let names = permission::Entity::find()
+            .filter(permission::Column::Id.is_in(permission_ids))
+            .order_by_asc(permission::Column::Name)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|permission| permission.name)
+            .collect();
+        Ok(names)
+    }
+
+    async fn assign_role(conn: &DatabaseTransaction, model: user_role::ActiveModel) -> AppResult<()> {
+        user_role::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([user_role::Column::UserId, user_role::Column::RoleId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use crate::core::error::AppResult;
+use crate::domain::user::security_event::{ActiveModel, Column, Entity, Model};
+use crate::domain::user::security_event_repository_interface::SecurityEventRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+#[async_trait]
+impl SecurityEventRepositoryInterface for Entity {
+    async fn create_security_event(conn: &DatabaseTransaction, model: ActiveModel) -> AppResult<()> {
+        Entity::insert(model).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn list_security_events(conn: &DatabaseTransaction, user_id: i64, limit: u64) -> AppResult<Vec<Model>> {
+        let events = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .all(conn)
+            .await?;
+        Ok(events)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::core::error::AppResult;
+use crate::domain::user::status_change::{ActiveModel, Column, Entity, Model};
+use crate::domain::user::status_change_repository_interface::StatusChangeRepositoryInterface;
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+
+#[async_trait]
+impl StatusChangeRepositoryInterface for Entity {
+    async fn create_status_change(conn: &DatabaseTransaction, model: ActiveModel) -> AppResult<()> {
+        Entity::insert(model).exec(conn).await?;
+        Ok(())
+    }
+
+    async fn list_status_changes(conn: &DatabaseTransaction, user_id: i64) -> AppResult<Vec<Model>> {
+        let changes = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .all(conn)
+            .await?;
+        Ok(changes)
+    }
+}
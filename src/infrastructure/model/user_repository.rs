@@ -1,15 +1,27 @@
 use async_trait::async_trait;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityLoaderTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
-use crate::core::error::AppResult;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityLoaderTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
 use crate::domain::user::user::{ActiveModel, ActiveModelEx, Model, ModelEx};
 use crate::domain::user::user_repository_interface::UserRepositoryInterface;
 use crate::domain::{address, user};
+use sea_orm::sea_query::{Expr, ExprTrait, Func, SimpleExpr};
+
+/// `LOWER(column) = LOWER(value)`, served by the lower() unique indexes on users
+fn lower_eq(column: user::user::Column, value: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(Expr::col(column))).eq(value.trim().to_lowercase())
+}
+
+/// Every query only ever sees the users of the request's tenant
+fn in_current_tenant() -> SimpleExpr {
+    user::user::Column::TenantId.eq(tenant::current_tenant())
+}
 
 #[async_trait]
 impl UserRepositoryInterface for user::user::Entity {
-    async fn create_user(conn: &DatabaseTransaction, model: ActiveModelEx) -> AppResult<bool> {
+    async fn create_user(conn: &DatabaseTransaction, mut model: ActiveModelEx) -> AppResult<bool> {
         // Convert Model to ActiveModel in infrastructure layer
-
+        model.tenant_id = Set(tenant::current_tenant());
 
         let user = model.insert(conn).await.map_err(
             |e| e,
@@ -19,6 +31,11 @@ impl UserRepositoryInterface for user::user::Entity {
     }
 
     async fn update_user(conn: &DatabaseTransaction, model: ActiveModelEx) -> AppResult<bool> {
+        // Updates go by primary key, so the model must come from this tenant's queries
+        let tenant_id = model.tenant_id.try_as_ref();
+        if tenant_id.is_some_and(|tenant_id| *tenant_id != tenant::current_tenant()) {
+            return Err(AppError::EntityNotFoundError { detail: "User not found".to_string() });
+        }
         let _user = model.update(conn).await?;
         Ok(true)
     }
@@ -26,6 +43,7 @@ impl UserRepositoryInterface for user::user::Entity {
     async fn find_user_by_id(conn: &DatabaseTransaction, id: i64) -> AppResult<Option<ModelEx>> {
         let user = user::user::Entity::load()
             .filter_by_id(id)
+            .filter(in_current_tenant())
             .with(address::address::Entity)
             .one(conn)
             .await?;
@@ -37,7 +55,8 @@ impl UserRepositoryInterface for user::user::Entity {
         username: &str,
     ) -> AppResult<Option<ModelEx>> {
         let user = user::user::Entity::load()
-            .filter(user::user::Column::Username.eq(username))
+            .filter(lower_eq(user::user::Column::Username, username))
+            .filter(in_current_tenant())
             .with(address::address::Entity)
             .one(conn)
             .await?;
@@ -49,19 +68,49 @@ impl UserRepositoryInterface for user::user::Entity {
         email: &str,
     ) -> AppResult<Option<ModelEx>> {
         let user = user::user::Entity::load()
-            .filter(user::user::Column::Email.eq(email))
+            .filter(lower_eq(user::user::Column::Email, email))
+            .filter(in_current_tenant())
             .with(address::address::Entity)
             .one(conn)
             .await?;
         Ok(user)
     }
 
+    async fn find_user_by_identifier(
+        conn: &DatabaseTransaction,
+        identifier: &str,
+    ) -> AppResult<Option<ModelEx>> {
+        if identifier.contains('@') {
+            Self::find_user_by_email(conn, identifier).await
+        } else {
+            Self::find_user_by_username(conn, identifier).await
+        }
+    }
+
+    async fn find_user_by_phone_number(
+        conn: &DatabaseTransaction,
+        phone_number: &str,
+    ) -> AppResult<Option<ModelEx>> {
+        let holders = user::user::Entity::find()
+            .filter(user::user::Column::PhoneNumber.eq(phone_number.trim()))
+            .filter(in_current_tenant())
+            .filter(user::user::Column::IsDeleted.eq(false))
+            .limit(2)
+            .all(conn)
+            .await?;
+        match holders.as_slice() {
+            [holder] => Self::find_user_by_id(conn, holder.id).await,
+            _ => Ok(None),
+        }
+    }
+
     async fn delete_user(conn: &DatabaseTransaction, id: i64) -> AppResult<()> {
         use sea_orm::Set;
         let user = user::user::Entity::find_by_id(id)
+            .filter(in_current_tenant())
             .one(conn)
             .await?
-            .ok_or_else(|| crate::core::error::AppError::EntityNotFoundError {
+            .ok_or_else(|| AppError::EntityNotFoundError {
                 detail: format!("User with id {} not found", id),
             })?;
 
@@ -72,11 +121,12 @@ impl UserRepositoryInterface for user::user::Entity {
         Ok(())
     }
 
+    // Deleted accounts still hold their username and email in the per-tenant unique indexes
     async fn username_exists(conn: &DatabaseTransaction, username: &str) -> AppResult<bool> {
         use sea_orm::EntityTrait;
         let count = user::user::Entity::find()
-            .filter(user::user::Column::Username.eq(username))
-            .filter(user::user::Column::IsDeleted.eq(false))
+            .filter(lower_eq(user::user::Column::Username, username))
+            .filter(in_current_tenant())
             .count(conn)
             .await?;
         Ok(count > 0)
@@ -85,8 +135,8 @@ impl UserRepositoryInterface for user::user::Entity {
     async fn email_exists(conn: &DatabaseTransaction, email: &str) -> AppResult<bool> {
         use sea_orm::EntityTrait;
         let count = user::user::Entity::find()
-            .filter(user::user::Column::Email.eq(email))
-            .filter(user::user::Column::IsDeleted.eq(false))
+            .filter(lower_eq(user::user::Column::Email, email))
+            .filter(in_current_tenant())
             .count(conn)
             .await?;
         Ok(count > 0)
@@ -100,6 +150,21 @@ impl UserRepositoryInterface for user::user::Entity {
         use sea_orm::{EntityTrait, PaginatorTrait};
         let users = user::user::Entity::find()
             .filter(user::user::Column::IsDeleted.eq(false))
+            .filter(in_current_tenant())
+            .paginate(conn, page_size)
+            .fetch_page(page)
+            .await?;
+        Ok(users)
+    }
+
+    async fn list_all_users(
+        conn: &DatabaseTransaction,
+        page: u64,
+        page_size: u64,
+    ) -> AppResult<Vec<Model>> {
+        let users = user::user::Entity::find()
+            .filter(in_current_tenant())
+            .order_by_asc(user::user::Column::Id)
             .paginate(conn, page_size)
             .fetch_page(page)
             .await?;
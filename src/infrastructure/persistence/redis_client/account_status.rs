@@ -0,0 +1,61 @@
+use crate::core::error::{AppError, AppResult};
+use crate::domain::user::user::Status;
+use crate::infrastructure::third_party::redis::errors;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::util::constant::EXPIRE_BEARER_TOKEN_SECS;
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An account that may not use its tokens, checked on every authenticated request.
+/// Login and refresh read the status from the database, so the entry only has to
+/// outlive the access tokens issued before the change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockedAccount {
+    pub status: Status,
+    pub suspended_until: Option<NaiveDateTime>,
+}
+
+fn blocked_account_key(user_id: i64) -> String {
+    format!("blocked:user_id:{user_id}")
+}
+
+pub async fn block_account(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    blocked: &BlockedAccount,
+) -> AppResult<()> {
+    let mut ttl = EXPIRE_BEARER_TOKEN_SECS.as_secs() as i64;
+    if let Some(until) = blocked.suspended_until {
+        ttl = ttl.min((until - Utc::now().naive_utc()).num_seconds());
+    }
+    if ttl <= 0 {
+        return Ok(());
+    }
+    redis
+        .serialize_and_set_key_with_expiry(&blocked_account_key(user_id).into(), blocked, ttl)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+pub async fn unblock_account(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    redis
+        .delete_key(&blocked_account_key(user_id).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Fails with the account's status error while it is blocked.
+pub async fn ensure_not_blocked(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    match redis
+        .get_and_deserialize_key::<BlockedAccount>(&blocked_account_key(user_id).into(), "BlockedAccount")
+        .await
+    {
+        Ok(blocked) => match blocked.status.sign_in_error(blocked.suspended_until) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        },
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => Ok(()),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
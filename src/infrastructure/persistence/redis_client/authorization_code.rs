@@ -0,0 +1,62 @@
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::errors;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::DelReply;
+use crate::util::constant::EXPIRE_AUTHORIZATION_CODE_SECS;
+use crate::util::random;
+use serde::{Deserialize, Serialize};
+
+/// What `/oauth/authorize` approved, waiting for the client to redeem it at `/oauth/token`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationCode {
+    pub client_id: String,
+    pub user_id: i64,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub nonce: Option<String>,
+    pub code_challenge: String,
+    /// When the user last entered their credentials, echoed in the id_token
+    pub auth_time: i64,
+}
+
+fn authorization_code_key(code: &str) -> String {
+    format!("oauth_code:code:{code}")
+}
+
+/// Stores the grant and returns the code handed to the client through the redirect.
+pub async fn create_authorization_code(
+    redis: &RedisConnectionPool,
+    grant: &AuthorizationCode,
+) -> AppResult<String> {
+    let code = random::generate_random_string(48);
+    redis
+        .serialize_and_set_key_with_expiry(
+            &authorization_code_key(&code).into(),
+            grant,
+            EXPIRE_AUTHORIZATION_CODE_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(code)
+}
+
+/// Codes are single use: only the caller that actually removes the code gets the grant.
+pub async fn take_authorization_code(
+    redis: &RedisConnectionPool,
+    code: &str,
+) -> AppResult<Option<AuthorizationCode>> {
+    let key = authorization_code_key(code).into();
+    let grant = match redis
+        .get_and_deserialize_key::<AuthorizationCode>(&key, "AuthorizationCode")
+        .await
+    {
+        Ok(grant) => grant,
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => return Ok(None),
+        Err(err) => return Err(AppError::BadRequestError(err.to_string())),
+    };
+    match redis.delete_key(&key).await {
+        Ok(DelReply::KeyDeleted) => Ok(Some(grant)),
+        Ok(DelReply::KeyNotDeleted) => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::errors;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::DelReply;
+use crate::util::constant::EXPIRE_EXTERNAL_LOGIN_SECS;
+use crate::util::random;
+use serde::{Deserialize, Serialize};
+
+/// A sign-in at an external provider that was started here and waits for the callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalLoginState {
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    /// Set when a signed-in user links the identity instead of logging in with it
+    pub link_user_id: Option<i64>,
+    pub device_name: Option<String>,
+}
+
+fn external_login_key(state: &str) -> String {
+    format!("external_login:state:{state}")
+}
+
+/// Stores the flow and returns the `state` value sent to the provider.
+pub async fn create_external_login(
+    redis: &RedisConnectionPool,
+    login: &ExternalLoginState,
+) -> AppResult<String> {
+    let state = random::generate_random_string(48);
+    redis
+        .serialize_and_set_key_with_expiry(
+            &external_login_key(&state).into(),
+            login,
+            EXPIRE_EXTERNAL_LOGIN_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(state)
+}
+
+/// Single use, so a callback URL cannot be replayed.
+pub async fn take_external_login(
+    redis: &RedisConnectionPool,
+    state: &str,
+) -> AppResult<Option<ExternalLoginState>> {
+    let key = external_login_key(state).into();
+    let login = match redis
+        .get_and_deserialize_key::<ExternalLoginState>(&key, "ExternalLoginState")
+        .await
+    {
+        Ok(login) => login,
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => return Ok(None),
+        Err(err) => return Err(AppError::BadRequestError(err.to_string())),
+    };
+    match redis.delete_key(&key).await {
+        Ok(DelReply::KeyDeleted) => Ok(Some(login)),
+        Ok(DelReply::KeyNotDeleted) => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
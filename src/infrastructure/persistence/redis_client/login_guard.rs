@@ -0,0 +1,260 @@
+//! Failed password attempts are counted per account and per client IP. Every failure
+//! opens a backoff window that doubles with each attempt; too many failures on one
+//! account lock it for `EXPIRE_BLOCKED_EMAIL_SECS`. Known accounts are tracked by user id,
+//! so signing in with the username and the email draw on the same budget. Unknown
+//! identifiers are tracked the same way so the responses do not reveal which accounts exist.
+//!
+//! Wrong second-factor codes are counted per user on their own. A correct password
+//! does not reset that count, so minting new login challenges does not buy more guesses.
+
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::RedisKey;
+use crate::util::constant::{
+    EXPIRE_BLOCKED_EMAIL_SECS, EXPIRE_LOGIN_FAILURES_SECS, EXPIRE_SECOND_FACTOR_FAILURES_SECS,
+    MAX_LOGIN_BACKOFF_SECS, MAX_LOGIN_FAILURES_PER_IP, MAX_LOGIN_FAILURES_PER_USERNAME,
+    MAX_SECOND_FACTOR_FAILURES,
+};
+
+/// Whose budget a login attempt draws on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginSubject {
+    User(i64),
+    /// Identifier that matches no account
+    Unknown(String),
+}
+
+impl LoginSubject {
+    pub fn new(user_id: Option<i64>, identifier: &str) -> Self {
+        match user_id {
+            Some(user_id) => Self::User(user_id),
+            None => Self::Unknown(normalize(identifier)),
+        }
+    }
+
+    fn scope(&self) -> (&'static str, String) {
+        match self {
+            Self::User(user_id) => ("user_id", user_id.to_string()),
+            Self::Unknown(identifier) => ("identifier", identifier.clone()),
+        }
+    }
+}
+
+fn failures_key(scope: &str, value: &str) -> RedisKey {
+    format!("login_failures:{scope}:{value}").into()
+}
+
+fn backoff_key(scope: &str, value: &str) -> RedisKey {
+    format!("login_backoff:{scope}:{value}").into()
+}
+
+fn lock_key(scope: &str, value: &str) -> RedisKey {
+    format!("login_locked:{scope}:{value}").into()
+}
+
+fn second_factor_failures_key(user_id: i64) -> RedisKey {
+    format!("second_factor_failures:user_id:{user_id}").into()
+}
+
+fn second_factor_lock_key(user_id: i64) -> RedisKey {
+    format!("second_factor_locked:user_id:{user_id}").into()
+}
+
+fn normalize(identifier: &str) -> String {
+    identifier.trim().to_lowercase()
+}
+
+/// 1s, 2s, 4s, ... capped at `MAX_LOGIN_BACKOFF_SECS`
+fn backoff_secs(failures: i64) -> i64 {
+    2i64.saturating_pow(failures.saturating_sub(1).clamp(0, 30) as u32).min(MAX_LOGIN_BACKOFF_SECS)
+}
+
+async fn remaining_secs(redis: &RedisConnectionPool, key: &RedisKey) -> AppResult<i64> {
+    redis.get_ttl(key).await.map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+fn account_locked(seconds: i64) -> AppError {
+    AppError::AccountLockedError(format!(
+        "Too many failed login attempts. The account is locked, try again in {} minute(s)",
+        (seconds + 59) / 60
+    ))
+}
+
+fn backing_off(seconds: i64) -> AppError {
+    AppError::TooManyRequestsError(format!(
+        "Too many failed login attempts, try again in {seconds} second(s)"
+    ))
+}
+
+/// Refuses the attempt while the account is locked or a backoff window is still open.
+pub async fn check_login_allowed(
+    redis: &RedisConnectionPool,
+    subject: &LoginSubject,
+    ip_address: Option<&str>,
+) -> AppResult<()> {
+    let (scope, value) = subject.scope();
+
+    let locked_for = remaining_secs(redis, &lock_key(scope, &value)).await?;
+    if locked_for > 0 {
+        return Err(account_locked(locked_for));
+    }
+
+    let mut keys = vec![backoff_key(scope, &value)];
+    if let Some(ip_address) = ip_address {
+        keys.push(backoff_key("ip", ip_address));
+    }
+    for key in keys {
+        let wait_for = remaining_secs(redis, &key).await?;
+        if wait_for > 0 {
+            return Err(backing_off(wait_for));
+        }
+    }
+    Ok(())
+}
+
+/// Counts a failed attempt and returns the error the caller should answer with.
+pub async fn record_login_failure(
+    redis: &RedisConnectionPool,
+    subject: &LoginSubject,
+    ip_address: Option<&str>,
+) -> AppResult<AppError> {
+    let (scope, value) = subject.scope();
+    let window = EXPIRE_LOGIN_FAILURES_SECS.as_secs() as i64;
+
+    let account_failures = redis
+        .increment_key(&failures_key(scope, &value), window)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    if account_failures >= MAX_LOGIN_FAILURES_PER_USERNAME {
+        let lock_secs = EXPIRE_BLOCKED_EMAIL_SECS.as_secs() as i64;
+        redis
+            .set_key_with_expiry::<String>(&lock_key(scope, &value), "1".to_string(), lock_secs)
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        let _ = redis.delete_key(&failures_key(scope, &value)).await;
+        log::warn!("Login locked for {scope} '{value}' after {account_failures} failures.");
+        return Ok(account_locked(lock_secs));
+    }
+    set_backoff(redis, &backoff_key(scope, &value), account_failures).await?;
+
+    if let Some(ip_address) = ip_address {
+        let ip_failures = redis
+            .increment_key(&failures_key("ip", ip_address), window)
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        // An IP gets a much larger budget since many users can share it
+        if ip_failures >= MAX_LOGIN_FAILURES_PER_IP {
+            set_backoff(redis, &backoff_key("ip", ip_address), ip_failures - MAX_LOGIN_FAILURES_PER_IP + 1)
+                .await?;
+        }
+    }
+
+    Ok(AppError::InvalidCredentialsError("Username or password is not correct".to_string()))
+}
+
+async fn set_backoff(redis: &RedisConnectionPool, key: &RedisKey, failures: i64) -> AppResult<()> {
+    redis
+        .set_key_with_expiry::<String>(key, "1".to_string(), backoff_secs(failures))
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+/// Forgets the failures of the account after a successful password check.
+pub async fn clear_login_failures(redis: &RedisConnectionPool, subject: &LoginSubject) -> AppResult<()> {
+    let (scope, value) = subject.scope();
+    redis
+        .delete_multiple_keys(&[failures_key(scope, &value), backoff_key(scope, &value)])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Lifts a lock early, e.g. after the owner reset the password.
+pub async fn unlock(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    let (scope, value) = LoginSubject::User(user_id).scope();
+    redis
+        .delete_multiple_keys(&[
+            lock_key(scope, &value),
+            failures_key(scope, &value),
+            backoff_key(scope, &value),
+        ])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Refuses second-factor attempts while the user is locked out of them.
+pub async fn check_second_factor_allowed(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    let locked_for = remaining_secs(redis, &second_factor_lock_key(user_id)).await?;
+    if locked_for > 0 {
+        return Err(account_locked(locked_for));
+    }
+    Ok(())
+}
+
+/// Counts a wrong second-factor code and returns the error the caller should answer with.
+pub async fn record_second_factor_failure(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+) -> AppResult<AppError> {
+    let failures = redis
+        .increment_key(
+            &second_factor_failures_key(user_id),
+            EXPIRE_SECOND_FACTOR_FAILURES_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    if failures >= MAX_SECOND_FACTOR_FAILURES {
+        let lock_secs = EXPIRE_BLOCKED_EMAIL_SECS.as_secs() as i64;
+        redis
+            .set_key_with_expiry::<String>(&second_factor_lock_key(user_id), "1".to_string(), lock_secs)
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+        let _ = redis.delete_key(&second_factor_failures_key(user_id)).await;
+        log::warn!("Second factor locked for user {user_id} after {failures} failures.");
+        return Ok(account_locked(lock_secs));
+    }
+    Ok(AppError::BadRequestError("The code is not correct".to_string()))
+}
+
+/// Forgets the wrong codes of the user after a successful second factor.
+pub async fn clear_second_factor_failures(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    redis
+        .delete_key(&second_factor_failures_key(user_id))
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_accounts_share_one_budget_whatever_name_is_used() {
+        assert_eq!(LoginSubject::new(Some(7), "jane"), LoginSubject::new(Some(7), "jane@example.com"));
+        assert_eq!(
+            LoginSubject::new(None, " Ghost@Example.com"),
+            LoginSubject::Unknown("ghost@example.com".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_account_locks_after_too_many_failures_until_unlocked() {
+        let redis = RedisConnectionPool::mock().await;
+        let subject = LoginSubject::User(7);
+        for _ in 1..MAX_LOGIN_FAILURES_PER_USERNAME {
+            let err = record_login_failure(&redis, &subject, None).await.unwrap();
+            assert!(matches!(err, AppError::InvalidCredentialsError(_)));
+        }
+        let err = record_login_failure(&redis, &subject, None).await.unwrap();
+        assert!(matches!(err, AppError::AccountLockedError(_)));
+        assert!(matches!(
+            check_login_allowed(&redis, &subject, None).await,
+            Err(AppError::AccountLockedError(_))
+        ));
+
+        unlock(&redis, 7).await.unwrap();
+        assert!(check_login_allowed(&redis, &subject, None).await.is_ok());
+    }
+}
@@ -1,2 +1,10 @@
+pub mod account_status;
+pub mod authorization_code;
+pub mod external_login;
 pub mod instance;
+pub mod login_guard;
+pub mod passwordless;
 pub mod session;
+pub mod tenant_keys;
+pub mod token_denylist;
+pub mod two_factor;
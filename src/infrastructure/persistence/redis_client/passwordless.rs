@@ -0,0 +1,167 @@
+//! Pending magic links and phone codes. Each one can be redeemed once; how often a
+//! link or code may be sent is counted per destination (email address or phone number).
+
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::errors;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::{DelReply, RedisKey};
+use crate::util::constant::{
+    EXPIRE_MAGIC_LINK_SECS, EXPIRE_PASSWORDLESS_SENDS_SECS, EXPIRE_PHONE_OTP_SECS,
+    MAX_PASSWORDLESS_SENDS_PER_DESTINATION,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Magic link waiting to be opened, keyed by the `jti` of its token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLink {
+    pub user_id: i64,
+    pub device_name: Option<String>,
+}
+
+/// Code texted to a phone number, stored hashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhoneOtp {
+    pub user_id: i64,
+    pub code_hash: String,
+    pub device_name: Option<String>,
+}
+
+fn magic_link_key(jti: &Uuid) -> String {
+    format!("magic_link:jti:{jti}")
+}
+
+fn phone_otp_key(phone_number: &str) -> String {
+    format!("phone_otp:phone:{phone_number}")
+}
+
+pub fn phone_otp_attempts_key(phone_number: &str) -> String {
+    format!("phone_otp:attempts:phone:{phone_number}")
+}
+
+fn sends_key(channel: &str, destination: &str) -> RedisKey {
+    format!("passwordless_sends:{channel}:{destination}").into()
+}
+
+/// Counts one more message to `destination` and refuses it once the hourly budget is spent.
+pub async fn check_send_allowed(
+    redis: &RedisConnectionPool,
+    channel: &str,
+    destination: &str,
+) -> AppResult<()> {
+    let sends = redis
+        .increment_key(
+            &sends_key(channel, &destination.trim().to_lowercase()),
+            EXPIRE_PASSWORDLESS_SENDS_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    if sends > MAX_PASSWORDLESS_SENDS_PER_DESTINATION {
+        return Err(AppError::TooManyRequestsError(
+            "Too many sign-in messages were sent to this destination, try again later".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn store_magic_link(
+    redis: &RedisConnectionPool,
+    jti: &Uuid,
+    link: &MagicLink,
+) -> AppResult<()> {
+    redis
+        .serialize_and_set_key_with_expiry(
+            &magic_link_key(jti).into(),
+            link,
+            EXPIRE_MAGIC_LINK_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+/// Returns the link and removes it, so a second use finds nothing.
+pub async fn take_magic_link(
+    redis: &RedisConnectionPool,
+    jti: &Uuid,
+) -> AppResult<Option<MagicLink>> {
+    take(redis, &magic_link_key(jti).into(), "MagicLink").await
+}
+
+/// Replaces any earlier code for the number and resets its attempt counter.
+pub async fn store_phone_otp(
+    redis: &RedisConnectionPool,
+    phone_number: &str,
+    otp: &PhoneOtp,
+) -> AppResult<()> {
+    redis
+        .delete_key(&phone_otp_attempts_key(phone_number).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    redis
+        .serialize_and_set_key_with_expiry(
+            &phone_otp_key(phone_number).into(),
+            otp,
+            EXPIRE_PHONE_OTP_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+pub async fn find_phone_otp(
+    redis: &RedisConnectionPool,
+    phone_number: &str,
+) -> AppResult<Option<PhoneOtp>> {
+    match redis
+        .get_and_deserialize_key::<PhoneOtp>(&phone_otp_key(phone_number).into(), "PhoneOtp")
+        .await
+    {
+        Ok(otp) => Ok(Some(otp)),
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
+
+/// Same as [`find_phone_otp`], but also uses the code up.
+pub async fn take_phone_otp(
+    redis: &RedisConnectionPool,
+    phone_number: &str,
+) -> AppResult<Option<PhoneOtp>> {
+    let otp = take(redis, &phone_otp_key(phone_number).into(), "PhoneOtp").await?;
+    if otp.is_some() {
+        redis
+            .delete_key(&phone_otp_attempts_key(phone_number).into())
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    }
+    Ok(otp)
+}
+
+pub async fn delete_phone_otp(redis: &RedisConnectionPool, phone_number: &str) -> AppResult<()> {
+    redis
+        .delete_multiple_keys(&[
+            phone_otp_key(phone_number).into(),
+            phone_otp_attempts_key(phone_number).into(),
+        ])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Only the request that actually deletes the key gets the value.
+async fn take<T: DeserializeOwned>(
+    redis: &RedisConnectionPool,
+    key: &RedisKey,
+    type_name: &'static str,
+) -> AppResult<Option<T>> {
+    let value = match redis.get_and_deserialize_key::<T>(key, type_name).await {
+        Ok(value) => value,
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => return Ok(None),
+        Err(err) => return Err(AppError::BadRequestError(err.to_string())),
+    };
+    match redis.delete_key(key).await {
+        Ok(DelReply::KeyDeleted) => Ok(Some(value)),
+        Ok(DelReply::KeyNotDeleted) => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
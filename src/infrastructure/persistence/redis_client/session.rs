@@ -1,27 +1,375 @@
 use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::middleware::client_info::ClientInfo;
+use crate::infrastructure::third_party::redis::errors;
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::util::claim::UserClaims;
+use crate::util::constant::{
+    EXPIRE_IMPERSONATION_SECS, EXPIRE_REFRESH_TOKEN_SECS, EXPIRE_SESSION_ABSOLUTE_SECS,
+    EXPIRE_SESSION_IDLE_SECS,
+};
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use uuid::Uuid;
 
+/// Swaps the live refresh token id of a session only if the presented one is still current,
+/// so two concurrent refreshes with the same token cannot both succeed.
+const ROTATE_REFRESH_TOKEN_SCRIPT: &str = r#"
+local current = redis.call('GET', KEYS[1])
+if current == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+end
+return 0
+"#;
+
+/// One signed-in device. Stored under its `sid`, with an index of sids per user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub session_id: Uuid,
+    pub user_id: i64,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    /// Absolute end of the session, no matter how active it is
+    pub expires_at: NaiveDateTime,
+    /// Admin who opened this session to impersonate the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<i64>,
+}
+
+impl SessionRecord {
+    /// Seconds the record may live from now: the idle timeout, capped by the absolute lifetime.
+    fn remaining_ttl(&self, now: NaiveDateTime) -> i64 {
+        let absolute = (self.expires_at - now).num_seconds();
+        absolute.min(EXPIRE_SESSION_IDLE_SECS.as_secs() as i64)
+    }
+}
+
+pub fn session_key(session_id: &Uuid) -> String {
+    format!("session:session_id:{session_id}")
+}
+
+pub fn user_sessions_key(user_id: i64) -> String {
+    format!("sessions:user_id:{user_id}")
+}
+
+pub fn refresh_token_key(session_id: &Uuid) -> String {
+    format!("refresh_token:session_id:{session_id}")
+}
+
+async fn save_session(redis: &RedisConnectionPool, record: &SessionRecord, ttl: i64) -> AppResult<()> {
+    redis
+        .serialize_and_set_key_with_expiry(&session_key(&record.session_id).into(), record, ttl)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+/// Starts a new session for the user without touching the other devices' sessions.
+pub async fn create_session(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    device_name: Option<String>,
+    client: &ClientInfo,
+) -> AppResult<SessionRecord> {
+    let now = Utc::now().naive_utc();
+    let record = SessionRecord {
+        session_id: Uuid::new_v4(),
+        user_id,
+        device_name,
+        ip_address: client.ip_address.clone(),
+        user_agent: client.user_agent.clone(),
+        created_at: now,
+        last_seen_at: now,
+        expires_at: now + chrono::Duration::seconds(EXPIRE_SESSION_ABSOLUTE_SECS.as_secs() as i64),
+        impersonator_id: None,
+    };
+    insert_session(redis, &record, now).await?;
+    Ok(record)
+}
+
+/// Session of an admin impersonating the user. It ends with the impersonation token and
+/// shows up in the user's session list like any other device. Only built here, the caller
+/// stores it with [`store_new_session`] once the impersonation is on record.
+pub fn impersonation_session(
+    user_id: i64,
+    impersonator_id: i64,
+    client: &ClientInfo,
+) -> SessionRecord {
+    let now = Utc::now().naive_utc();
+    SessionRecord {
+        session_id: Uuid::new_v4(),
+        user_id,
+        device_name: Some(format!("Support (admin {impersonator_id})")),
+        ip_address: client.ip_address.clone(),
+        user_agent: client.user_agent.clone(),
+        created_at: now,
+        last_seen_at: now,
+        expires_at: now + chrono::Duration::seconds(EXPIRE_IMPERSONATION_SECS.as_secs() as i64),
+        impersonator_id: Some(impersonator_id),
+    }
+}
+
+pub async fn store_new_session(redis: &RedisConnectionPool, record: &SessionRecord) -> AppResult<()> {
+    insert_session(redis, record, Utc::now().naive_utc()).await
+}
+
+async fn insert_session(
+    redis: &RedisConnectionPool,
+    record: &SessionRecord,
+    now: NaiveDateTime,
+) -> AppResult<()> {
+    save_session(redis, record, record.remaining_ttl(now)).await?;
+
+    let index_key = user_sessions_key(record.user_id).into();
+    redis
+        .sadd(&index_key, record.session_id.to_string())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    redis
+        .set_expiry(&index_key, EXPIRE_SESSION_ABSOLUTE_SECS.as_secs() as i64)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+pub async fn find_session(
+    redis: &RedisConnectionPool,
+    session_id: &Uuid,
+) -> AppResult<Option<SessionRecord>> {
+    match redis
+        .get_and_deserialize_key::<SessionRecord>(&session_key(session_id).into(), "SessionRecord")
+        .await
+    {
+        Ok(record) => Ok(Some(record)),
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
+
+/// Checks that `claims` belong to a live session of the user and slides its idle expiry.
+/// A missing session means the user logged out, the session was revoked or it expired.
 pub async fn is_valid_session(
     redis: &RedisConnectionPool,
     claims: &UserClaims,
     is_del_session: bool,
 ) -> AppResult<i64> {
-    let session_key = claims.user_id.to_string();
-    let session_id: Option<String> =
-        redis.get_key::<Option<String>>(&session_key.clone().into()).await.unwrap();
-    if claims.sid != Uuid::from_str(session_id.unwrap().as_str())? {
+    let mut record = find_session(redis, &claims.sid)
+        .await?
+        .ok_or_else(|| AppError::InvalidSessionError("Session has expired".to_string()))?;
+    if record.user_id != claims.user_id {
         if is_del_session {
-            redis.delete_key(&session_key.into()).await.unwrap();
+            revoke_session(redis, record.user_id, &record.session_id).await?;
         }
         return Err(AppError::InvalidSessionError("Session is invalid".to_string()));
     }
+
+    let now = Utc::now().naive_utc();
+    let ttl = record.remaining_ttl(now);
+    if ttl <= 0 {
+        revoke_session(redis, record.user_id, &record.session_id).await?;
+        return Err(AppError::InvalidSessionError("Session has expired".to_string()));
+    }
+    record.last_seen_at = now;
+    save_session(redis, &record, ttl).await?;
+
     Ok(claims.user_id)
 }
 
-pub fn generate(user_uuid: &Uuid) -> (String, Uuid) {
-    let session_id = Uuid::new_v4();
-    (user_uuid.to_string(), session_id)
+/// Active sessions of the user. Index entries whose record already expired are dropped.
+pub async fn list_sessions(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+) -> AppResult<Vec<SessionRecord>> {
+    let index_key = user_sessions_key(user_id).into();
+    let session_ids = redis
+        .get_set_members(&index_key)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+
+    let mut sessions = Vec::with_capacity(session_ids.len());
+    let mut stale = Vec::new();
+    for session_id in session_ids {
+        let record = match Uuid::from_str(&session_id) {
+            Ok(sid) => find_session(redis, &sid).await?,
+            Err(_) => None,
+        };
+        match record {
+            Some(record) => sessions.push(record),
+            None => stale.push(session_id),
+        }
+    }
+
+    if !stale.is_empty() {
+        redis
+            .srem(&index_key, stale)
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    }
+
+    sessions.sort_by(|a, b| b.last_seen_at.cmp(&a.last_seen_at));
+    Ok(sessions)
+}
+
+pub async fn revoke_session(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    session_id: &Uuid,
+) -> AppResult<()> {
+    redis
+        .delete_multiple_keys(&[session_key(session_id).into(), refresh_token_key(session_id).into()])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    redis
+        .srem(&user_sessions_key(user_id).into(), session_id.to_string())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Revokes every session of the user except `keep`. Returns how many were revoked.
+pub async fn revoke_all_sessions(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    keep: Option<&Uuid>,
+) -> AppResult<usize> {
+    let session_ids = redis
+        .get_set_members(&user_sessions_key(user_id).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+
+    let mut revoked = 0;
+    for session_id in session_ids.iter().filter_map(|sid| Uuid::from_str(sid).ok()) {
+        if Some(&session_id) == keep {
+            continue;
+        }
+        revoke_session(redis, user_id, &session_id).await?;
+        revoked += 1;
+    }
+    Ok(revoked)
+}
+
+/// Remembers `refresh_jti` as the only refresh token the session will accept.
+pub async fn store_refresh_token(
+    redis: &RedisConnectionPool,
+    session_id: &Uuid,
+    refresh_jti: &Uuid,
+) -> AppResult<()> {
+    redis
+        .set_key_with_expiry::<String>(
+            &refresh_token_key(session_id).into(),
+            refresh_jti.to_string(),
+            EXPIRE_REFRESH_TOKEN_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+/// Id of the refresh token the session currently accepts.
+pub async fn current_refresh_jti(
+    redis: &RedisConnectionPool,
+    session_id: &Uuid,
+) -> AppResult<Option<Uuid>> {
+    Ok(redis
+        .get_key::<Option<String>>(&refresh_token_key(session_id).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?
+        .and_then(|jti| Uuid::from_str(&jti).ok()))
+}
+
+/// Returns `false` when `presented_jti` is no longer the live refresh token of the session,
+/// i.e. the token was already used or the session is gone.
+pub async fn rotate_refresh_token(
+    redis: &RedisConnectionPool,
+    session_id: &Uuid,
+    presented_jti: &Uuid,
+    new_jti: &Uuid,
+) -> AppResult<bool> {
+    let rotated: i64 = redis
+        .evaluate_redis_script(
+            ROTATE_REFRESH_TOKEN_SCRIPT,
+            vec![redis.add_prefix(&refresh_token_key(session_id))],
+            vec![
+                presented_jti.to_string(),
+                new_jti.to_string(),
+                EXPIRE_REFRESH_TOKEN_SECS.as_secs().to_string(),
+            ],
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(rotated == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn record_expiring_at(expires_at: NaiveDateTime) -> SessionRecord {
+        let now = Utc::now().naive_utc();
+        SessionRecord {
+            session_id: Uuid::new_v4(),
+            user_id: 1,
+            device_name: None,
+            ip_address: None,
+            user_agent: None,
+            created_at: now,
+            last_seen_at: now,
+            expires_at,
+            impersonator_id: None,
+        }
+    }
+
+    #[test]
+    fn test_session_ttl_is_the_idle_timeout_capped_by_its_lifetime() {
+        let now = Utc::now().naive_utc();
+        let idle = EXPIRE_SESSION_IDLE_SECS.as_secs() as i64;
+
+        assert_eq!(record_expiring_at(now + Duration::seconds(idle * 10)).remaining_ttl(now), idle);
+        assert_eq!(record_expiring_at(now + Duration::seconds(30)).remaining_ttl(now), 30);
+        assert!(record_expiring_at(now - Duration::seconds(1)).remaining_ttl(now) <= 0);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_session_no_longer_validates_its_claims() {
+        let redis = RedisConnectionPool::mock().await;
+        let record = create_session(&redis, 7, None, &ClientInfo::default()).await.unwrap();
+        let claims = UserClaims::new(EXPIRE_SESSION_IDLE_SECS, &7, &record.session_id);
+        assert_eq!(is_valid_session(&redis, &claims, false).await.unwrap(), 7);
+
+        revoke_session(&redis, 7, &record.session_id).await.unwrap();
+        assert!(matches!(
+            is_valid_session(&redis, &claims, false).await,
+            Err(AppError::InvalidSessionError(_))
+        ));
+        assert!(list_sessions(&redis, 7).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_of_another_user_is_rejected() {
+        let redis = RedisConnectionPool::mock().await;
+        let record = create_session(&redis, 7, None, &ClientInfo::default()).await.unwrap();
+        let claims = UserClaims::new(EXPIRE_SESSION_IDLE_SECS, &8, &record.session_id);
+        assert!(matches!(
+            is_valid_session(&redis, &claims, false).await,
+            Err(AppError::InvalidSessionError(_))
+        ));
+        // The owner keeps the session
+        assert_eq!(list_sessions(&redis, 7).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_session_exists_only_once_stored() {
+        let redis = RedisConnectionPool::mock().await;
+        let record = impersonation_session(7, 1, &ClientInfo::default());
+        let claims = UserClaims::new(EXPIRE_IMPERSONATION_SECS, &7, &record.session_id).impersonated_by(1);
+        assert!(is_valid_session(&redis, &claims, false).await.is_err());
+
+        store_new_session(&redis, &record).await.unwrap();
+        assert_eq!(is_valid_session(&redis, &claims, false).await.unwrap(), 7);
+        let sessions = list_sessions(&redis, 7).await.unwrap();
+        assert_eq!(sessions[0].impersonator_id, Some(1));
+    }
 }
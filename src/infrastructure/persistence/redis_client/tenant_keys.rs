@@ -0,0 +1,62 @@
+//! Moves the Redis state written before tenants existed under the default tenant.
+//!
+//! Keys used to be `[key_prefix:]key`; since tenants they are `[key_prefix:]tenant:key`.
+//! Without this, a deploy would log everyone out, forget lockouts and accept revoked
+//! access tokens again until they expire. It runs once at startup, before the server
+//! accepts requests; the marker key keeps later starts from touching the keys of a
+//! tenant whose id happens to equal one of the namespaces below.
+//!
+//! Rollout: deploy as usual, the first instance to start moves the keys. A key an old
+//! instance writes during a rolling deploy stays under the legacy name and is lost, so
+//! stop the old instances first when that matters. Delete `default:migration:tenant_keys`
+//! to run it again. Caches are not moved, they are rebuilt from the database.
+
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::infrastructure::third_party::redis::types::SetnxReply;
+
+const MIGRATION_MARKER_KEY: &str = "migration:tenant_keys";
+
+/// Every namespace holding state rather than cache
+const LEGACY_NAMESPACES: [&str; 19] = [
+    "session",
+    "sessions",
+    "refresh_token",
+    "revoked",
+    "blocked",
+    "login_failures",
+    "login_backoff",
+    "login_locked",
+    "login_challenge",
+    "second_factor_failures",
+    "second_factor_locked",
+    "totp",
+    "forget_password",
+    "verify_email",
+    "phone_otp",
+    "magic_link",
+    "passwordless_sends",
+    "oauth_code",
+    "external_login",
+];
+
+/// Returns how many keys were moved, 0 when another start already did it.
+pub async fn migrate_legacy_keys(redis: &RedisConnectionPool) -> AppResult<usize> {
+    let marker = redis
+        .set_key_if_not_exists(&MIGRATION_MARKER_KEY.into(), "1")
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    if matches!(marker, SetnxReply::KeyNotSet) {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for namespace in LEGACY_NAMESPACES {
+        moved += redis
+            .move_legacy_keys(&format!("{namespace}:*").into())
+            .await
+            .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    }
+    log::info!("Moved {moved} Redis keys under the default tenant");
+    Ok(moved)
+}
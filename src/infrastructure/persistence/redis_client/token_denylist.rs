@@ -0,0 +1,27 @@
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use chrono::Utc;
+use uuid::Uuid;
+
+/// Revoked token ids are kept only until the token would have expired anyway.
+fn revoked_jti_key(jti: &Uuid) -> String {
+    format!("revoked:jti:{jti}")
+}
+
+pub async fn revoke_jti(redis: &RedisConnectionPool, jti: &Uuid, exp: i64) -> AppResult<()> {
+    let ttl = exp - Utc::now().timestamp();
+    if ttl <= 0 {
+        return Ok(());
+    }
+    redis
+        .set_key_with_expiry::<String>(&revoked_jti_key(jti).into(), "1".to_string(), ttl)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+pub async fn is_revoked(redis: &RedisConnectionPool, jti: &Uuid) -> AppResult<bool> {
+    redis
+        .exists::<String>(&revoked_jti_key(jti).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
@@ -0,0 +1,151 @@
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::third_party::redis::errors;
+use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
+use crate::util::claim::Authentication;
+use crate::util::constant::{EXPIRE_LOGIN_CHALLENGE_SECS, EXPIRE_TOTP_ENROLLMENT_SECS};
+use crate::util::{random, totp};
+use serde::{Deserialize, Serialize};
+
+/// Password step of a login that still waits for the second factor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginChallenge {
+    pub user_id: i64,
+    pub device_name: Option<String>,
+    /// The first factor, completed by the second one
+    #[serde(default)]
+    pub authentication: Authentication,
+}
+
+fn login_challenge_key(token: &str) -> String {
+    format!("login_challenge:token:{token}")
+}
+
+pub fn login_challenge_attempts_key(token: &str) -> String {
+    format!("login_challenge:attempts:token:{token}")
+}
+
+fn totp_enrollment_key(user_id: i64) -> String {
+    format!("totp:enrollment:user_id:{user_id}")
+}
+
+fn totp_last_step_key(user_id: i64) -> String {
+    format!("totp:last_step:user_id:{user_id}")
+}
+
+/// Stores the challenge and returns the opaque token the client exchanges later.
+pub async fn create_login_challenge(
+    redis: &RedisConnectionPool,
+    challenge: &LoginChallenge,
+) -> AppResult<String> {
+    let token = random::generate_random_string(48);
+    redis
+        .serialize_and_set_key_with_expiry(
+            &login_challenge_key(&token).into(),
+            challenge,
+            EXPIRE_LOGIN_CHALLENGE_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(token)
+}
+
+pub async fn find_login_challenge(
+    redis: &RedisConnectionPool,
+    token: &str,
+) -> AppResult<Option<LoginChallenge>> {
+    match redis
+        .get_and_deserialize_key::<LoginChallenge>(&login_challenge_key(token).into(), "LoginChallenge")
+        .await
+    {
+        Ok(challenge) => Ok(Some(challenge)),
+        Err(err) if err.current_context() == &errors::RedisError::NotFound => Ok(None),
+        Err(err) => Err(AppError::BadRequestError(err.to_string())),
+    }
+}
+
+pub async fn delete_login_challenge(redis: &RedisConnectionPool, token: &str) -> AppResult<()> {
+    redis
+        .delete_multiple_keys(&[
+            login_challenge_key(token).into(),
+            login_challenge_attempts_key(token).into(),
+        ])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Keeps a freshly generated secret until the user confirms it with a first code.
+pub async fn store_pending_totp_secret(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    secret: &str,
+) -> AppResult<()> {
+    redis
+        .set_key_with_expiry::<String>(
+            &totp_enrollment_key(user_id).into(),
+            secret.to_string(),
+            EXPIRE_TOTP_ENROLLMENT_SECS.as_secs() as i64,
+        )
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+pub async fn find_pending_totp_secret(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+) -> AppResult<Option<String>> {
+    redis
+        .get_key::<Option<String>>(&totp_enrollment_key(user_id).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))
+}
+
+pub async fn delete_pending_totp_secret(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    redis
+        .delete_key(&totp_enrollment_key(user_id).into())
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
+
+/// Verifies a TOTP code and remembers its time step, so a code that was already
+/// accepted once cannot be replayed within its validity window.
+pub async fn accept_totp_code(
+    redis: &RedisConnectionPool,
+    user_id: i64,
+    secret: &str,
+    code: &str,
+) -> AppResult<bool> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let Some(step) = totp::verify(secret, code, now) else {
+        return Ok(false);
+    };
+
+    let key = totp_last_step_key(user_id).into();
+    let last_step = redis
+        .get_key::<Option<String>>(&key)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?
+        .and_then(|value| value.parse::<u64>().ok());
+    if last_step.is_some_and(|last_step| step <= last_step) {
+        return Ok(false);
+    }
+
+    let window = (totp::TOTP_PERIOD_SECS * (2 * totp::TOTP_ALLOWED_SKEW + 1)) as i64;
+    redis
+        .set_key_with_expiry::<String>(&key, step.to_string(), window)
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(true)
+}
+
+pub async fn forget_totp_state(redis: &RedisConnectionPool, user_id: i64) -> AppResult<()> {
+    redis
+        .delete_multiple_keys(&[
+            totp_enrollment_key(user_id).into(),
+            totp_last_step_key(user_id).into(),
+        ])
+        .await
+        .map_err(|err| AppError::BadRequestError(err.to_string()))?;
+    Ok(())
+}
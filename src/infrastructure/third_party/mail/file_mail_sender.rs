@@ -0,0 +1,62 @@
+use super::{MailMessage, MailSender};
+use crate::core::error::AppResult;
+use crate::util::file::store_file;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Writes every message as an `.eml` file into an outbox directory instead of sending it.
+/// Meant for local development and tests.
+pub struct FileMailSender {
+    pub from_address: String,
+    pub outbox_dir: PathBuf,
+}
+
+impl FileMailSender {
+    pub fn new(from_address: String, outbox_dir: PathBuf) -> Self {
+        Self { from_address, outbox_dir }
+    }
+}
+
+#[async_trait]
+impl MailSender for FileMailSender {
+    async fn send(&self, message: MailMessage) -> AppResult<()> {
+        let file_path = self.outbox_dir.join(format!(
+            "{}_{}.eml",
+            Utc::now().format("%Y%m%d%H%M%S"),
+            Uuid::new_v4()
+        ));
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from_address, message.to, message.subject, message.body
+        );
+        store_file(&file_path, content.as_bytes()).await?;
+        log::info!("Mail to {} written to {}", message.to, file_path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_message_is_written_to_the_outbox() {
+        let outbox_dir = std::env::temp_dir().join(format!("mail_outbox_{}", Uuid::new_v4()));
+        let sender = FileMailSender::new("no-reply@june18.test".to_string(), outbox_dir.clone());
+        let message = MailMessage {
+            to: "user@june18.test".to_string(),
+            subject: "Reset your password".to_string(),
+            body: "Your code is 12345".to_string(),
+        };
+        sender.send(message).await.unwrap();
+
+        let mut files = std::fs::read_dir(&outbox_dir).unwrap();
+        let content = std::fs::read_to_string(files.next().unwrap().unwrap().path()).unwrap();
+        assert!(content.starts_with("From: no-reply@june18.test\r\nTo: user@june18.test\r\n"));
+        assert!(content.contains("Subject: Reset your password\r\n"));
+        assert!(content.ends_with("\r\n\r\nYour code is 12345\r\n"));
+        std::fs::remove_dir_all(outbox_dir).unwrap();
+    }
+}
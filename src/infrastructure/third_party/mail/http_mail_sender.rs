@@ -0,0 +1,40 @@
+use super::{MailMessage, MailSender};
+use crate::core::client::http::HttpClient;
+use crate::core::error::{AppError, AppResult};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Hands every message to a mail provider's HTTP API.
+pub struct HttpMailSender {
+    pub http: HttpClient,
+    pub from_address: String,
+    pub api_url: String,
+    pub api_key: String,
+}
+
+impl HttpMailSender {
+    pub fn new(http: HttpClient, from_address: String, api_url: String, api_key: String) -> Self {
+        Self { http, from_address, api_url, api_key }
+    }
+}
+
+#[async_trait]
+impl MailSender for HttpMailSender {
+    async fn send(&self, message: MailMessage) -> AppResult<()> {
+        self.http
+            .post(&self.api_url)
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "from": self.from_address,
+                "to": message.to,
+                "subject": message.subject,
+                "text": message.body,
+            }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| AppError::UnknownError(anyhow::anyhow!("Mail delivery failed: {err}")))?;
+        log::info!("Mail to {} handed to the provider", message.to);
+        Ok(())
+    }
+}
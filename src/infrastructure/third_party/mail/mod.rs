@@ -0,0 +1,45 @@
+pub mod file_mail_sender;
+pub mod http_mail_sender;
+
+use crate::core::client::http::HttpClient;
+use crate::core::configure::app::Profile;
+use crate::core::configure::delivery::{self, DeliveryTransport};
+use crate::core::configure::mail::MailConfig;
+use crate::core::error::AppResult;
+use crate::util::dir::get_project_root;
+use async_trait::async_trait;
+use file_mail_sender::FileMailSender;
+use http_mail_sender::HttpMailSender;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers transactional emails (password reset, verification codes, ...).
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, message: MailMessage) -> AppResult<()>;
+}
+
+/// The sender configured for `profile`; fails when there is none fit for it.
+pub fn build_mail_sender(
+    profile: Profile,
+    config: &MailConfig,
+    http: HttpClient,
+) -> AppResult<Arc<dyn MailSender>> {
+    Ok(match delivery::select_transport(profile, "mail", config.transport)? {
+        DeliveryTransport::File => Arc::new(FileMailSender::new(
+            config.from_address.clone(),
+            get_project_root()?.join(&config.outbox_dir),
+        )),
+        DeliveryTransport::Http => {
+            let (api_url, api_key) = delivery::http_endpoint("mail", &config.api_url, &config.api_key)?;
+            Arc::new(HttpMailSender::new(http, config.from_address.clone(), api_url, api_key))
+        },
+    })
+}
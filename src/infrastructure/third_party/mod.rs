@@ -1,2 +1,5 @@
+pub mod mail;
+pub mod oidc;
 pub mod redis;
+pub mod sms;
 pub mod token;
@@ -0,0 +1,326 @@
+use crate::core::configure::auth::IdentityProviderConfig;
+use crate::core::error::{AppError, AppResult};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+/// The part of an OpenID provider's discovery document the login flow needs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Who the provider says signed in, taken from a validated id_token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalIdentity {
+    #[serde(rename = "sub")]
+    pub subject: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    nonce: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderTokenResponse {
+    id_token: String,
+}
+
+/// Providers may publish other key types next to RSA ones, so every field is optional.
+#[derive(Debug, Deserialize)]
+struct ProviderJwk {
+    kid: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<ProviderJwk>,
+}
+
+/// Relying-party side of the OpenID Connect authorization code flow, for any provider
+/// that publishes a discovery document.
+#[derive(Clone)]
+pub struct OidcRelyingParty {
+    http: reqwest::Client,
+}
+
+fn provider_error(provider: &IdentityProviderConfig, detail: impl std::fmt::Display) -> AppError {
+    AppError::UnauthorizedError(format!("Sign-in with {} failed: {detail}", provider.name))
+}
+
+impl OidcRelyingParty {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+
+    pub async fn discover(&self, provider: &IdentityProviderConfig) -> AppResult<ProviderMetadata> {
+        let issuer = provider.issuer.trim_end_matches('/');
+        let metadata: ProviderMetadata = self
+            .http
+            .get(format!("{issuer}/.well-known/openid-configuration"))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| provider_error(provider, err))?
+            .json()
+            .await
+            .map_err(|err| provider_error(provider, err))?;
+        // OpenID Connect Discovery 4.3: the document must be about the issuer we asked for
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(provider_error(provider, "issuer mismatch in discovery document"));
+        }
+        Ok(metadata)
+    }
+
+    pub fn authorization_url(
+        &self,
+        metadata: &ProviderMetadata,
+        provider: &IdentityProviderConfig,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> String {
+        let query = serde_urlencoded::to_string([
+            ("response_type", "code"),
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", provider.redirect_uri.as_str()),
+            ("scope", provider.scope.as_str()),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .unwrap_or_default();
+        let separator = if metadata.authorization_endpoint.contains('?') { '&' } else { '?' };
+        format!("{}{separator}{query}", metadata.authorization_endpoint)
+    }
+
+    /// Redeems the code and returns the identity from the id_token, once its signature,
+    /// issuer, audience, expiry and `nonce` have been checked.
+    pub async fn exchange_code(
+        &self,
+        metadata: &ProviderMetadata,
+        provider: &IdentityProviderConfig,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> AppResult<ExternalIdentity> {
+        let tokens: ProviderTokenResponse = self
+            .http
+            .post(&metadata.token_endpoint)
+            .basic_auth(&provider.client_id, Some(&provider.client_secret))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", provider.redirect_uri.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| provider_error(provider, err))?
+            .json()
+            .await
+            .map_err(|err| provider_error(provider, err))?;
+
+        self.validate_id_token(metadata, provider, &tokens.id_token, nonce).await
+    }
+
+    async fn validate_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        provider: &IdentityProviderConfig,
+        id_token: &str,
+        nonce: &str,
+    ) -> AppResult<ExternalIdentity> {
+        let header = jsonwebtoken::decode_header(id_token).map_err(|err| provider_error(provider, err))?;
+        if header.alg != Algorithm::RS256 {
+            return Err(provider_error(provider, "id_token must be signed with RS256"));
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| provider_error(provider, err))?
+            .json()
+            .await
+            .map_err(|err| provider_error(provider, err))?;
+        let rsa_keys: Vec<_> = jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| Some((jwk.kid.as_deref(), jwk.n.as_deref()?, jwk.e.as_deref()?)))
+            .collect();
+        let (_, n, e) = match header.kid.as_deref() {
+            Some(kid) => rsa_keys.iter().find(|(jwk_kid, _, _)| *jwk_kid == Some(kid)),
+            None if rsa_keys.len() == 1 => rsa_keys.first(),
+            None => None,
+        }
+        .ok_or_else(|| provider_error(provider, "id_token signed with an unknown key"))?;
+        let key = DecodingKey::from_rsa_components(n, e).map_err(|err| provider_error(provider, err))?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[&provider.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+        let identity = jsonwebtoken::decode::<ExternalIdentity>(id_token, &key, &validation)
+            .map_err(|err| provider_error(provider, err))?
+            .claims;
+
+        // Ties the id_token to the login this browser started
+        if identity.nonce.as_deref() != Some(nonce) {
+            return Err(provider_error(provider, "nonce mismatch"));
+        }
+        Ok(identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::key_ring::KeyRing;
+    use axum::extract::State;
+    use axum::routing::{get, post};
+    use axum::{Form, Json, Router};
+    use jsonwebtoken::Header;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn read_pem(name: &str) -> String {
+        std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("static/secret_key").join(name),
+        )
+        .unwrap()
+    }
+
+    /// Local IdP that answers every code with an id_token built from `claims`.
+    struct MockIdp {
+        issuer: String,
+        keys: KeyRing,
+        claims: Value,
+    }
+
+    async fn discovery(State(idp): State<Arc<MockIdp>>) -> Json<Value> {
+        Json(json!({
+            "issuer": idp.issuer,
+            "authorization_endpoint": format!("{}/authorize", idp.issuer),
+            "token_endpoint": format!("{}/token", idp.issuer),
+            "jwks_uri": format!("{}/jwks", idp.issuer),
+        }))
+    }
+
+    async fn jwks(State(idp): State<Arc<MockIdp>>) -> Json<Value> {
+        Json(json!({ "keys": idp.keys.jwks() }))
+    }
+
+    async fn token(
+        State(idp): State<Arc<MockIdp>>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Json<Value> {
+        assert_eq!(form.get("grant_type").map(String::as_str), Some("authorization_code"));
+        let header = Header { kid: Some(idp.keys.active_kid().to_string()), ..Header::new(Algorithm::RS256) };
+        let id_token = jsonwebtoken::encode(&header, &idp.claims, idp.keys.encoding_key()).unwrap();
+        Json(json!({ "access_token": "opaque", "token_type": "Bearer", "id_token": id_token }))
+    }
+
+    async fn start_mock_idp(claims: impl FnOnce(&str) -> Value) -> IdentityProviderConfig {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let idp = Arc::new(MockIdp {
+            claims: claims(&issuer),
+            issuer: issuer.clone(),
+            keys: KeyRing::from_pems(
+                "mock",
+                &read_pem("private_access_rsa_key.pem"),
+                &read_pem("public_access_rsa_key.pem"),
+                &[],
+            )
+            .unwrap(),
+        });
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(idp);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        IdentityProviderConfig {
+            name: "mock".to_string(),
+            issuer,
+            client_id: "june18".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "http://localhost/callback".to_string(),
+            scope: "openid email profile".to_string(),
+        }
+    }
+
+    fn id_token_claims(issuer: &str, audience: &str, nonce: &str) -> Value {
+        let now = chrono::Utc::now().timestamp();
+        json!({
+            "iss": issuer,
+            "sub": "248289761001",
+            "aud": audience,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": nonce,
+            "email": "jane@corp.example",
+            "email_verified": true,
+            "preferred_username": "jane",
+        })
+    }
+
+    async fn sign_in(provider: &IdentityProviderConfig, nonce: &str) -> AppResult<ExternalIdentity> {
+        let rp = OidcRelyingParty::new(reqwest::Client::new());
+        let metadata = rp.discover(provider).await?;
+        rp.exchange_code(&metadata, provider, "code", "verifier", nonce).await
+    }
+
+    #[tokio::test]
+    async fn test_valid_id_token_yields_identity() {
+        let provider = start_mock_idp(|issuer| id_token_claims(issuer, "june18", "n-1")).await;
+        let identity = sign_in(&provider, "n-1").await.unwrap();
+        assert_eq!(identity.subject, "248289761001");
+        assert_eq!(identity.email.as_deref(), Some("jane@corp.example"));
+        assert!(identity.email_verified);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_mismatch_is_rejected() {
+        let provider = start_mock_idp(|issuer| id_token_claims(issuer, "june18", "n-1")).await;
+        assert!(sign_in(&provider, "other").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_for_another_client_is_rejected() {
+        let provider = start_mock_idp(|issuer| id_token_claims(issuer, "someone-else", "n-1")).await;
+        assert!(sign_in(&provider, "n-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_from_another_issuer_is_rejected() {
+        let provider = start_mock_idp(|_| id_token_claims("https://evil.example", "june18", "n-1")).await;
+        assert!(sign_in(&provider, "n-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorization_url_carries_state_nonce_and_pkce() {
+        let provider = start_mock_idp(|issuer| id_token_claims(issuer, "june18", "n-1")).await;
+        let rp = OidcRelyingParty::new(reqwest::Client::new());
+        let metadata = rp.discover(&provider).await.unwrap();
+        let url = rp.authorization_url(&metadata, &provider, "s-1", "n-1", "challenge");
+        assert!(url.starts_with(&format!("{}/authorize?", provider.issuer)));
+        for param in ["state=s-1", "nonce=n-1", "code_challenge=challenge", "code_challenge_method=S256"] {
+            assert!(url.contains(param), "{url} lacks {param}");
+        }
+    }
+}
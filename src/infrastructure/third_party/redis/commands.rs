@@ -10,6 +10,7 @@ use super::{
     errors,
     types::{DelReply, HsetnxReply, MsetnxReply, RedisEntryId, RedisKey, SaddReply, SetnxReply},
 };
+use crate::core::tenant;
 use crate::infrastructure::third_party::redis::errors::CustomResult;
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use crate::util::ext_trait::{AsyncExt, ByteSliceExt, Encode, StringExt};
@@ -26,7 +27,18 @@ use fred::{
 use futures::StreamExt;
 
 impl RedisConnectionPool {
+    /// Keys of one tenant never collide with another's: `[key_prefix:]tenant:key`.
     pub fn add_prefix(&self, key: &str) -> String {
+        let tenant = tenant::current_tenant();
+        if self.key_prefix.is_empty() {
+            format!("{tenant}:{key}")
+        } else {
+            format!("{}:{tenant}:{key}", self.key_prefix)
+        }
+    }
+
+    /// Format of keys written before tenants existed: `[key_prefix:]key`.
+    pub fn add_legacy_prefix(&self, key: &str) -> String {
         if self.key_prefix.is_empty() {
             key.to_string()
         } else {
@@ -34,6 +46,50 @@ impl RedisConnectionPool {
         }
     }
 
+    /// Moves keys written before tenants existed and matching `pattern` under the current
+    /// tenant, keeping their TTL. A key the tenant already has is left alone.
+    /// Returns how many keys were moved.
+    pub async fn move_legacy_keys(&self, pattern: &RedisKey) -> CustomResult<usize, errors::RedisError> {
+        let legacy_keys: Vec<String> = self
+            .pool
+            .next()
+            .scan(pattern.tenant_unaware_key(self), None, None)
+            .filter_map(|value| async move {
+                match value {
+                    Ok(mut v) => {
+                        let v = v.take_results()?;
+
+                        let v: Vec<String> =
+                            v.into_iter().filter_map(|val| val.into_string()).collect();
+                        Some(futures::stream::iter(v))
+                    },
+                    Err(_err) => None,
+                }
+            })
+            .flatten()
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut moved = 0;
+        for legacy_key in legacy_keys {
+            let key = match self.key_prefix.is_empty() {
+                true => legacy_key.as_str(),
+                false => legacy_key
+                    .strip_prefix(&format!("{}:", self.key_prefix))
+                    .unwrap_or(&legacy_key),
+            };
+            let renamed: bool = self
+                .pool
+                .renamenx(&legacy_key, self.add_prefix(key))
+                .await
+                .change_context(errors::RedisError::RenameFailed)?;
+            if renamed {
+                moved += 1;
+            }
+        }
+        Ok(moved)
+    }
+
     pub async fn set_key<V>(&self, key: &RedisKey, value: V) -> CustomResult<(), errors::RedisError>
     where
         V: TryInto<RedisValue> + Debug + Send + Sync,
@@ -162,6 +218,10 @@ impl RedisConnectionPool {
 
                 #[cfg(feature = "multitenancy_fallback")]
                 {
+                    // Keys written before tenants existed all belong to the default tenant
+                    if tenant::current_tenant() != tenant::DEFAULT_TENANT {
+                        return Err(_err);
+                    }
                     self.pool
                         .get(key.tenant_unaware_key(self))
                         .await
@@ -190,6 +250,10 @@ impl RedisConnectionPool {
 
                 #[cfg(feature = "multitenancy_fallback")]
                 {
+                    // Keys written before tenants existed all belong to the default tenant
+                    if tenant::current_tenant() != tenant::DEFAULT_TENANT {
+                        return Err(_err);
+                    }
                     self.pool
                         .exists(key.tenant_unaware_key(self))
                         .await
@@ -232,6 +296,10 @@ impl RedisConnectionPool {
 
                 #[cfg(feature = "multitenancy_fallback")]
                 {
+                    // Keys written before tenants existed all belong to the default tenant
+                    if tenant::current_tenant() != tenant::DEFAULT_TENANT {
+                        return Err(_err);
+                    }
                     self.pool
                         .del(key.tenant_unaware_key(self))
                         .await
@@ -270,6 +338,38 @@ impl RedisConnectionPool {
             .change_context(errors::RedisError::SetExFailed)
     }
 
+    /// Increments a counter, starting its expiry window on the first increment.
+    pub async fn increment_key(
+        &self,
+        key: &RedisKey,
+        seconds: i64,
+    ) -> CustomResult<i64, errors::RedisError> {
+        let value: i64 = self
+            .pool
+            .incr(key.tenant_aware_key(self))
+            .await
+            .change_context(errors::RedisError::IncrementFailed)?;
+        if value == 1 {
+            self.set_expiry(key, seconds).await?;
+        }
+        Ok(value)
+    }
+
+    pub async fn set_key_if_not_exists<V>(
+        &self,
+        key: &RedisKey,
+        value: V,
+    ) -> CustomResult<SetnxReply, errors::RedisError>
+    where
+        V: TryInto<RedisValue> + Debug + Send + Sync,
+        V::Error: Into<fred::error::RedisError> + Send + Sync,
+    {
+        self.pool
+            .set(key.tenant_aware_key(self), value, None, Some(SetOptions::NX), false)
+            .await
+            .change_context(errors::RedisError::SetFailed)
+    }
+
     pub async fn set_key_if_not_exists_with_expiry<V>(
         &self,
         key: &RedisKey,
@@ -303,6 +403,14 @@ impl RedisConnectionPool {
             .change_context(errors::RedisError::SetExpiryFailed)
     }
 
+    /// Remaining time to live in seconds. Negative when the key is missing or has no expiry.
+    pub async fn get_ttl(&self, key: &RedisKey) -> CustomResult<i64, errors::RedisError> {
+        self.pool
+            .ttl(key.tenant_aware_key(self))
+            .await
+            .change_context(errors::RedisError::GetFailed)
+    }
+
     pub async fn set_expire_at(
         &self,
         key: &RedisKey,
@@ -507,6 +615,10 @@ impl RedisConnectionPool {
             Err(_err) => {
                 #[cfg(feature = "multitenancy_fallback")]
                 {
+                    // Keys written before tenants existed all belong to the default tenant
+                    if tenant::current_tenant() != tenant::DEFAULT_TENANT {
+                        return Err(_err);
+                    }
                     self.pool
                         .hget(key.tenant_unaware_key(self), field)
                         .await
@@ -535,6 +647,10 @@ impl RedisConnectionPool {
             Err(_err) => {
                 #[cfg(feature = "multitenancy_fallback")]
                 {
+                    // Keys written before tenants existed all belong to the default tenant
+                    if tenant::current_tenant() != tenant::DEFAULT_TENANT {
+                        return Err(_err);
+                    }
                     self.pool
                         .hgetall(key.tenant_unaware_key(self))
                         .await
@@ -584,6 +700,27 @@ impl RedisConnectionPool {
             .change_context(errors::RedisError::SetAddMembersFailed)
     }
 
+    pub async fn srem<V>(&self, key: &RedisKey, members: V) -> CustomResult<usize, errors::RedisError>
+    where
+        V: TryInto<MultipleValues> + Debug + Send,
+        V::Error: Into<fred::error::RedisError> + Send,
+    {
+        self.pool
+            .srem(key.tenant_aware_key(self), members)
+            .await
+            .change_context(errors::RedisError::SetRemoveMembersFailed)
+    }
+
+    pub async fn get_set_members(
+        &self,
+        key: &RedisKey,
+    ) -> CustomResult<Vec<String>, errors::RedisError> {
+        self.pool
+            .smembers(key.tenant_aware_key(self))
+            .await
+            .change_context(errors::RedisError::GetSetMembersFailed)
+    }
+
     pub async fn stream_append_entry<F>(
         &self,
         stream: &RedisKey,
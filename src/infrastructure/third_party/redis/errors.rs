@@ -16,6 +16,8 @@ pub enum RedisError {
     GetFailed,
     #[error("Failed to delete key value in Redis")]
     DeleteFailed,
+    #[error("Failed to rename key in Redis")]
+    RenameFailed,
     #[error("Failed to append entry to Redis stream")]
     StreamAppendFailed,
     #[error("Failed to read entries from Redis stream")]
@@ -50,6 +52,10 @@ pub enum RedisError {
     SetHashFieldFailed,
     #[error("Failed to add members to set in Redis")]
     SetAddMembersFailed,
+    #[error("Failed to remove members from set in Redis")]
+    SetRemoveMembersFailed,
+    #[error("Failed to get set members in Redis")]
+    GetSetMembersFailed,
     #[error("Failed to get hash field in Redis")]
     GetHashFieldFailed,
     #[error("The requested value was not found in Redis")]
@@ -76,6 +82,8 @@ pub enum RedisError {
     PopListElementsFailed,
     #[error("Failed to increment hash field in Redis")]
     IncrementHashFieldFailed,
+    #[error("Failed to increment key in Redis")]
+    IncrementFailed,
 }
 
 pub type CustomResult<T, E> = error_stack::Result<T, E>;
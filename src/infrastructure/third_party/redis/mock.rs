@@ -0,0 +1,217 @@
+use crate::infrastructure::third_party::redis::lib::{RedisConfig, RedisConnectionPool};
+use fred::error::{RedisError, RedisErrorKind};
+use fred::interfaces::ClientLike;
+use fred::mocks::{MockCommand, Mocks};
+use fred::types::RedisValue;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{atomic, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+enum Value {
+    Str(RedisValue),
+    Set(BTreeSet<String>),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+/// In-memory stand-in for Redis covering the string, set and expiry commands
+/// used by sessions, login throttling and token revocation.
+#[derive(Debug, Default)]
+pub struct MemoryRedis {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+fn invalid(message: &'static str) -> RedisError {
+    RedisError::new(RedisErrorKind::InvalidArgument, message)
+}
+
+fn string_arg(args: &[RedisValue], index: usize) -> Result<String, RedisError> {
+    args.get(index).and_then(RedisValue::as_string).ok_or_else(|| invalid("Missing argument."))
+}
+
+fn int_arg(args: &[RedisValue], index: usize) -> Result<i64, RedisError> {
+    args.get(index).and_then(RedisValue::as_i64).ok_or_else(|| invalid("Missing integer."))
+}
+
+fn after(seconds: i64) -> Option<Instant> {
+    Some(Instant::now() + Duration::from_secs(seconds.max(0) as u64))
+}
+
+impl MemoryRedis {
+    fn live(entries: &mut HashMap<String, Entry>, key: &str) -> bool {
+        match entries.get(key) {
+            Some(entry) if entry.expires_at.is_some_and(|at| at <= Instant::now()) => {
+                entries.remove(key);
+                false
+            },
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn set(entries: &mut HashMap<String, Entry>, args: &[RedisValue]) -> Result<RedisValue, RedisError> {
+        let key = string_arg(args, 0)?;
+        let value = args.get(1).cloned().ok_or_else(|| invalid("Missing value."))?;
+        let mut expires_at = None;
+        let mut only_new = false;
+        let mut index = 2;
+        while index < args.len() {
+            match string_arg(args, index)?.to_uppercase().as_str() {
+                "EX" => {
+                    expires_at = after(int_arg(args, index + 1)?);
+                    index += 1;
+                },
+                "NX" => only_new = true,
+                _ => return Err(invalid("Unsupported SET option.")),
+            }
+            index += 1;
+        }
+        if only_new && Self::live(entries, &key) {
+            return Ok(RedisValue::Null);
+        }
+        entries.insert(key, Entry { value: Value::Str(value), expires_at });
+        Ok(RedisValue::new_ok())
+    }
+
+    fn execute(&self, command: MockCommand) -> Result<RedisValue, RedisError> {
+        let mut entries = self.entries.lock().expect("mock redis lock");
+        let args = command.args;
+        match &*command.cmd {
+            "SET" => Self::set(&mut entries, &args),
+            "GET" => {
+                let key = string_arg(&args, 0)?;
+                if !Self::live(&mut entries, &key) {
+                    return Ok(RedisValue::Null);
+                }
+                match &entries[&key].value {
+                    Value::Str(value) => Ok(value.clone()),
+                    Value::Set(_) => Err(invalid("WRONGTYPE")),
+                }
+            },
+            "DEL" => {
+                let mut removed = 0;
+                for index in 0..args.len() {
+                    let key = string_arg(&args, index)?;
+                    if Self::live(&mut entries, &key) {
+                        entries.remove(&key);
+                        removed += 1;
+                    }
+                }
+                Ok(removed.into())
+            },
+            "EXISTS" => {
+                let mut found = 0;
+                for index in 0..args.len() {
+                    if Self::live(&mut entries, &string_arg(&args, index)?) {
+                        found += 1;
+                    }
+                }
+                Ok(found.into())
+            },
+            "INCR" => {
+                let key = string_arg(&args, 0)?;
+                let current = match Self::live(&mut entries, &key) {
+                    true => match &entries[&key].value {
+                        Value::Str(value) => value.as_i64().ok_or_else(|| invalid("Not an integer."))?,
+                        Value::Set(_) => return Err(invalid("WRONGTYPE")),
+                    },
+                    false => 0,
+                };
+                let expires_at = entries.get(&key).and_then(|entry| entry.expires_at);
+                entries.insert(key, Entry { value: Value::Str((current + 1).into()), expires_at });
+                Ok((current + 1).into())
+            },
+            "EXPIRE" => {
+                let key = string_arg(&args, 0)?;
+                if !Self::live(&mut entries, &key) {
+                    return Ok(0.into());
+                }
+                let seconds = int_arg(&args, 1)?;
+                entries.get_mut(&key).expect("live key").expires_at = after(seconds);
+                Ok(1.into())
+            },
+            "TTL" => {
+                let key = string_arg(&args, 0)?;
+                if !Self::live(&mut entries, &key) {
+                    return Ok((-2).into());
+                }
+                Ok(match entries[&key].expires_at {
+                    Some(at) => (at.saturating_duration_since(Instant::now()).as_secs() as i64).into(),
+                    None => (-1).into(),
+                })
+            },
+            "SADD" | "SREM" => {
+                let key = string_arg(&args, 0)?;
+                if !Self::live(&mut entries, &key) {
+                    entries.insert(key.clone(), Entry { value: Value::Set(BTreeSet::new()), expires_at: None });
+                }
+                let Value::Set(members) = &mut entries.get_mut(&key).expect("live key").value else {
+                    return Err(invalid("WRONGTYPE"));
+                };
+                let mut changed = 0;
+                for index in 1..args.len() {
+                    let member = string_arg(&args, index)?;
+                    let done = match &*command.cmd {
+                        "SADD" => members.insert(member),
+                        _ => members.remove(&member),
+                    };
+                    if done {
+                        changed += 1;
+                    }
+                }
+                if members.is_empty() {
+                    entries.remove(&key);
+                }
+                Ok(changed.into())
+            },
+            "SMEMBERS" => {
+                let key = string_arg(&args, 0)?;
+                if !Self::live(&mut entries, &key) {
+                    return Ok(RedisValue::Array(Vec::new()));
+                }
+                match &entries[&key].value {
+                    Value::Set(members) => {
+                        Ok(RedisValue::Array(members.iter().map(|member| member.as_str().into()).collect()))
+                    },
+                    Value::Str(_) => Err(invalid("WRONGTYPE")),
+                }
+            },
+            _ => Err(RedisError::new(RedisErrorKind::Unknown, "Unimplemented.")),
+        }
+    }
+}
+
+impl Mocks for MemoryRedis {
+    fn process_command(&self, command: MockCommand) -> Result<RedisValue, RedisError> {
+        self.execute(command)
+    }
+}
+
+impl RedisConnectionPool {
+    /// Pool backed by a fresh [`MemoryRedis`], for tests that need Redis state.
+    pub async fn mock() -> Self {
+        let config = fred::types::RedisConfig {
+            mocks: Some(Arc::new(MemoryRedis::default())),
+            ..Default::default()
+        };
+        let pool = fred::prelude::RedisPool::new(config, None, None, None, 1).expect("mock redis pool");
+        pool.connect();
+        pool.wait_for_connect().await.expect("mock redis connection");
+
+        Self {
+            pool: Arc::new(pool),
+            key_prefix: String::default(),
+            config: Arc::new(RedisConfig {
+                default_ttl: 300,
+                default_stream_read_count: 1,
+                default_hash_ttl: 900,
+            }),
+            is_redis_available: Arc::new(atomic::AtomicBool::new(true)),
+        }
+    }
+}
@@ -2,3 +2,5 @@ pub mod commands;
 pub mod errors;
 pub mod lib;
 pub mod types;
+#[cfg(test)]
+pub mod mock;
@@ -262,8 +262,8 @@ impl RedisKey {
         pool.add_prefix(&self.0)
     }
 
-    pub fn tenant_unaware_key(&self, _pool: &RedisConnectionPool) -> String {
-        self.0.clone()
+    pub fn tenant_unaware_key(&self, pool: &RedisConnectionPool) -> String {
+        pool.add_legacy_prefix(&self.0)
     }
 }
 
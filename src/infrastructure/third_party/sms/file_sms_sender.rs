@@ -0,0 +1,62 @@
+use super::{SmsMessage, SmsSender};
+use crate::core::error::AppResult;
+use crate::util::file::store_file;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Writes every message as a `.txt` file into an outbox directory instead of sending it.
+/// Meant for local development and tests.
+pub struct FileSmsSender {
+    pub sender_id: String,
+    pub outbox_dir: PathBuf,
+}
+
+impl FileSmsSender {
+    pub fn new(sender_id: String, outbox_dir: PathBuf) -> Self {
+        Self {
+            sender_id,
+            outbox_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl SmsSender for FileSmsSender {
+    async fn send(&self, message: SmsMessage) -> AppResult<()> {
+        let file_path = self.outbox_dir.join(format!(
+            "{}_{}.txt",
+            Utc::now().format("%Y%m%d%H%M%S"),
+            Uuid::new_v4()
+        ));
+        let content = format!(
+            "From: {}\nTo: {}\n\n{}\n",
+            self.sender_id, message.to, message.body
+        );
+        store_file(&file_path, content.as_bytes()).await?;
+        log::info!("SMS to {} written to {}", message.to, file_path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_message_is_written_to_the_outbox() {
+        let outbox_dir = std::env::temp_dir().join(format!("sms_outbox_{}", Uuid::new_v4()));
+        let sender = FileSmsSender::new("June18".to_string(), outbox_dir.clone());
+        sender
+            .send(SmsMessage { to: "+15550100".to_string(), body: "12345 is your code".to_string() })
+            .await
+            .unwrap();
+
+        let mut files = std::fs::read_dir(&outbox_dir).unwrap();
+        let content = std::fs::read_to_string(files.next().unwrap().unwrap().path()).unwrap();
+        assert_eq!(content, "From: June18\nTo: +15550100\n\n12345 is your code\n");
+        assert!(files.next().is_none());
+        std::fs::remove_dir_all(outbox_dir).unwrap();
+    }
+}
@@ -0,0 +1,39 @@
+use super::{SmsMessage, SmsSender};
+use crate::core::client::http::HttpClient;
+use crate::core::error::{AppError, AppResult};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Hands every message to an SMS provider's HTTP API.
+pub struct HttpSmsSender {
+    pub http: HttpClient,
+    pub sender_id: String,
+    pub api_url: String,
+    pub api_key: String,
+}
+
+impl HttpSmsSender {
+    pub fn new(http: HttpClient, sender_id: String, api_url: String, api_key: String) -> Self {
+        Self { http, sender_id, api_url, api_key }
+    }
+}
+
+#[async_trait]
+impl SmsSender for HttpSmsSender {
+    async fn send(&self, message: SmsMessage) -> AppResult<()> {
+        self.http
+            .post(&self.api_url)
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "from": self.sender_id,
+                "to": message.to,
+                "text": message.body,
+            }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| AppError::UnknownError(anyhow::anyhow!("SMS delivery failed: {err}")))?;
+        log::info!("SMS to {} handed to the provider", message.to);
+        Ok(())
+    }
+}
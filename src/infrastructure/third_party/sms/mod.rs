@@ -0,0 +1,44 @@
+pub mod file_sms_sender;
+pub mod http_sms_sender;
+
+use crate::core::client::http::HttpClient;
+use crate::core::configure::app::Profile;
+use crate::core::configure::delivery::{self, DeliveryTransport};
+use crate::core::configure::sms::SmsConfig;
+use crate::core::error::AppResult;
+use crate::util::dir::get_project_root;
+use async_trait::async_trait;
+use file_sms_sender::FileSmsSender;
+use http_sms_sender::HttpSmsSender;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmsMessage {
+    pub to: String,
+    pub body: String,
+}
+
+/// Delivers text messages (one-time sign-in codes, ...).
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    async fn send(&self, message: SmsMessage) -> AppResult<()>;
+}
+
+/// The sender configured for `profile`; fails when there is none fit for it.
+pub fn build_sms_sender(
+    profile: Profile,
+    config: &SmsConfig,
+    http: HttpClient,
+) -> AppResult<Arc<dyn SmsSender>> {
+    Ok(match delivery::select_transport(profile, "sms", config.transport)? {
+        DeliveryTransport::File => Arc::new(FileSmsSender::new(
+            config.sender_id.clone(),
+            get_project_root()?.join(&config.outbox_dir),
+        )),
+        DeliveryTransport::Http => {
+            let (api_url, api_key) = delivery::http_endpoint("sms", &config.api_url, &config.api_key)?;
+            Arc::new(HttpSmsSender::new(http, config.sender_id.clone(), api_url, api_key))
+        },
+    })
+}
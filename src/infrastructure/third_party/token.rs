@@ -1,21 +1,101 @@
 use crate::core::error::AppResult;
-use crate::util::claim::UserClaims;
+use crate::util::claim::{Access, Authentication, UserClaims};
 use crate::util::constant::{
-    ACCESS_TOKEN_ENCODE_KEY, EXPIRE_BEARER_TOKEN_SECS, EXPIRE_REFRESH_TOKEN_SECS,
-    REFRESH_TOKEN_ENCODE_KEY,
+    ACCESS_TOKEN_KEYS, EXPIRE_BEARER_TOKEN_SECS, EXPIRE_REFRESH_TOKEN_SECS, REFRESH_TOKEN_KEYS,
 };
 use uuid::Uuid;
 use crate::presentation::authen::authen::TokenResponse;
 
+/// Issues an access/refresh pair for the session. The refresh token carries
+/// `refresh_jti` so the caller can remember which refresh token is the live one.
+/// Only the access token carries `access`, refreshing reads it again.
 pub fn service_generate_tokens(
     user_id: &i64,
     session_id: &Uuid,
+    refresh_jti: &Uuid,
+    authentication: &Authentication,
+    access: &Access,
 ) -> AppResult<TokenResponse> {
-    let access_token =
+    generate_tokens(
         UserClaims::new(EXPIRE_BEARER_TOKEN_SECS, user_id, session_id)
-            .encode(&ACCESS_TOKEN_ENCODE_KEY)?;
-    let refresh_token =
-        UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, user_id, session_id)
-            .encode(&REFRESH_TOKEN_ENCODE_KEY)?;
+            .authenticated(authentication)
+            .with_access(access),
+        UserClaims {
+            jti: *refresh_jti,
+            ..UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, user_id, session_id)
+        }
+        .authenticated(authentication),
+    )
+}
+
+/// Same as [`service_generate_tokens`] for a third-party client, limited to `scope`.
+pub fn service_generate_delegated_tokens(
+    user_id: &i64,
+    session_id: &Uuid,
+    refresh_jti: &Uuid,
+    authentication: &Authentication,
+    client_id: &str,
+    scope: &str,
+) -> AppResult<TokenResponse> {
+    generate_tokens(
+        UserClaims::new(EXPIRE_BEARER_TOKEN_SECS, user_id, session_id)
+            .authenticated(authentication)
+            .delegated_to(client_id, scope),
+        UserClaims {
+            jti: *refresh_jti,
+            ..UserClaims::new(EXPIRE_REFRESH_TOKEN_SECS, user_id, session_id)
+        }
+        .authenticated(authentication)
+        .delegated_to(client_id, scope),
+    )
+}
+
+fn generate_tokens(access: UserClaims, refresh: UserClaims) -> AppResult<TokenResponse> {
+    let access_token = access.encode(&ACCESS_TOKEN_KEYS)?;
+    let refresh_token = refresh.encode(&REFRESH_TOKEN_KEYS)?;
     Ok(TokenResponse::new(access_token, refresh_token, EXPIRE_BEARER_TOKEN_SECS.as_secs()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::claim::AMR_PASSWORD;
+
+    #[test]
+    fn test_refresh_token_carries_the_rotation_jti_but_not_the_access() {
+        let (session_id, refresh_jti) = (Uuid::new_v4(), Uuid::new_v4());
+        let access = Access { roles: vec!["admin".to_string()], permissions: vec!["users:read".to_string()] };
+        let tokens = service_generate_tokens(
+            &7,
+            &session_id,
+            &refresh_jti,
+            &Authentication::now(&[AMR_PASSWORD]),
+            &access,
+        )
+        .unwrap();
+
+        let refresh = UserClaims::decode(&tokens.refresh_token, &REFRESH_TOKEN_KEYS).unwrap().claims;
+        assert_eq!((refresh.user_id, refresh.sid, refresh.jti), (7, session_id, refresh_jti));
+        assert!(refresh.permissions.is_empty());
+        assert_eq!(refresh.amr, vec![AMR_PASSWORD]);
+
+        let access_claims = UserClaims::decode(&tokens.access_token, &ACCESS_TOKEN_KEYS).unwrap().claims;
+        assert_eq!(access_claims.sid, session_id);
+        assert_ne!(access_claims.jti, refresh_jti);
+        assert_eq!(access_claims.permissions, access.permissions);
+    }
+
+    #[test]
+    fn test_tokens_only_verify_with_their_own_keys() {
+        let tokens = service_generate_tokens(
+            &7,
+            &Uuid::new_v4(),
+            &Uuid::new_v4(),
+            &Authentication::now(&[AMR_PASSWORD]),
+            &Access::default(),
+        )
+        .unwrap();
+        assert!(UserClaims::decode(&tokens.refresh_token, &ACCESS_TOKEN_KEYS).is_err());
+        assert!(UserClaims::decode(&tokens.access_token, &REFRESH_TOKEN_KEYS).is_err());
+    }
+}
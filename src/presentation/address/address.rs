@@ -40,7 +40,9 @@ impl From<AddressModel> for AddressSerializer {
 
 #[derive(Debug, Deserialize, Serialize, ToSchema, Clone)]
 pub struct CreateAddressRequest {
-    pub user_id: i64,
+    /// Owner of the address, the caller when omitted. Other users need `users:write`.
+    #[serde(default)]
+    pub user_id: Option<i64>,
     pub title: Option<String>,
     pub address_line_1: String,
     pub address_line_2: Option<String>,
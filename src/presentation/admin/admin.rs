@@ -0,0 +1,87 @@
+use crate::domain::user::status_change;
+use crate::domain::user::user::{Model as UserRecord, ModelEx as UserModel, Status};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A user as admins see it, deleted accounts included.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct AdminUserSerializer {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub avatar: Option<String>,
+    pub birth_of_date: Option<NaiveDate>,
+    pub phone_number: Option<String>,
+    pub gender: Option<String>,
+    pub language: Option<String>,
+    pub status: Status,
+    pub suspended_until: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub two_factor_enabled: bool,
+    pub has_password: bool,
+    pub is_service_account: bool,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+impl From<UserRecord> for AdminUserSerializer {
+    fn from(value: UserRecord) -> Self {
+        AdminUserSerializer {
+            id: value.id,
+            username: value.username,
+            email: value.email,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            avatar: value.avatar,
+            birth_of_date: value.birth_of_date,
+            phone_number: value.phone_number,
+            gender: value.gender,
+            language: value.language,
+            status: value.status,
+            suspended_until: value.suspended_until,
+            email_verified_at: value.email_verified_at,
+            two_factor_enabled: value.totp_enabled_at.is_some() && value.totp_secret.is_some(),
+            has_password: value.password.is_some(),
+            is_service_account: value.is_service_account,
+            is_deleted: value.is_deleted,
+            created_at: value.created_at,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
+
+impl From<UserModel> for AdminUserSerializer {
+    fn from(value: UserModel) -> Self {
+        UserRecord::from(value).into()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct StatusChangeSerializer {
+    pub id: i64,
+    pub from_status: Status,
+    pub to_status: Status,
+    /// Absent when the system made the change, e.g. at the end of a suspension
+    pub actor_id: Option<i64>,
+    pub reason: Option<String>,
+    pub suspended_until: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<status_change::Model> for StatusChangeSerializer {
+    fn from(value: status_change::Model) -> Self {
+        StatusChangeSerializer {
+            id: value.id,
+            from_status: value.from_status,
+            to_status: value.to_status,
+            actor_id: value.actor_id,
+            reason: value.reason,
+            suspended_until: value.suspended_until,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::domain::user::api_key;
+use crate::domain::user::user::ModelEx as UserModel;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ApiKeySerializer {
+    pub id: i64,
+    pub name: String,
+    /// First characters of the key, enough to recognise it
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<api_key::Model> for ApiKeySerializer {
+    fn from(value: api_key::Model) -> Self {
+        ApiKeySerializer {
+            id: value.id,
+            name: value.name,
+            prefix: value.prefix,
+            scopes: value.scopes.split_whitespace().map(str::to_string).collect(),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            revoked_at: value.revoked_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// The plain key is returned only here, it cannot be read back later.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ApiKeyCreatedResponse {
+    /// Send as `Authorization: Bearer <key>`
+    pub key: String,
+    pub api_key: ApiKeySerializer,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ServiceAccountSerializer {
+    pub id: i64,
+    pub username: String,
+    pub display_name: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<UserModel> for ServiceAccountSerializer {
+    fn from(value: UserModel) -> Self {
+        ServiceAccountSerializer {
+            id: value.id,
+            username: value.username,
+            display_name: value.first_name,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::util::constant::BEARER;
+use crate::util::key_ring::PublicJwk;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -6,7 +7,9 @@ use utoipa::ToSchema;
 #[serde(tag = "type")]
 pub enum LoginResponse {
     Token(TokenResponse),
-    Code { message: String, expire_in: u64 },
+    /// The password was correct but a second factor is required. Exchange
+    /// `challenge_token` together with a TOTP or recovery code at `/v1/login/2fa`.
+    Code { message: String, expire_in: u64, challenge_token: String },
 }
 
 impl From<TokenResponse> for LoginResponse {
@@ -28,3 +31,9 @@ impl TokenResponse {
         Self { token_type: BEARER.to_string(), access_token, refresh_token, expire_in }
     }
 }
+
+/// RFC 7517 JWK Set with the public keys that verify access tokens.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct JwkSetResponse {
+    pub keys: Vec<PublicJwk>,
+}
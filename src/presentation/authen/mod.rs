@@ -1 +1,2 @@
 pub mod authen;
+pub mod oidc;
@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum AuthorizeResponse {
+    /// Send the browser to `redirect_to`. It carries the code, or an error for the client.
+    Redirect { redirect_to: String },
+    /// Ask the user whether `client_name` may access `scopes`, then post the
+    /// answer to `/oauth/authorize` with the same parameters.
+    ConsentRequired { client_id: String, client_name: String, scopes: Vec<String> },
+}
+
+/// RFC 6749 5.1 access token response, plus the OIDC `id_token`
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub refresh_token: String,
+    /// Only issued for the authorization code grant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    pub scope: String,
+}
+
+/// OpenID Connect Core 5.3.2, claims limited to the granted scopes
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct UserInfoResponse {
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ConsentResponse {
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// OpenID Connect Discovery 1.0 provider metadata
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct OpenIdConfigurationResponse {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub scopes_supported: Vec<String>,
+    pub claims_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+}
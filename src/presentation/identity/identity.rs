@@ -0,0 +1,33 @@
+use crate::domain::user::identity;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where to send the browser to sign in at the provider.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ExternalLoginStartResponse {
+    pub authorization_url: String,
+    /// Seconds left to come back with the provider's callback
+    pub expire_in: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct IdentitySerializer {
+    pub id: i64,
+    pub provider: String,
+    pub email: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+impl From<identity::Model> for IdentitySerializer {
+    fn from(value: identity::Model) -> Self {
+        IdentitySerializer {
+            id: value.id,
+            provider: value.provider,
+            email: value.email,
+            created_at: value.created_at,
+            last_login_at: value.last_login_at,
+        }
+    }
+}
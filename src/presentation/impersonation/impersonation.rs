@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An access token that acts as the user. There is no refresh token.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct ImpersonationResponse {
+    pub token_type: String,
+    pub access_token: String,
+    pub expire_in: u64,
+    pub user_id: i64,
+    pub session_id: Uuid,
+}
@@ -1,4 +1,11 @@
+pub mod admin;
+pub mod api_key;
+pub mod address;
 pub mod authen;
+pub mod identity;
+pub mod impersonation;
+pub mod oauth;
+pub mod session;
+pub mod two_factor;
 pub mod user;
-pub mod address;
 mod common;
@@ -0,0 +1,36 @@
+use crate::infrastructure::persistence::redis_client::session::SessionRecord;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct SessionSerializer {
+    pub session_id: Uuid,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub is_current: bool,
+    /// Set while an admin is signed in as the user for support
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<i64>,
+}
+
+impl SessionSerializer {
+    pub fn from_record(value: SessionRecord, current_session_id: &Uuid) -> Self {
+        SessionSerializer {
+            is_current: &value.session_id == current_session_id,
+            session_id: value.session_id,
+            device_name: value.device_name,
+            ip_address: value.ip_address,
+            user_agent: value.user_agent,
+            created_at: value.created_at,
+            last_seen_at: value.last_seen_at,
+            expires_at: value.expires_at,
+            impersonator_id: value.impersonator_id,
+        }
+    }
+}
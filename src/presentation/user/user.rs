@@ -12,11 +12,14 @@ pub struct UserSerializer {
     pub username: String,
     pub email: String,
     pub address: Vec<SubAddressSerializer>,
-    pub password: Option<String>,
     pub birth_of_date: Option<NaiveDate>,
     pub phone_number: Option<String>,
+    pub email_verified_at: Option<NaiveDateTime>,
     pub created_at: Option<NaiveDateTime>,
     pub deleted_at: Option<NaiveDateTime>,
+    /// Admin viewing the profile through an impersonation token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<i64>,
 }
 
 impl From<UserModel> for UserSerializer {
@@ -33,11 +36,12 @@ impl From<UserModel> for UserSerializer {
                 address_line_2: a.address_line_2,
                 country: a.country,
             }).collect(),
-            password: value.password,
             birth_of_date: value.birth_of_date,
             phone_number: value.phone_number,
+            email_verified_at: value.email_verified_at,
             created_at: value.created_at,
             deleted_at: value.deleted_at,
+            impersonated_by: None,
         }
     }
 }
@@ -54,7 +58,7 @@ pub struct CreateUserRequest {
     pub phone_number: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, ToSchema, Clone)]
+#[derive(Debug, Deserialize, Serialize, ToSchema, Clone, Default)]
 pub struct UpdateUserRequest {
     pub avatar: Option<String>,
     pub first_name: Option<String>,
@@ -63,4 +67,23 @@ pub struct UpdateUserRequest {
     pub birth_of_date: Option<NaiveDate>,
     pub phone_number: Option<String>,
     pub status: Option<Status>,
+    /// Why the status changes, kept in the account's status history
+    pub status_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_never_exposes_the_password_hash() {
+        let user_res = UserModel::create_external_user("jane", "jane@example.com", "Jane", "Doe", true)
+            .unwrap()
+            .change_password("$argon2id$v=19$hash".to_string());
+
+        let json = serde_json::to_value(UserSerializer::from(user_res)).unwrap();
+        assert_eq!(json["username"], "jane");
+        assert!(json.get("password").is_none());
+        assert!(!json.to_string().contains("argon2"));
+    }
 }
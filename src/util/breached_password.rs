@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Bundled into the binary, so the check needs no file or network access at runtime.
+static BREACHED_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("../../static/password/breached_passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+pub fn is_breached(password: &str) -> bool {
+    BREACHED_PASSWORDS.contains(password.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_password_is_breached_in_any_case() {
+        assert!(is_breached("password123"));
+        assert!(is_breached("PassWord123"));
+    }
+
+    #[test]
+    fn test_comments_are_not_entries() {
+        assert!(!BREACHED_PASSWORDS.iter().any(|entry| entry.starts_with('#')));
+        assert!(!is_breached("correct horse battery staple"));
+    }
+}
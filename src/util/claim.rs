@@ -1,7 +1,11 @@
 use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
 use chrono::Utc;
 use jsonwebtoken::Header;
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, TokenData, Validation};
+use crate::util::constant::EXPIRE_BEARER_TOKEN_SECS;
+use crate::util::key_ring::KeyRing;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, TokenData, Validation};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde::Serialize;
@@ -12,12 +16,91 @@ use uuid::Uuid;
 pub static DECODE_HEADER: Lazy<Validation> = Lazy::new(|| Validation::new(Algorithm::RS256));
 pub static ENCODE_HEADER: Lazy<Header> = Lazy::new(|| Header::new(Algorithm::RS256));
 
+// Authentication method references (RFC 8176) carried in `amr`
+pub const AMR_PASSWORD: &str = "pwd";
+pub const AMR_OTP: &str = "otp";
+pub const AMR_MFA: &str = "mfa";
+pub const AMR_SMS: &str = "sms";
+pub const AMR_EMAIL: &str = "email";
+pub const AMR_FEDERATED: &str = "fed";
+
+/// When and how the user last proved who they are. Every token of a session carries it,
+/// refreshing keeps it and re-authenticating renews it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct Authentication {
+    pub auth_time: i64,
+    pub amr: Vec<String>,
+}
+
+impl Authentication {
+    pub fn now(methods: &[&str]) -> Self {
+        Self {
+            auth_time: Utc::now().timestamp(),
+            amr: methods.iter().map(|method| method.to_string()).collect(),
+        }
+    }
+
+    /// A further factor on top of this one, e.g. the TOTP code after the password.
+    pub fn and_then(mut self, methods: &[&str]) -> Self {
+        self.auth_time = Utc::now().timestamp();
+        for method in methods {
+            if !self.amr.iter().any(|known| known == method) {
+                self.amr.push(method.to_string());
+            }
+        }
+        self
+    }
+}
+
+/// Roles of the user and the permissions they grant, copied into first-party access tokens.
+/// Changes reach the user with the next refresh.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct Access {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+/// The admin acting as the user on an impersonation token (RFC 8693 `act`).
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, ToSchema)]
+pub struct Actor {
+    pub user_id: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, ToSchema)]
 pub struct UserClaims {
     pub iat: i64,
     pub exp: i64,
     pub user_id: i64,
     pub sid: Uuid,
+    pub jti: Uuid,
+    /// Client the token was issued to through the OIDC flow. Absent on first-party tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp: Option<String>,
+    /// Scopes the user granted to `azp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Set when the request authenticated with an API key instead of a JWT. Never part of a token.
+    #[serde(skip)]
+    pub api_key_id: Option<i64>,
+    /// See [`Authentication`]. Tokens issued before it was added have 0 and count as stale.
+    #[serde(default)]
+    pub auth_time: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amr: Vec<String>,
+    /// Set on tokens an admin uses to see the service as this user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+    /// Tenant the token was issued in, older tokens belong to the default one
+    #[serde(default = "default_tenant_id")]
+    pub tenant_id: String,
+}
+
+fn default_tenant_id() -> String {
+    tenant::DEFAULT_TENANT.to_string()
 }
 
 impl UserClaims {
@@ -32,19 +115,236 @@ impl UserClaims {
             exp: now + (duration.as_secs() as i64),
             user_id: *user_id,
             sid: *session_id,
+            jti: Uuid::new_v4(),
+            azp: None,
+            scope: None,
+            api_key_id: None,
+            // Only `authenticated` vouches for a login, a bare token never counts as recent
+            auth_time: 0,
+            amr: Vec::new(),
+            act: None,
+            roles: Vec::new(),
+            permissions: Vec::new(),
+            tenant_id: tenant::current_tenant(),
+        }
+    }
+
+    pub fn authenticated(self, authentication: &Authentication) -> Self {
+        Self { auth_time: authentication.auth_time, amr: authentication.amr.clone(), ..self }
+    }
+
+    pub fn with_access(self, access: &Access) -> Self {
+        Self { roles: access.roles.clone(), permissions: access.permissions.clone(), ..self }
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|granted| granted == permission)
+    }
+
+    pub fn require_permission(&self, permission: &str) -> AppResult<()> {
+        if !self.has_permission(permission) {
+            return Err(AppError::PermissionDeniedError(format!(
+                "Permission `{permission}` required"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn authentication(&self) -> Authentication {
+        Authentication { auth_time: self.auth_time, amr: self.amr.clone() }
+    }
+
+    /// Refuses callers whose last login or re-authentication is older than `max_age`.
+    /// API keys never qualify, nobody typed anything to use them, and neither do
+    /// impersonation tokens.
+    pub fn require_recent_auth(&self, max_age: Duration) -> AppResult<()> {
+        if self.is_api_key() {
+            return Err(AppError::PermissionDeniedError(
+                "This operation is not available to API keys".to_string(),
+            ));
+        }
+        self.require_not_impersonated()?;
+        let age = Utc::now().timestamp() - self.auth_time;
+        if self.auth_time <= 0 || age > max_age.as_secs() as i64 {
+            return Err(AppError::ReauthenticationRequiredError(
+                "Please confirm your password or a second factor again to continue".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Claims standing in for an API key for the duration of one request. The key has no
+    /// session, so `sid` is nil.
+    pub fn from_api_key(user_id: i64, api_key_id: i64, scope: &str, expires_at: Option<i64>) -> Self {
+        let now = Utc::now().timestamp();
+        Self {
+            iat: now,
+            exp: expires_at.unwrap_or(now + EXPIRE_BEARER_TOKEN_SECS.as_secs() as i64),
+            user_id,
+            sid: Uuid::nil(),
+            jti: Uuid::new_v4(),
+            azp: None,
+            scope: Some(scope.to_string()),
+            api_key_id: Some(api_key_id),
+            auth_time: 0,
+            amr: Vec::new(),
+            act: None,
+            roles: Vec::new(),
+            permissions: Vec::new(),
+            tenant_id: tenant::current_tenant(),
+        }
+    }
+
+    /// Limits the token to what the user granted a third-party client, which never
+    /// includes the user's permissions.
+    pub fn delegated_to(self, client_id: &str, scope: &str) -> Self {
+        Self {
+            azp: Some(client_id.to_string()),
+            scope: Some(scope.to_string()),
+            roles: Vec::new(),
+            permissions: Vec::new(),
+            ..self
+        }
+    }
+
+    /// Tokens held by a third-party client only reach the endpoints meant for it.
+    pub fn is_delegated(&self) -> bool {
+        self.azp.is_some()
+    }
+
+    pub fn is_api_key(&self) -> bool {
+        self.api_key_id.is_some()
+    }
+
+    /// Turns the claims into an impersonation token of `actor_id`. It never counts as a
+    /// recent authentication of the user.
+    pub fn impersonated_by(self, actor_id: i64) -> Self {
+        Self { auth_time: 0, amr: Vec::new(), act: Some(Actor { user_id: actor_id }), ..self }
+    }
+
+    pub fn is_impersonated(&self) -> bool {
+        self.act.is_some()
+    }
+
+    /// Only the account owner may change how the account is secured.
+    pub fn require_not_impersonated(&self) -> AppResult<()> {
+        if self.is_impersonated() {
+            return Err(AppError::PermissionDeniedError(
+                "This operation is not available while impersonating a user".to_string(),
+            ));
         }
+        Ok(())
     }
 
+    /// Claims of a signed-in device. API keys have no session of their own (`sid` is nil),
+    /// so they cannot manage the user's sessions.
+    pub fn require_session(&self) -> AppResult<()> {
+        if self.is_api_key() || self.sid.is_nil() {
+            return Err(AppError::PermissionDeniedError(
+                "This operation needs a signed-in session, not an API key".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tokens are only accepted in the tenant they were issued in.
+    pub fn require_current_tenant(&self) -> AppResult<()> {
+        if self.tenant_id != tenant::current_tenant() {
+            return Err(AppError::InvalidSessionError(
+                "The token was not issued for this tenant".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verifies with the key named by the token's `kid`.
     pub fn decode(
         token: &str,
-        key: &DecodingKey,
+        keys: &KeyRing,
     ) -> Result<TokenData<Self>, jsonwebtoken::errors::Error> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let key = keys
+            .decoding_key(header.kid.as_deref())
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidSignature))?;
         jsonwebtoken::decode::<UserClaims>(token, key, &DECODE_HEADER)
     }
 
-    pub fn encode(&self, key: &EncodingKey) -> Result<String, jsonwebtoken::errors::Error> {
-        jsonwebtoken::encode(&ENCODE_HEADER, self, key)
+    /// Signs with the active key and stamps its `kid` into the header.
+    pub fn encode(&self, keys: &KeyRing) -> Result<String, jsonwebtoken::errors::Error> {
+        sign(self, keys)
+    }
+}
+
+/// OpenID Connect Core 2 ID token, handed to the client next to the access token.
+/// Signed with the access token keys so clients verify it through the same JWKS.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, ToSchema)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub sid: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+impl IdTokenClaims {
+    pub fn encode(&self, keys: &KeyRing) -> Result<String, jsonwebtoken::errors::Error> {
+        sign(self, keys)
+    }
+}
+
+const MAGIC_LINK_AUDIENCE: &str = "magic_link";
+
+/// Emailed sign-in link. Signed with the access token keys under its own audience, so
+/// it is never accepted as an access token; `jti` makes it single use.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct MagicLinkClaims {
+    pub aud: String,
+    pub user_id: i64,
+    pub jti: Uuid,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+impl MagicLinkClaims {
+    pub fn new(duration: Duration, user_id: i64) -> Self {
+        let now = Utc::now().timestamp();
+        Self {
+            aud: MAGIC_LINK_AUDIENCE.to_string(),
+            user_id,
+            jti: Uuid::new_v4(),
+            iat: now,
+            exp: now + (duration.as_secs() as i64),
+        }
+    }
+
+    pub fn encode(&self, keys: &KeyRing) -> Result<String, jsonwebtoken::errors::Error> {
+        sign(self, keys)
     }
+
+    pub fn decode(token: &str, keys: &KeyRing) -> Result<Self, jsonwebtoken::errors::Error> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let key = keys
+            .decoding_key(header.kid.as_deref())
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidSignature))?;
+        let mut validation = DECODE_HEADER.clone();
+        validation.set_audience(&[MAGIC_LINK_AUDIENCE]);
+        Ok(jsonwebtoken::decode::<Self>(token, key, &validation)?.claims)
+    }
+}
+
+fn sign<T: Serialize>(claims: &T, keys: &KeyRing) -> Result<String, jsonwebtoken::errors::Error> {
+    let header = Header { kid: Some(keys.active_kid().to_string()), ..ENCODE_HEADER.clone() };
+    jsonwebtoken::encode(&header, claims, keys.encoding_key())
 }
 
 pub trait UserClaimsRequest {
@@ -67,3 +367,88 @@ impl UserClaimsRequest for axum::extract::Request {
             .ok_or_else(|| AppError::UnauthorizedError("User must login".to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims_authenticated_at(auth_time: i64) -> UserClaims {
+        UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4())
+            .authenticated(&Authentication { auth_time, amr: vec![AMR_PASSWORD.to_string()] })
+    }
+
+    #[test]
+    fn test_recent_auth_accepts_fresh_login() {
+        let claims = claims_authenticated_at(Utc::now().timestamp() - 30);
+        assert!(claims.require_recent_auth(Duration::from_secs(300)).is_ok());
+    }
+
+    #[test]
+    fn test_recent_auth_rejects_old_or_missing_auth_time() {
+        let old = claims_authenticated_at(Utc::now().timestamp() - 3600);
+        assert!(matches!(
+            old.require_recent_auth(Duration::from_secs(300)),
+            Err(AppError::ReauthenticationRequiredError(_))
+        ));
+        assert!(claims_authenticated_at(0).require_recent_auth(Duration::from_secs(300)).is_err());
+    }
+
+    #[test]
+    fn test_unauthenticated_claims_are_never_recent_auth() {
+        let claims = UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4());
+        assert!(matches!(
+            claims.require_recent_auth(Duration::from_secs(300)),
+            Err(AppError::ReauthenticationRequiredError(_))
+        ));
+    }
+
+    #[test]
+    fn test_recent_auth_rejects_api_keys() {
+        let claims = UserClaims::from_api_key(1, 7, "read write", None);
+        assert!(matches!(
+            claims.require_recent_auth(Duration::from_secs(300)),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+    }
+
+    #[test]
+    fn test_session_operations_reject_api_keys_and_nil_sessions() {
+        assert!(UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4()).require_session().is_ok());
+        assert!(matches!(
+            UserClaims::from_api_key(1, 7, "read write", None).require_session(),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+        assert!(matches!(
+            UserClaims::new(Duration::from_secs(60), &1, &Uuid::nil()).require_session(),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+    }
+
+    #[test]
+    fn test_second_factor_extends_amr_once() {
+        let authentication = Authentication::now(&[AMR_PASSWORD]).and_then(&[AMR_OTP, AMR_PASSWORD]);
+        assert_eq!(authentication.amr, vec![AMR_PASSWORD, AMR_OTP]);
+    }
+
+    #[test]
+    fn test_impersonation_token_is_never_recent_auth() {
+        let claims = claims_authenticated_at(Utc::now().timestamp()).impersonated_by(42);
+        assert_eq!(claims.act, Some(Actor { user_id: 42 }));
+        assert!(matches!(
+            claims.require_recent_auth(Duration::from_secs(300)),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+    }
+
+    #[test]
+    fn test_permission_must_be_granted() {
+        let access = Access { roles: vec!["support".to_string()], permissions: vec!["users:read".to_string()] };
+        let claims = claims_authenticated_at(0).with_access(&access);
+        assert!(claims.require_permission("users:read").is_ok());
+        assert!(matches!(
+            claims.require_permission("users:delete"),
+            Err(AppError::PermissionDeniedError(_))
+        ));
+        assert!(!claims.delegated_to("client", "openid").has_permission("users:read"));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::core::client::http::{ClientBuilder, HttpClient};
 use crate::core::configure;
 use crate::core::configure::app::Profile;
-use jsonwebtoken::{DecodingKey, EncodingKey};
+use crate::util::key_ring::KeyRing;
 use reqwest::Client;
 use std::sync::LazyLock;
 use std::time::Duration;
@@ -13,9 +13,37 @@ pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(120);
 pub const EXPIRE_SESSION_CODE_SECS: Duration = Duration::from_secs(36000);
 pub const EXPIRE_INVITATION_CODE_SECS: Duration = Duration::from_secs(86000);
 pub const EXPIRE_BLOCKED_EMAIL_SECS: Duration = Duration::from_secs(300);
+pub const EXPIRE_LOGIN_FAILURES_SECS: Duration = Duration::from_secs(900);
+pub const MAX_LOGIN_FAILURES_PER_USERNAME: i64 = 5;
+pub const MAX_LOGIN_FAILURES_PER_IP: i64 = 50;
+pub const MAX_LOGIN_BACKOFF_SECS: i64 = 60;
 pub const EXPIRE_FORGET_PASS_CODE_SECS: Duration = Duration::from_secs(300);
+pub const EXPIRE_RESEND_VERIFY_EMAIL_SECS: Duration = Duration::from_secs(60);
+pub const MAX_VERIFY_EMAIL_ATTEMPTS: i64 = 5;
+pub const EXPIRE_TOTP_ENROLLMENT_SECS: Duration = Duration::from_secs(600);
+pub const EXPIRE_LOGIN_CHALLENGE_SECS: Duration = Duration::from_secs(300);
+pub const MAX_LOGIN_CHALLENGE_ATTEMPTS: i64 = 5;
+pub const EXPIRE_SECOND_FACTOR_FAILURES_SECS: Duration = Duration::from_secs(3600);
+pub const MAX_SECOND_FACTOR_FAILURES: i64 = 10;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+pub const RECOVERY_CODE_LEN: usize = 10;
+pub const EXPIRE_AUTHORIZATION_CODE_SECS: Duration = Duration::from_secs(60);
+pub const EXPIRE_ID_TOKEN_SECS: Duration = Duration::from_secs(3600);
+pub const EXPIRE_EXTERNAL_LOGIN_SECS: Duration = Duration::from_secs(600);
+pub const EXPIRE_MAGIC_LINK_SECS: Duration = Duration::from_secs(600);
+pub const EXPIRE_PHONE_OTP_SECS: Duration = Duration::from_secs(300);
+pub const PHONE_OTP_LEN: usize = 6;
+pub const MAX_PHONE_OTP_ATTEMPTS: i64 = 5;
+pub const EXPIRE_PASSWORDLESS_SENDS_SECS: Duration = Duration::from_secs(3600);
+pub const MAX_PASSWORDLESS_SENDS_PER_DESTINATION: i64 = 5;
 pub const EXPIRE_BEARER_TOKEN_SECS: Duration = Duration::from_secs(36000);
+/// How long after a login or re-authentication sensitive operations stay allowed
+pub const RECENT_AUTH_MAX_AGE_SECS: u64 = 600;
 pub const EXPIRE_REFRESH_TOKEN_SECS: Duration = Duration::from_secs(86400);
+/// Impersonation tokens cannot be refreshed, support starts a new one when it runs out
+pub const EXPIRE_IMPERSONATION_SECS: Duration = Duration::from_secs(900);
+pub const EXPIRE_SESSION_IDLE_SECS: Duration = Duration::from_secs(86400);
+pub const EXPIRE_SESSION_ABSOLUTE_SECS: Duration = Duration::from_secs(2592000);
 pub const QUEUE_EMPTY_DELAY_SECS: Duration = Duration::from_secs(60);
 pub const COMPLETE_TASK_DELAY_SECS: Duration = Duration::from_secs(10);
 pub const CHECK_EMAIL_MESSAGE: &str = "Please check you email.";
@@ -62,24 +90,14 @@ pub static HTTP: LazyLock<Client> =
     LazyLock::new(|| HttpClient::build_from_config(&CONFIG).unwrap());
 // pub static REDIS: Lazy<RedisClient> = Lazy::new(|| RedisClient::build_from_config(&CONFIG).unwrap());
 // pub static EMAIL: Lazy<EmailClient> = Lazy::new(|| EmailClient::build_from_config(&CONFIG).unwrap());
-pub static REFRESH_TOKEN_ENCODE_KEY: LazyLock<EncodingKey> = LazyLock::new(|| {
-    let key = CONFIG.secret.read_private_refresh_key().unwrap();
-    EncodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
+pub static ACCESS_TOKEN_KEYS: LazyLock<KeyRing> =
+    LazyLock::new(|| CONFIG.secret.read_access_key_ring().unwrap());
 
-pub static REFRESH_TOKEN_DECODE_KEY: LazyLock<DecodingKey> = LazyLock::new(|| {
-    let key = CONFIG.secret.read_public_refresh_key().unwrap();
-    DecodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
-pub static ACCESS_TOKEN_ENCODE_KEY: LazyLock<EncodingKey> = LazyLock::new(|| {
-    let key = CONFIG.secret.read_private_access_key().unwrap();
-    EncodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
+pub static REFRESH_TOKEN_KEYS: LazyLock<KeyRing> =
+    LazyLock::new(|| CONFIG.secret.read_refresh_key_ring().unwrap());
 
-pub static ACCESS_TOKEN_DECODE_KEY: LazyLock<DecodingKey> = LazyLock::new(|| {
-    let key = CONFIG.secret.read_public_access_key().unwrap();
-    DecodingKey::from_rsa_pem(key.as_bytes()).unwrap()
-});
+pub static PASSWORD_HASHER: LazyLock<argon2::Argon2<'static>> =
+    LazyLock::new(|| CONFIG.auth.password_hash.argon2().unwrap());
 // pub static API_DOC: LazyLock<utoipa::openapi::OpenApi> = LazyLock::new(ApiDoc::openapi);
 // pub static TEMPLATE_ENGIN: Lazy<TemplateEngine> = Lazy::new(|| {
 //     let path = get_static_dir().unwrap().join("template/**/*").into_os_string().into_string().unwrap();
@@ -11,6 +11,8 @@ pub async fn store_file<P: AsRef<Path>>(file_path: &P, content: &[u8]) -> AppRes
 
     let mut file = fs::File::create(&file_path).await?;
     file.write_all(content).await?;
+    // tokio hands the write to a blocking thread, without this it may still be pending on drop
+    file.flush().await?;
     Ok(())
 }
 
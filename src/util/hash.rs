@@ -1,11 +1,16 @@
+use sha2::{Digest, Sha256};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use data_encoding::BASE64;
+use pbkdf2::Pbkdf2;
 
-pub fn argon_hash(content: impl AsRef<str>) -> Result<String, argon2::password_hash::Error> {
+pub fn argon_hash(
+    argon: &Argon2,
+    content: impl AsRef<str>,
+) -> Result<String, argon2::password_hash::Error> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon = Argon2::default();
     Ok(argon.hash_password(content.as_ref().as_bytes(), &salt)?.to_string())
 }
 
@@ -16,3 +21,170 @@ pub fn argon_verify(
     let parsed_hash = PasswordHash::new(hash.as_ref())?;
     Argon2::default().verify_password(content.as_ref().as_bytes(), &parsed_hash)
 }
+
+/// Password hash formats we can verify. Anything but Argon2id with the current
+/// parameters gets replaced at the next login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// PHC string, `$argon2id$v=19$m=...,t=...,p=...$salt$hash` (also argon2i/argon2d)
+    Argon2,
+    /// `$2a$`, `$2b$`, `$2x$` or `$2y$` modular crypt format
+    Bcrypt,
+    /// PHC string, `$pbkdf2-sha256$i=...,l=...$salt$hash` (also pbkdf2-sha512)
+    Pbkdf2,
+    /// Django's `pbkdf2_sha256$iterations$salt$base64hash`
+    DjangoPbkdf2,
+}
+
+pub fn identify(hash: &str) -> Option<HashAlgorithm> {
+    if hash.starts_with("$argon2") {
+        Some(HashAlgorithm::Argon2)
+    } else if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+        Some(HashAlgorithm::Bcrypt)
+    } else if hash.starts_with("$pbkdf2") {
+        Some(HashAlgorithm::Pbkdf2)
+    } else if hash.starts_with("pbkdf2_sha256$") {
+        Some(HashAlgorithm::DjangoPbkdf2)
+    } else {
+        None
+    }
+}
+
+/// Checks a password against a hash in any format of [`HashAlgorithm`], with the
+/// parameters stored in the hash itself.
+pub fn verify_any(
+    content: impl AsRef<str>,
+    hash: impl AsRef<str>,
+) -> Result<(), argon2::password_hash::Error> {
+    use argon2::password_hash::Error;
+
+    let (content, hash) = (content.as_ref(), hash.as_ref());
+    match identify(hash).ok_or(Error::Algorithm)? {
+        HashAlgorithm::Argon2 => argon_verify(content, hash),
+        HashAlgorithm::Bcrypt => match bcrypt::verify(content, hash) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::Password),
+            Err(_) => Err(Error::PhcStringField),
+        },
+        HashAlgorithm::Pbkdf2 => {
+            Pbkdf2.verify_password(content.as_bytes(), &PasswordHash::new(hash)?)
+        },
+        HashAlgorithm::DjangoPbkdf2 => django_pbkdf2_verify(content, hash),
+    }
+}
+
+/// Length of the SHA-256 digest Django stores
+const DJANGO_PBKDF2_DIGEST_LEN: usize = 32;
+
+fn django_pbkdf2_verify(content: &str, hash: &str) -> Result<(), argon2::password_hash::Error> {
+    use argon2::password_hash::Error;
+
+    let mut parts = hash.splitn(4, '$').skip(1);
+    let (Some(iterations), Some(salt), Some(expected)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Error::PhcStringField);
+    };
+    let iterations: u32 = iterations.parse().map_err(|_| Error::PhcStringField)?;
+    let expected = BASE64.decode(expected.as_bytes()).map_err(|_| Error::PhcStringField)?;
+    // A truncated or empty digest would compare equal to a prefix of any password's digest
+    if iterations == 0 || expected.len() < DJANGO_PBKDF2_DIGEST_LEN {
+        return Err(Error::PhcStringField);
+    }
+
+    let mut actual = vec![0u8; expected.len()];
+    pbkdf2::pbkdf2_hmac::<Sha256>(content.as_bytes(), salt.as_bytes(), iterations, &mut actual);
+    if actual.iter().zip(&expected).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0 {
+        Ok(())
+    } else {
+        Err(Error::Password)
+    }
+}
+
+/// True unless the hash is Argon2id made with exactly the parameters of `argon`.
+pub fn needs_rehash(argon: &Argon2, hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return true;
+    };
+    if parsed.algorithm != argon2::Algorithm::Argon2id.ident()
+        || parsed.version != Some(argon2::Version::V0x13.into())
+    {
+        return true;
+    }
+    let Ok(stored) = argon2::Params::try_from(&parsed) else {
+        return true;
+    };
+    let current = argon.params();
+    (stored.m_cost(), stored.t_cost(), stored.p_cost())
+        != (current.m_cost(), current.t_cost(), current.p_cost())
+}
+
+/// Fast digest for high-entropy secrets (random codes, tokens) that are looked up by value.
+pub fn sha256_hex(content: impl AsRef<str>) -> String {
+    data_encoding::HEXLOWER.encode(&Sha256::digest(content.as_ref().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct horse battery staple";
+
+    fn cheap_argon(t_cost: u32) -> Argon2<'static> {
+        let params = argon2::Params::new(8, t_cost, 1, None).unwrap();
+        Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+    }
+
+    #[test]
+    fn test_argon2_with_any_parameters() {
+        let hash = argon_hash(&cheap_argon(1), PASSWORD).unwrap();
+        assert_eq!(identify(&hash), Some(HashAlgorithm::Argon2));
+        assert!(verify_any(PASSWORD, &hash).is_ok());
+        assert!(verify_any("wrong", &hash).is_err());
+    }
+
+    #[test]
+    fn test_bcrypt() {
+        // crypt_blowfish test vector
+        let hash = "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW";
+        assert_eq!(identify(hash), Some(HashAlgorithm::Bcrypt));
+        assert!(verify_any("U*U", hash).is_ok());
+        assert!(verify_any("U*U*", hash).is_err());
+    }
+
+    #[test]
+    fn test_pbkdf2_phc() {
+        let hash = "$pbkdf2-sha256$i=1000,l=32$c2FsdHlzYWx0MTIzNDU2Nw$hBfrxhlblqvPcbbQR8uQaiXsr7hTLWMuw9sRDaZIeFw";
+        assert_eq!(identify(hash), Some(HashAlgorithm::Pbkdf2));
+        assert!(verify_any(PASSWORD, hash).is_ok());
+        assert!(verify_any("wrong", hash).is_err());
+    }
+
+    #[test]
+    fn test_django_pbkdf2() {
+        let hash = "pbkdf2_sha256$1000$django-salt$efk9apInV+Xm/mo4fSLSjnrNpSQYT2dBw0rRQPzKcOc=";
+        assert_eq!(identify(hash), Some(HashAlgorithm::DjangoPbkdf2));
+        assert!(verify_any(PASSWORD, hash).is_ok());
+        assert!(verify_any("wrong", hash).is_err());
+    }
+
+    #[test]
+    fn test_django_pbkdf2_with_empty_or_short_digest_is_rejected() {
+        assert!(verify_any(PASSWORD, "pbkdf2_sha256$260000$salt$").is_err());
+        assert!(verify_any("anything", "pbkdf2_sha256$260000$salt$").is_err());
+        assert!(verify_any(PASSWORD, "pbkdf2_sha256$1000$django-salt$efk9apInV+Xm").is_err());
+        assert!(verify_any(PASSWORD, "pbkdf2_sha256$0$django-salt$efk9apInV+Xm/mo4fSLSjnrNpSQYT2dBw0rRQPzKcOc=").is_err());
+    }
+
+    #[test]
+    fn test_unknown_format_is_rejected() {
+        assert!(verify_any(PASSWORD, "5f4dcc3b5aa765d61d8327deb882cf99").is_err());
+    }
+
+    #[test]
+    fn test_needs_rehash_only_for_outdated_hashes() {
+        let current = cheap_argon(2);
+        assert!(!needs_rehash(&current, &argon_hash(&current, PASSWORD).unwrap()));
+        assert!(needs_rehash(&current, &argon_hash(&cheap_argon(1), PASSWORD).unwrap()));
+        assert!(needs_rehash(&current, "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::core::error::{AppError, AppResult};
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// RFC 7517 public key as published in the JWKS document.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq, Eq)]
+pub struct PublicJwk {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    pub n: String,
+    pub e: String,
+}
+
+impl PublicJwk {
+    fn from_public_pem(kid: &str, public_pem: &str) -> AppResult<Self> {
+        let key = RsaPublicKey::from_public_key_pem(public_pem)
+            .map_err(|err| AppError::UnknownError(anyhow::anyhow!("Invalid RSA public key {kid}: {err}")))?;
+        Ok(Self {
+            kty: "RSA".to_string(),
+            key_use: "sig".to_string(),
+            alg: "RS256".to_string(),
+            kid: kid.to_string(),
+            n: BASE64URL_NOPAD.encode(&key.n().to_bytes_be()),
+            e: BASE64URL_NOPAD.encode(&key.e().to_bytes_be()),
+        })
+    }
+}
+
+/// Signing key of one token type plus every public key still accepted for it.
+///
+/// Tokens are signed with the active key and carry its `kid`. Verification picks the
+/// key by `kid`, so tokens signed with a previous key stay valid until they expire.
+pub struct KeyRing {
+    active_kid: String,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, DecodingKey>,
+    jwks: Vec<PublicJwk>,
+}
+
+impl KeyRing {
+    /// `previous` holds `(kid, public PEM)` pairs of keys that are only verified, never used to sign.
+    pub fn from_pems(
+        active_kid: &str,
+        private_pem: &str,
+        public_pem: &str,
+        previous: &[(String, String)],
+    ) -> AppResult<Self> {
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = Vec::new();
+        for (kid, pem) in std::iter::once((active_kid, public_pem))
+            .chain(previous.iter().map(|(kid, pem)| (kid.as_str(), pem.as_str())))
+        {
+            if decoding_keys.contains_key(kid) {
+                return Err(AppError::UnknownError(anyhow::anyhow!("Duplicate key id {kid}")));
+            }
+            decoding_keys.insert(kid.to_string(), DecodingKey::from_rsa_pem(pem.as_bytes())?);
+            jwks.push(PublicJwk::from_public_pem(kid, pem)?);
+        }
+
+        Ok(Self {
+            active_kid: active_kid.to_string(),
+            encoding_key: EncodingKey::from_rsa_pem(private_pem.as_bytes())?,
+            decoding_keys,
+            jwks,
+        })
+    }
+
+    pub fn active_kid(&self) -> &str {
+        &self.active_kid
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    /// Tokens issued before `kid` was stamped have none and are checked with the active key.
+    pub fn decoding_key(&self, kid: Option<&str>) -> Option<&DecodingKey> {
+        self.decoding_keys.get(kid.unwrap_or(&self.active_kid))
+    }
+
+    /// Active key first, then the previous ones.
+    pub fn jwks(&self) -> &[PublicJwk] {
+        &self.jwks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::claim::UserClaims;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn read_pem(name: &str) -> String {
+        std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("static/secret_key").join(name),
+        )
+        .unwrap()
+    }
+
+    fn old_ring() -> KeyRing {
+        KeyRing::from_pems(
+            "old",
+            &read_pem("private_access_rsa_key.pem"),
+            &read_pem("public_access_rsa_key.pem"),
+            &[],
+        )
+        .unwrap()
+    }
+
+    fn rotated_ring() -> KeyRing {
+        KeyRing::from_pems(
+            "new",
+            &read_pem("private_refresh_rsa_key.pem"),
+            &read_pem("public_refresh_rsa_key.pem"),
+            &[("old".to_string(), read_pem("public_access_rsa_key.pem"))],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_signed_with_previous_key_still_decodes() {
+        let claims = UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4());
+        let token = claims.encode(&old_ring()).unwrap();
+
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("old"));
+        assert_eq!(UserClaims::decode(&token, &rotated_ring()).unwrap().claims, claims);
+    }
+
+    #[test]
+    fn test_unknown_kid_is_rejected() {
+        let claims = UserClaims::new(Duration::from_secs(60), &1, &Uuid::new_v4());
+        let token = claims.encode(&rotated_ring()).unwrap();
+        assert!(UserClaims::decode(&token, &old_ring()).is_err());
+    }
+
+    #[test]
+    fn test_jwks_lists_active_key_first() {
+        let kids: Vec<_> = rotated_ring().jwks().iter().map(|jwk| jwk.kid.clone()).collect();
+        assert_eq!(kids, vec!["new", "old"]);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod assertion;
+pub mod breached_password;
 pub mod claim;
 pub mod constant;
 pub mod database;
@@ -9,8 +10,10 @@ pub mod file;
 pub mod filter_and_pagination;
 pub mod fp_utils;
 pub mod hash;
+pub mod key_ring;
 pub mod password;
 pub mod path;
+pub mod pkce;
 pub mod random;
 pub mod redis_cache_helper;
 pub mod result;
@@ -19,4 +22,5 @@ pub mod string;
 pub mod task;
 pub mod test;
 pub mod timecode;
+pub mod totp;
 pub mod validate;
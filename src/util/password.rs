@@ -1,14 +1,16 @@
 use super::hash;
 use crate::core::error::{AppError, AppResult};
+use crate::util::constant::PASSWORD_HASHER;
 
 pub async fn hash(password: String) -> AppResult<String> {
-    let jh = tokio::task::spawn_blocking(move || hash::argon_hash(password));
+    let jh = tokio::task::spawn_blocking(move || hash::argon_hash(&PASSWORD_HASHER, password));
     let password = jh.await??;
     Ok(password)
 }
 
+/// Accepts argon2, bcrypt and PBKDF2 hashes, including ones imported from other systems.
 pub async fn verify(password: String, hashed_pass: String) -> AppResult {
-    let jh = tokio::task::spawn_blocking(move || hash::argon_verify(password, hashed_pass));
+    let jh = tokio::task::spawn_blocking(move || hash::verify_any(password, hashed_pass));
     if let Err(err) = jh.await? {
         log::debug!("The password is not correct: {err}");
         Err(AppError::BadRequestError("The password is not correct!".to_string()))
@@ -16,3 +18,8 @@ pub async fn verify(password: String, hashed_pass: String) -> AppResult {
         Ok(())
     }
 }
+
+/// Whether a hash that just verified should be replaced with one made by [`hash`].
+pub fn needs_rehash(hashed_pass: &str) -> bool {
+    hash::needs_rehash(&PASSWORD_HASHER, hashed_pass)
+}
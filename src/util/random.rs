@@ -8,3 +8,8 @@ pub fn generate_random_string(len: usize) -> String {
 pub fn generate_random_string_with_prefix(prefix: &str) -> String {
     format!("{prefix}_{}", generate_random_string(10))
 }
+
+pub fn generate_random_code(len: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect()
+}
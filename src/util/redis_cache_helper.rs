@@ -5,6 +5,7 @@
 //! cache invalidation, and cache key generation.
 
 use crate::core::error::{AppError, AppResult};
+use crate::core::tenant;
 use crate::infrastructure::third_party::redis::lib::RedisConnectionPool;
 use chrono::NaiveDateTime;
 use log::{debug, warn};
@@ -106,7 +107,8 @@ where
 
             if let Some(v) = value_clone {
                 let redis_clone = redis.clone("");
-                tokio::spawn(async move {
+                // The write runs outside the request, keep it under the request's tenant
+                tokio::spawn(tenant::scope(tenant::current_tenant(), async move {
                     let cache_key_redis = cache_key_for_cache.clone();
                     if let Err(e) = redis_clone
                         .serialize_and_set_key_with_expiry(&cache_key_for_cache.into(), &v, ttl)
@@ -114,7 +116,7 @@ where
                     {
                         warn!("Failed to cache data for key {}: {:?}", cache_key_redis, e);
                     }
-                });
+                }));
             }
 
             Ok(value)
@@ -305,7 +307,7 @@ where
         let hash_key_owned = hash_key.to_string();
         let fetched_clone = fetched.clone();
 
-        tokio::spawn(async move {
+        tokio::spawn(tenant::scope(tenant::current_tenant(), async move {
             for (id, entity) in fetched_clone {
                 let field = id.to_string();
                 if let Err(e) = redis_clone
@@ -320,7 +322,7 @@ where
                     warn!("Failed to cache entity {}:{}: {:?}", hash_key_owned, id, e);
                 }
             }
-        });
+        }));
 
         results.extend(fetched);
     }
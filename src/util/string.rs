@@ -8,3 +8,19 @@ pub fn increment_mixed_string(s: &str) -> Option<String> {
 
     Some(format!("{}{}", prefix, new_number))
 }
+
+/// Emails are compared and stored trimmed and lowercased.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emails_differing_in_case_or_whitespace_normalize_alike() {
+        assert_eq!(normalize_email("  Bob@X.com "), "bob@x.com");
+        assert_eq!(normalize_email("bob@x.com"), normalize_email("BOB@x.COM"));
+    }
+}
@@ -0,0 +1,94 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+/// RFC 6238 defaults understood by every authenticator app
+pub const TOTP_DIGITS: u32 = 6;
+pub const TOTP_PERIOD_SECS: u64 = 30;
+/// How many periods before/after the current one are still accepted (clock drift)
+pub const TOTP_ALLOWED_SKEW: u64 = 1;
+const SECRET_LEN: usize = 20;
+
+/// Generates a random 160-bit secret, base32 encoded without padding.
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LEN];
+    rand::thread_rng().fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// Builds the `otpauth://` URI that authenticator apps import (usually through a QR code).
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = urlencoding(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD_SECS}",
+        urlencoding(account)
+    )
+}
+
+/// RFC 4226 HOTP value of `key` for `counter`, truncated to `digits` digits.
+pub fn hotp(key: &[u8], counter: u64, digits: u32) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = ((digest[offset] as u32 & 0x7f) << 24)
+        | ((digest[offset + 1] as u32) << 16)
+        | ((digest[offset + 2] as u32) << 8)
+        | (digest[offset + 3] as u32);
+    format!("{:0width$}", binary % 10u32.pow(digits), width = digits as usize)
+}
+
+/// Checks `code` against the base32 `secret` at unix time `now`, tolerating clock drift.
+/// Returns the matched time step so callers can refuse replays of the same code.
+pub fn verify(secret: &str, code: &str, now: u64) -> Option<u64> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let current = now / TOTP_PERIOD_SECS;
+    (current.saturating_sub(TOTP_ALLOWED_SKEW)..=current + TOTP_ALLOWED_SKEW)
+        .find(|step| constant_time_eq(hotp(&key, *step, TOTP_DIGITS).as_bytes(), code.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn urlencoding(value: &str) -> String {
+    serde_urlencoded::to_string([("", value)])
+        .map(|encoded| encoded.trim_start_matches('=').replace('+', "%20"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA1 variant
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        assert_eq!(hotp(RFC_KEY, 59 / 30, 8), "94287082");
+        assert_eq!(hotp(RFC_KEY, 1111111109 / 30, 8), "07081804");
+        assert_eq!(hotp(RFC_KEY, 1234567890 / 30, 8), "89005924");
+        assert_eq!(hotp(RFC_KEY, 20000000000 / 30, 8), "65353130");
+    }
+
+    #[test]
+    fn test_verify_accepts_adjacent_step() {
+        let secret = BASE32_NOPAD.encode(RFC_KEY);
+        let code = hotp(RFC_KEY, 1111111109 / 30, TOTP_DIGITS);
+        assert_eq!(verify(&secret, &code, 1111111109), Some(1111111109 / 30));
+        assert_eq!(verify(&secret, &code, 1111111109 + 30), Some(1111111109 / 30));
+        assert_eq!(verify(&secret, &code, 1111111109 + 90), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("June 18", "john@example.com", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/June%2018:john%40example.com?secret=ABC&issuer=June%2018&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}